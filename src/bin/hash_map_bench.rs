@@ -0,0 +1,90 @@
+//! Сравнение хэш-таблиц из `lr_4` со стандартной `HashMap`.
+//!
+//! Запуск: `cargo run --release --bin hash_map_bench -- [количество ключей]`.
+
+#[allow(dead_code, unused_imports)]
+#[path = "../common/mod.rs"]
+mod common;
+
+#[allow(dead_code, unused_imports)]
+#[path = "../lr_1/mod.rs"]
+mod lr_1;
+
+#[allow(dead_code, unused_imports)]
+#[path = "../lr_4/mod.rs"]
+mod lr_4;
+
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+/// Перемешанные ключи, чтобы порядок вставки не совпадал с порядком хэшей.
+fn shuffled_keys(count: u64, seed: u64) -> Vec<u64> {
+    let mut state: u64 = seed;
+    (0..count)
+        .map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        })
+        .collect()
+}
+
+fn time<F: FnOnce()>(operation: F) -> Duration {
+    let start: Instant = Instant::now();
+    operation();
+    start.elapsed()
+}
+
+/// Измеряет вставку, успешный и неуспешный поиск и удаление. У всех
+/// таблиц одинаковые методы, так что одно тело подходит для каждой.
+macro_rules! run {
+    ($name:expr, $map:expr, $keys:expr, $missing:expr) => {{
+        let (keys, missing): (&[u64], &[u64]) = ($keys, $missing);
+        let mut map = $map;
+        let insert: Duration = time(|| {
+            for key in keys {
+                map.insert(*key, *key);
+            }
+        });
+        let hit: Duration = time(|| {
+            for key in keys {
+                black_box(map.get(key));
+            }
+        });
+        let miss: Duration = time(|| {
+            for key in missing {
+                black_box(map.get(key));
+            }
+        });
+        let remove: Duration = time(|| {
+            for key in keys {
+                map.remove(key);
+            }
+        });
+        let per_key = |duration: Duration| duration.as_nanos() as f64 / keys.len() as f64;
+        println!(
+            "{:<28}{:>12.1}{:>12.1}{:>12.1}{:>12.1}",
+            $name, per_key(insert), per_key(hit), per_key(miss), per_key(remove),
+        );
+    }};
+}
+
+fn main() {
+    let count: u64 = std::env::args().nth(1).and_then(|arg| arg.parse().ok()).unwrap_or(1_000_000);
+    let keys: Vec<u64> = shuffled_keys(count, 0x9E37_79B9_7F4A_7C15);
+    let missing: Vec<u64> = shuffled_keys(count, 0x2545_F491_4F6C_DD1D);
+    println!("{} ключей, нс на операцию", count);
+    println!("{:<28}{:>12}{:>12}{:>12}{:>12}", "", "insert", "get hit", "get miss", "remove");
+    run!("lr_4::HashMap", lr_4::HashMap::new(), &keys, &missing);
+    run!("lr_4::HashMap (capacity)", lr_4::HashMap::with_capacity(keys.len()), &keys, &missing);
+    let mut incremental: lr_4::HashMap<u64, u64> = lr_4::HashMap::new();
+    incremental.set_incremental_rehash(true);
+    run!("lr_4::HashMap (incremental)", incremental, &keys, &missing);
+    run!("lr_4::HashMap (Fx)", lr_4::HashMap::with_hasher(lr_4::FxBuildHasher::default()), &keys, &missing);
+    run!("lr_4::HashMap (FNV)", lr_4::HashMap::with_hasher(lr_4::FnvBuildHasher::default()), &keys, &missing);
+    run!("lr_4::RobinHoodMap", lr_4::RobinHoodMap::new(), &keys, &missing);
+    run!("lr_4::RobinHoodMap (Fx)", lr_4::RobinHoodMap::with_hasher(lr_4::FxBuildHasher::default()), &keys, &missing);
+    run!("lr_4::CuckooHashMap", lr_4::CuckooHashMap::new(), &keys, &missing);
+    run!("std HashMap", std::collections::HashMap::new(), &keys, &missing);
+}
@@ -0,0 +1,315 @@
+use std::rc::Rc;
+use std::fmt::Debug;
+use std::cell::RefCell;
+use std::cmp::{max, Ordering};
+
+type RcRefcellNode<N> = Rc<RefCell<N>>;
+
+/// A node with at most two children.
+pub trait BinaryNode<T: Ord + Copy + Debug>: Sized {
+    fn left_child(&self) -> &Option<Rc<RefCell<Self>>>;
+
+    fn right_child(&self) -> &Option<Rc<RefCell<Self>>>;
+
+    fn node_key(&self) -> T;
+}
+
+/// Folds and structural metrics of a tree whose nodes have at most two
+/// children, such as BSTree and RBTree.
+///
+/// A BTree node holds many keys and children, so BTree does not implement
+/// this trait.
+pub trait BinaryTree<T: Ord + Copy + Debug> {
+    type Node: BinaryNode<T>;
+
+    fn root_node(&self) -> &Option<Rc<RefCell<Self::Node>>>;
+
+    /// Folds the tree bottom-up in a single pass.
+    ///
+    /// `combine` receives the key of a node and the results of its left and
+    /// right subtrees, where a missing child contributes `empty`. Several
+    /// aggregates can be computed at once by folding into a tuple.
+    ///
+    /// The walk uses an explicit stack, so degenerate trees of any depth
+    /// are folded without deep recursion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::binary_tree::BinaryTree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in 1..=7 {
+    ///     tree.insert(i);
+    /// }
+    ///
+    /// // The sum of all keys and the number of nodes, in one pass.
+    /// let (sum, nodes) = tree.fold((0, 0), |key, left, right| {
+    ///     (left.0 + right.0 + key, left.1 + right.1 + 1)
+    /// });
+    /// assert_eq!((sum, nodes), (28, 7));
+    /// ```
+    fn fold<A, F>(&self, empty: A, mut combine: F) -> A
+    where
+        A: Clone,
+        F: FnMut(T, A, A) -> A,
+    {
+        self.fold_with_context((), |_, _| (), empty, |key, _, left, right| combine(key, left, right))
+    }
+
+    /// Folds the tree in a single pass that carries a context down from the
+    /// root and results up from the leaves.
+    ///
+    /// The context of the root is `descend(&root_context, root_key)`, and
+    /// the context of every other node is `descend(&parent_context, key)`.
+    /// `combine` then receives the key of a node, its context and the
+    /// results of its subtrees, where a missing child contributes `empty`.
+    ///
+    /// The walk uses an explicit stack, so degenerate trees of any depth
+    /// are folded without deep recursion.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::binary_tree::BinaryTree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in 1..=7 {
+    ///     tree.insert(i);
+    /// }
+    ///
+    /// // Root-to-leaf path sums: the context is the sum down to the node.
+    /// let sums = tree.fold_with_context(0, |sum, key| sum + key, Vec::new(), |_, sum, left, right| {
+    ///     match (left.is_empty(), right.is_empty()) {
+    ///         (true, true) => vec![*sum],
+    ///         _ => [left, right].concat(),
+    ///     }
+    /// });
+    /// assert_eq!(sums, vec![3, 9, 17, 19]);
+    /// ```
+    fn fold_with_context<C, A, D, F>(&self, root_context: C, mut descend: D, empty: A, mut combine: F) -> A
+    where
+        A: Clone,
+        D: FnMut(&C, T) -> C,
+        F: FnMut(T, &C, A, A) -> A,
+    {
+        let root: &Rc<RefCell<Self::Node>> = match self.root_node() {
+            None => return empty,
+            Some(root) => root,
+        };
+        // Each node is visited twice: on the way down its children are
+        // scheduled, on the way up their results are combined.
+        let mut stack: Vec<(RcRefcellNode<Self::Node>, C, bool)> = Vec::new();
+        let mut results: Vec<A> = Vec::new();
+        let context: C = descend(&root_context, root.borrow().node_key());
+        stack.push((Rc::clone(root), context, false));
+        while let Some((node, context, expanded)) = stack.pop() {
+            let (key, left, right) = {
+                let node = node.borrow();
+                (node.node_key(), node.left_child().clone(), node.right_child().clone())
+            };
+            if expanded {
+                let right: A = match right {
+                    None => empty.clone(),
+                    Some(_) => results.pop().unwrap(),
+                };
+                let left: A = match left {
+                    None => empty.clone(),
+                    Some(_) => results.pop().unwrap(),
+                };
+                results.push(combine(key, &context, left, right));
+                continue;
+            }
+            let children: Vec<(RcRefcellNode<Self::Node>, C)> = [right, left]
+                .into_iter()
+                .flatten()
+                .map(|child| {
+                    let child_context: C = descend(&context, child.borrow().node_key());
+                    (child, child_context)
+                })
+                .collect();
+            stack.push((node, context, true));
+            for (child, child_context) in children {
+                stack.push((child, child_context, false));
+            }
+        }
+        results.pop().unwrap()
+    }
+
+    /// The keys on the way from the root down to `key`, both included.
+    ///
+    /// Returns `None` if the tree does not contain `key`.
+    fn _path_from_root(&self, key: T) -> Option<Vec<T>> {
+        let mut path: Vec<T> = Vec::new();
+        let mut current: Option<RcRefcellNode<Self::Node>> = self.root_node().clone();
+        while let Some(node) = current {
+            let node = node.borrow();
+            path.push(node.node_key());
+            current = match node.node_key().cmp(&key) {
+                Ordering::Greater => node.left_child().clone(),
+                Ordering::Less => node.right_child().clone(),
+                Ordering::Equal => return Some(path),
+            };
+        }
+        None
+    }
+
+    /// The depth of the node holding `key`, the root being at depth 0.
+    ///
+    /// Returns `None` if the tree does not contain `key`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::binary_tree::BinaryTree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in 1..=7 {
+    ///     tree.insert(i);
+    /// }
+    /// assert_eq!(tree.depth(2), Some(0));
+    /// assert_eq!(tree.depth(7), Some(3));
+    /// assert_eq!(tree.depth(8), None);
+    /// ```
+    fn depth(&self, key: T) -> Option<u32> {
+        self._path_from_root(key).map(|path| path.len() as u32 - 1)
+    }
+
+    /// The lowest common ancestor of `a` and `b`: the deepest node that has
+    /// both of them in its subtree. A node counts as its own ancestor.
+    ///
+    /// Returns `None` if either key is missing from the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::binary_tree::BinaryTree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in 1..=7 {
+    ///     tree.insert(i);
+    /// }
+    /// assert_eq!(tree.lca(5, 7), Some(6));
+    /// assert_eq!(tree.lca(1, 7), Some(2));
+    /// assert_eq!(tree.lca(4, 5), Some(4));
+    /// assert_eq!(tree.lca(1, 8), None);
+    /// ```
+    fn lca(&self, a: T, b: T) -> Option<T> {
+        let path_a: Vec<T> = self._path_from_root(a)?;
+        let path_b: Vec<T> = self._path_from_root(b)?;
+        path_a.iter().zip(path_b.iter()).take_while(|(x, y)| x == y).last().map(|(x, _)| *x)
+    }
+
+    /// The keys along the route from `a` to `b`, both included: up from `a`
+    /// to the lowest common ancestor and down again to `b`.
+    ///
+    /// Returns `None` if either key is missing from the tree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::binary_tree::BinaryTree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in 1..=7 {
+    ///     tree.insert(i);
+    /// }
+    /// assert_eq!(tree.path(1, 5), Some(vec![1, 2, 4, 6, 5]));
+    /// assert_eq!(tree.path(3, 3), Some(vec![3]));
+    /// ```
+    fn path(&self, a: T, b: T) -> Option<Vec<T>> {
+        let path_a: Vec<T> = self._path_from_root(a)?;
+        let path_b: Vec<T> = self._path_from_root(b)?;
+        let common: usize = path_a.iter().zip(path_b.iter()).take_while(|(x, y)| x == y).count();
+        let mut path: Vec<T> = path_a[common - 1..].iter().rev().copied().collect();
+        path.extend_from_slice(&path_b[common..]);
+        Some(path)
+    }
+
+    /// All keys at depth `k`, from left to right. The root is at depth 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::binary_tree::BinaryTree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in 1..=7 {
+    ///     tree.insert(i);
+    /// }
+    /// assert_eq!(tree.level(0), vec![2]);
+    /// assert_eq!(tree.level(2), vec![3, 6]);
+    /// assert_eq!(tree.level(4), vec![]);
+    /// ```
+    fn level(&self, k: u32) -> Vec<T> {
+        let mut nodes: Vec<RcRefcellNode<Self::Node>> = self.root_node().iter().cloned().collect();
+        for _ in 0..k {
+            if nodes.is_empty() {
+                break;
+            }
+            nodes = nodes
+                .iter()
+                .flat_map(|node| {
+                    let node = node.borrow();
+                    [node.left_child().clone(), node.right_child().clone()]
+                })
+                .flatten()
+                .collect();
+        }
+        nodes.iter().map(|node| node.borrow().node_key()).collect()
+    }
+
+    /// The number of edges on the longest path between any two nodes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::binary_tree::BinaryTree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// assert_eq!(tree.diameter(), 0);
+    /// for i in 1..=7 {
+    ///     tree.insert(i);
+    /// }
+    /// // The path 1, 2, 4, 6, 7.
+    /// assert_eq!(tree.diameter(), 4);
+    /// ```
+    fn diameter(&self) -> u32 {
+        // (height, diameter) of every subtree
+        let (_, diameter): (u32, u32) = self.fold((0, 0), |_, left, right| {
+            (max(left.0, right.0) + 1, max(max(left.1, right.1), left.0 + right.0))
+        });
+        diameter
+    }
+
+    /// Checks whether the heights of the two subtrees of every node differ
+    /// by at most one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::binary_tree::BinaryTree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in 1..=7 {
+    ///     tree.insert(i);
+    /// }
+    /// // The subtrees of 2 are 1 alone and 4, 3, 6, 5, 7 of height 3.
+    /// assert!(!tree.is_balanced());
+    /// ```
+    fn is_balanced(&self) -> bool {
+        // (height, balanced) of every subtree
+        let (_, balanced): (u32, bool) = self.fold((0, true), |_, left, right| {
+            (max(left.0, right.0) + 1, left.1 && right.1 && left.0.abs_diff(right.0) <= 1)
+        });
+        balanced
+    }
+}
@@ -0,0 +1,526 @@
+use std::collections::{BTreeMap, VecDeque};
+use std::fmt::{self, Display};
+use std::str::FromStr;
+use crate::common::FixedSize;
+
+const MAGIC: &[u8; 4] = b"TREE";
+const VERSION: u8 = 2;
+const COLOURED: u8 = 1;
+const MULTISET: u8 = 2;
+
+const HAS_LEFT: u8 = 1;
+const HAS_RIGHT: u8 = 2;
+const RED: u8 = 4;
+
+/// Why a tree could not be loaded.
+#[derive(Clone, Debug, PartialEq)]
+pub enum DecodeError {
+    /// A token is neither a key nor a null marker.
+    BadToken(String),
+    /// The input ended while nodes were still expected.
+    UnexpectedEnd,
+    /// The input goes on after the tree is complete.
+    TrailingData,
+    /// The binary header is missing or of another version.
+    BadHeader,
+    /// The keys do not form a search tree, or a key repeats.
+    OutOfOrder,
+    /// A red-black tree was loaded from a shape without colours.
+    MissingColours,
+    /// The root of a red-black tree is red.
+    RedRoot,
+    /// A red node has a red child.
+    DoubleRed,
+    /// Two paths from a node to its leaves pass different numbers of black nodes.
+    BlackHeightMismatch,
+    /// A key is stored zero times, or more than once outside a multiset.
+    BadCount,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::BadToken(token) => write!(f, "bad token {:?}", token),
+            DecodeError::UnexpectedEnd => write!(f, "unexpected end of input"),
+            DecodeError::TrailingData => write!(f, "trailing data after the tree"),
+            DecodeError::BadHeader => write!(f, "bad header"),
+            DecodeError::OutOfOrder => write!(f, "keys are out of order"),
+            DecodeError::MissingColours => write!(f, "the nodes carry no colours"),
+            DecodeError::RedRoot => write!(f, "the root is red"),
+            DecodeError::DoubleRed => write!(f, "a red node has a red child"),
+            DecodeError::BlackHeightMismatch => write!(f, "black heights differ"),
+            DecodeError::BadCount => write!(f, "a key is stored a wrong number of times"),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+/// A node of a `Shape`; children are indices into the shape.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ShapeNode<T> {
+    pub key: T,
+    /// How many times the key is stored; always 1 outside multiset shapes.
+    pub count: usize,
+    pub red: bool,
+    pub left: Option<usize>,
+    pub right: Option<usize>,
+}
+
+/// The shape of a binary tree, stored flat so that it can be encoded and
+/// decoded without recursion. The root, if any, is at index 0.
+///
+/// `coloured` shapes carry red-black colours, which every format keeps.
+///
+/// Text formats, for the tree 2 (1, 4 (3, 6)):
+///
+/// * preorder with null markers: `2,1,#,#,4,3,#,#,6,#,#`
+/// * level order: `[2,1,4,null,null,3,6]`
+///
+/// In a coloured shape every key carries its colour: `2:B`, `4:R`.
+///
+/// `multiset` shapes carry the number of times every key is stored. In
+/// text it follows the key: `2*3`, or `2*3:B` with a colour. A text shape
+/// is a multiset one when any of its keys carries a count.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Shape<T> {
+    pub nodes: Vec<ShapeNode<T>>,
+    pub coloured: bool,
+    pub multiset: bool,
+}
+
+/// What changed between two trees, see `Shape::diff`. Every list is sorted.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TreeDiff<T> {
+    /// Keys found only in the newer tree.
+    pub inserted: Vec<T>,
+    /// Keys found only in the older tree.
+    pub removed: Vec<T>,
+    /// Keys found in both trees whose node has another left or right child,
+    /// another colour or another count: the roots of the subtrees that were
+    /// rearranged, and the keys stored another number of times.
+    pub changed: Vec<T>,
+}
+
+impl<T> TreeDiff<T> {
+    pub fn is_empty(&self) -> bool {
+        self.inserted.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl<T: Ord + Copy> Shape<T> {
+    pub fn new(coloured: bool) -> Self {
+        Shape {
+            nodes: Vec::new(),
+            coloured,
+            multiset: false,
+        }
+    }
+
+    /// Adds a node and returns its index. `parent` is the index of the
+    /// parent and whether the node is its left child, or `None` for the root.
+    pub fn add_node(&mut self, parent: Option<(usize, bool)>, key: T, count: usize, red: bool) -> usize {
+        let index: usize = self.nodes.len();
+        self.nodes.push(ShapeNode { key, count, red, left: None, right: None });
+        match parent {
+            None => {}
+            Some((parent, true)) => self.nodes[parent].left = Some(index),
+            Some((parent, false)) => self.nodes[parent].right = Some(index),
+        }
+        index
+    }
+
+    /// Node indices in preorder.
+    pub fn preorder(&self) -> Vec<usize> {
+        let mut order: Vec<usize> = Vec::with_capacity(self.nodes.len());
+        let mut stack: Vec<usize> = match self.nodes.is_empty() {
+            true => Vec::new(),
+            false => vec![0],
+        };
+        while let Some(index) = stack.pop() {
+            order.push(index);
+            stack.extend(self.nodes[index].right);
+            stack.extend(self.nodes[index].left);
+        }
+        order
+    }
+
+    /// Checks that the keys are strictly ordered as in a search tree, and
+    /// that every key is stored at least once, and only once outside a
+    /// multiset.
+    pub fn check_order(&self) -> Result<(), DecodeError> {
+        if self.nodes.iter().any(|node| node.count == 0 || (!self.multiset && node.count != 1)) {
+            return Err(DecodeError::BadCount);
+        }
+        let mut stack: Vec<(usize, Option<T>, Option<T>)> = match self.nodes.is_empty() {
+            true => Vec::new(),
+            false => vec![(0, None, None)],
+        };
+        while let Some((index, lower, upper)) = stack.pop() {
+            let node: &ShapeNode<T> = &self.nodes[index];
+            if lower.is_some_and(|lower| node.key <= lower) || upper.is_some_and(|upper| node.key >= upper) {
+                return Err(DecodeError::OutOfOrder);
+            }
+            if let Some(left) = node.left {
+                stack.push((left, lower, Some(node.key)));
+            }
+            if let Some(right) = node.right {
+                stack.push((right, Some(node.key), upper));
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the red-black invariants: a black root, no red node with a
+    /// red child, and the same number of black nodes on every path.
+    pub fn check_red_black(&self) -> Result<(), DecodeError> {
+        if self.nodes.first().is_some_and(|root| root.red) {
+            return Err(DecodeError::RedRoot);
+        }
+        let mut black_height: Vec<u32> = vec![0; self.nodes.len()];
+        // Reversed preorder visits children before their parent.
+        for index in self.preorder().into_iter().rev() {
+            let node: &ShapeNode<T> = &self.nodes[index];
+            let mut heights: [u32; 2] = [1, 1];
+            for (side, child) in [node.left, node.right].into_iter().enumerate() {
+                if let Some(child) = child {
+                    if node.red && self.nodes[child].red {
+                        return Err(DecodeError::DoubleRed);
+                    }
+                    heights[side] = black_height[child];
+                }
+            }
+            if heights[0] != heights[1] {
+                return Err(DecodeError::BlackHeightMismatch);
+            }
+            black_height[index] = heights[0] + if node.red { 0 } else { 1 };
+        }
+        Ok(())
+    }
+
+    /// The keys in order.
+    pub fn keys(&self) -> Vec<T> {
+        let mut keys: Vec<T> = Vec::with_capacity(self.nodes.len());
+        let mut stack: Vec<usize> = Vec::new();
+        let mut current: Option<usize> = self.nodes.first().map(|_| 0);
+        loop {
+            while let Some(index) = current {
+                stack.push(index);
+                current = self.nodes[index].left;
+            }
+            match stack.pop() {
+                None => break,
+                Some(index) => {
+                    keys.push(self.nodes[index].key);
+                    current = self.nodes[index].right;
+                }
+            }
+        }
+        keys
+    }
+
+    /// Walks both shapes in step from the roots and checks every pair of
+    /// nodes met on the way with `same`.
+    fn _walk_in_step<F: Fn(&ShapeNode<T>, &ShapeNode<T>) -> bool>(&self, other: &Shape<T>, same: F) -> bool {
+        if self.nodes.len() != other.nodes.len() {
+            return false;
+        }
+        let mut stack: Vec<(usize, usize)> = match self.nodes.is_empty() {
+            true => Vec::new(),
+            false => vec![(0, 0)],
+        };
+        while let Some((index, other_index)) = stack.pop() {
+            let (node, other_node) = (&self.nodes[index], &other.nodes[other_index]);
+            if !same(node, other_node) {
+                return false;
+            }
+            for (child, other_child) in [(node.left, other_node.left), (node.right, other_node.right)] {
+                match (child, other_child) {
+                    (None, None) => {}
+                    (Some(child), Some(other_child)) => stack.push((child, other_child)),
+                    _ => return false,
+                }
+            }
+        }
+        true
+    }
+
+    /// Checks whether both trees have the same branching, whatever their
+    /// keys and colours.
+    pub fn same_shape(&self, other: &Shape<T>) -> bool {
+        self._walk_in_step(other, |_, _| true)
+    }
+
+    /// Checks whether both trees have the same branching and the same keys
+    /// in the same places, stored as many times each. Colours are compared
+    /// when both shapes carry them.
+    pub fn structural_eq(&self, other: &Shape<T>) -> bool {
+        let colours: bool = self.coloured && other.coloured;
+        self._walk_in_step(other, |node, other_node| {
+            node.key == other_node.key
+                && node.count == other_node.count
+                && (!colours || node.red == other_node.red)
+        })
+    }
+
+    /// The children, colour and count of every node, by key.
+    fn _links(&self) -> BTreeMap<T, (Option<T>, Option<T>, bool, usize)> {
+        self.nodes
+            .iter()
+            .map(|node| {
+                let key = |child: Option<usize>| child.map(|child| self.nodes[child].key);
+                (node.key, (key(node.left), key(node.right), node.red, node.count))
+            })
+            .collect()
+    }
+
+    /// What changed from this tree to `other`.
+    pub fn diff(&self, other: &Shape<T>) -> TreeDiff<T> {
+        let colours: bool = self.coloured && other.coloured;
+        let (links, other_links) = (self._links(), other._links());
+        let mut diff: TreeDiff<T> = TreeDiff {
+            inserted: other_links.keys().filter(|key| !links.contains_key(key)).copied().collect(),
+            removed: Vec::new(),
+            changed: Vec::new(),
+        };
+        for (key, (left, right, red, count)) in links {
+            match other_links.get(&key) {
+                None => diff.removed.push(key),
+                Some((other_left, other_right, other_red, other_count)) => {
+                    let moved: bool = left != *other_left || right != *other_right;
+                    if moved || (colours && red != *other_red) || count != *other_count {
+                        diff.changed.push(key);
+                    }
+                }
+            }
+        }
+        diff
+    }
+}
+
+impl<T: Ord + Copy + Display + FromStr> Shape<T> {
+    fn _token(&self, index: usize) -> String {
+        let node: &ShapeNode<T> = &self.nodes[index];
+        let key: String = match self.multiset {
+            true => format!("{}*{}", node.key, node.count),
+            false => node.key.to_string(),
+        };
+        match (self.coloured, node.red) {
+            (false, _) => key,
+            (true, true) => format!("{}:R", key),
+            (true, false) => format!("{}:B", key),
+        }
+    }
+
+    /// Parses a key token into the key, its count if the token has one, and
+    /// its colour, or returns `None` for a null marker.
+    fn _parse_token(token: &str, coloured: bool, null: &str) -> Result<Option<(T, Option<usize>, bool)>, DecodeError> {
+        if token == null {
+            return Ok(None);
+        }
+        let bad_token = || DecodeError::BadToken(token.to_string());
+        let (key, red): (&str, bool) = match coloured {
+            false => (token, false),
+            true => match token.rsplit_once(':') {
+                Some((key, "R")) => (key, true),
+                Some((key, "B")) => (key, false),
+                _ => return Err(bad_token()),
+            },
+        };
+        let (key, count): (&str, Option<usize>) = match key.rsplit_once('*') {
+            None => (key, None),
+            Some((key, count)) => (key, Some(count.parse().map_err(|_| bad_token())?)),
+        };
+        let key: T = key.parse().map_err(|_| bad_token())?;
+        Ok(Some((key, count, red)))
+    }
+
+    /// Adds a parsed node; a count makes the shape a multiset one.
+    fn _add_parsed(&mut self, parent: Option<(usize, bool)>, (key, count, red): (T, Option<usize>, bool)) -> usize {
+        self.multiset |= count.is_some();
+        self.add_node(parent, key, count.unwrap_or(1), red)
+    }
+
+    /// Preorder with `#` for every missing child.
+    pub fn to_preorder_text(&self) -> String {
+        let mut tokens: Vec<String> = Vec::new();
+        let mut stack: Vec<Option<usize>> = vec![self.nodes.first().map(|_| 0)];
+        while let Some(entry) = stack.pop() {
+            match entry {
+                None => tokens.push("#".to_string()),
+                Some(index) => {
+                    tokens.push(self._token(index));
+                    stack.push(self.nodes[index].right);
+                    stack.push(self.nodes[index].left);
+                }
+            }
+        }
+        tokens.join(",")
+    }
+
+    pub fn from_preorder_text(text: &str, coloured: bool) -> Result<Self, DecodeError> {
+        let mut shape: Shape<T> = Shape::new(coloured);
+        // Places still waiting for a node or a null marker.
+        let mut slots: Vec<Option<(usize, bool)>> = vec![None];
+        for token in text.split(',').map(str::trim) {
+            let slot: Option<(usize, bool)> = slots.pop().ok_or(DecodeError::TrailingData)?;
+            if let Some(node) = Self::_parse_token(token, coloured, "#")? {
+                let index: usize = shape._add_parsed(slot, node);
+                slots.push(Some((index, false)));
+                slots.push(Some((index, true)));
+            }
+        }
+        match slots.is_empty() {
+            true => Ok(shape),
+            false => Err(DecodeError::UnexpectedEnd),
+        }
+    }
+
+    /// Level order in the LeetCode style: `null` marks a missing child and
+    /// trailing nulls are left out.
+    pub fn to_level_order_text(&self) -> String {
+        let mut tokens: Vec<String> = Vec::new();
+        let mut queue: VecDeque<Option<usize>> = VecDeque::new();
+        if !self.nodes.is_empty() {
+            queue.push_back(Some(0));
+        }
+        while let Some(entry) = queue.pop_front() {
+            match entry {
+                None => tokens.push("null".to_string()),
+                Some(index) => {
+                    tokens.push(self._token(index));
+                    queue.push_back(self.nodes[index].left);
+                    queue.push_back(self.nodes[index].right);
+                }
+            }
+        }
+        while tokens.last().is_some_and(|token| token == "null") {
+            tokens.pop();
+        }
+        format!("[{}]", tokens.join(","))
+    }
+
+    pub fn from_level_order_text(text: &str, coloured: bool) -> Result<Self, DecodeError> {
+        let inner: &str = text
+            .trim()
+            .strip_prefix('[')
+            .and_then(|rest| rest.strip_suffix(']'))
+            .ok_or_else(|| DecodeError::BadToken(text.to_string()))?;
+        let mut shape: Shape<T> = Shape::new(coloured);
+        if inner.trim().is_empty() {
+            return Ok(shape);
+        }
+        let mut tokens = inner.split(',').map(str::trim);
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        if let Some(node) = Self::_parse_token(tokens.next().unwrap(), coloured, "null")? {
+            queue.push_back(shape._add_parsed(None, node));
+        }
+        while let Some(parent) = queue.pop_front() {
+            for is_left in [true, false] {
+                let token: &str = match tokens.next() {
+                    None => return Ok(shape),
+                    Some(token) => token,
+                };
+                if let Some(node) = Self::_parse_token(token, coloured, "null")? {
+                    queue.push_back(shape._add_parsed(Some((parent, is_left)), node));
+                }
+            }
+        }
+        match tokens.all(|token| token == "null") {
+            true => Ok(shape),
+            false => Err(DecodeError::TrailingData),
+        }
+    }
+}
+
+impl<T: Ord + Copy + FixedSize> Shape<T> {
+    /// The size of one node in the binary form.
+    fn _record_size(multiset: bool) -> usize {
+        match multiset {
+            true => 1 + T::SIZE + 8,
+            false => 1 + T::SIZE,
+        }
+    }
+
+    /// A compact binary form: a header, then one flag byte, the key bytes
+    /// and, in a multiset shape, the count per node in preorder, without
+    /// null markers.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(10 + self.nodes.len() * Self::_record_size(self.multiset));
+        bytes.extend_from_slice(MAGIC);
+        bytes.push(VERSION);
+        let mut header: u8 = 0;
+        if self.coloured {
+            header |= COLOURED;
+        }
+        if self.multiset {
+            header |= MULTISET;
+        }
+        bytes.push(header);
+        bytes.extend_from_slice(&(self.nodes.len() as u32).to_le_bytes());
+        let mut key: Vec<u8> = vec![0; T::SIZE];
+        for index in self.preorder() {
+            let node: &ShapeNode<T> = &self.nodes[index];
+            let mut flags: u8 = 0;
+            if node.left.is_some() {
+                flags |= HAS_LEFT;
+            }
+            if node.right.is_some() {
+                flags |= HAS_RIGHT;
+            }
+            if node.red {
+                flags |= RED;
+            }
+            bytes.push(flags);
+            node.key.encode(&mut key);
+            bytes.extend_from_slice(&key);
+            if self.multiset {
+                bytes.extend_from_slice(&(node.count as u64).to_le_bytes());
+            }
+        }
+        bytes
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        if bytes.len() < 10 || &bytes[..4] != MAGIC || bytes[4] != VERSION || bytes[5] & !(COLOURED | MULTISET) != 0 {
+            return Err(DecodeError::BadHeader);
+        }
+        let mut shape: Shape<T> = Shape::new(bytes[5] & COLOURED != 0);
+        shape.multiset = bytes[5] & MULTISET != 0;
+        let record_size: usize = Self::_record_size(shape.multiset);
+        let count: usize = u32::from_le_bytes(bytes[6..10].try_into().unwrap()) as usize;
+        let mut records = bytes[10..].chunks(record_size);
+        let mut slots: Vec<Option<(usize, bool)>> = match count {
+            0 => Vec::new(),
+            _ => vec![None],
+        };
+        while let Some(slot) = slots.pop() {
+            let record: &[u8] = records.next().ok_or(DecodeError::UnexpectedEnd)?;
+            if record.len() < record_size {
+                return Err(DecodeError::UnexpectedEnd);
+            }
+            let flags: u8 = record[0];
+            if flags & !(HAS_LEFT | HAS_RIGHT | RED) != 0 || (flags & RED != 0 && !shape.coloured) {
+                return Err(DecodeError::BadToken(format!("flags {:#04x}", flags)));
+            }
+            let occurrences: usize = match shape.multiset {
+                true => u64::from_le_bytes(record[1 + T::SIZE..].try_into().unwrap()) as usize,
+                false => 1,
+            };
+            let index: usize = shape.add_node(slot, T::decode(&record[1..]), occurrences, flags & RED != 0);
+            if flags & HAS_RIGHT != 0 {
+                slots.push(Some((index, false)));
+            }
+            if flags & HAS_LEFT != 0 {
+                slots.push(Some((index, true)));
+            }
+        }
+        if records.next().is_some() {
+            return Err(DecodeError::TrailingData);
+        }
+        match shape.nodes.len() == count {
+            true => Ok(shape),
+            false => Err(DecodeError::BadHeader),
+        }
+    }
+}
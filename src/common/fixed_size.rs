@@ -0,0 +1,29 @@
+/// A type that is stored in a fixed number of bytes, such as a key inside
+/// a B+ tree page or a node of a binary tree shape.
+pub trait FixedSize: Copy {
+    const SIZE: usize;
+
+    fn encode(&self, buffer: &mut [u8]);
+
+    fn decode(buffer: &[u8]) -> Self;
+}
+
+macro_rules! impl_fixed_size {
+    ($($number:ty),*) => {
+        $(
+            impl FixedSize for $number {
+                const SIZE: usize = std::mem::size_of::<$number>();
+
+                fn encode(&self, buffer: &mut [u8]) {
+                    buffer[..Self::SIZE].copy_from_slice(&self.to_le_bytes());
+                }
+
+                fn decode(buffer: &[u8]) -> Self {
+                    <$number>::from_le_bytes(buffer[..Self::SIZE].try_into().unwrap())
+                }
+            }
+        )*
+    };
+}
+
+impl_fixed_size!(u8, u16, u32, u64, i8, i16, i32, i64);
@@ -0,0 +1,11 @@
+mod binary_tree;
+mod checksum;
+mod codec;
+mod fixed_size;
+#[cfg(test)]
+pub mod test_utils;
+
+pub use binary_tree::{BinaryNode, BinaryTree};
+pub use checksum::crc32;
+pub use codec::{DecodeError, Shape, TreeDiff};
+pub use fixed_size::FixedSize;
\ No newline at end of file
@@ -0,0 +1,20 @@
+use std::fs;
+use std::path::PathBuf;
+
+/// Small xorshift generator, enough to drive the randomized tests.
+pub fn next_random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// A fresh path in a per-process temporary directory; any file left there
+/// by an earlier run is removed.
+pub fn temp_file(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("mp_lab_ii_{}", std::process::id()));
+    fs::create_dir_all(&directory).unwrap();
+    let path = directory.join(name);
+    let _ = fs::remove_file(&path);
+    path
+}
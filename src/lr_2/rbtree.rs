@@ -1,8 +1,12 @@
 use crate::lr_2::base::Tree;
 use crate::lr_2::base::TreeNode;
+use crate::common::{BinaryNode, BinaryTree, DecodeError, FixedSize, Shape, TreeDiff};
 use std::cell::{RefCell, Ref, RefMut};
+use std::cmp::max;
 use std::rc::Rc;
-use std::fmt::Debug;
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
 
 #[derive(Clone, Debug, PartialEq)]
@@ -23,6 +27,8 @@ type OptionNode<T> = Option<RcRefcellRBTNode<T>>;
 #[derive(Debug)]
 pub struct RBTreeNode<T: Ord + Copy + Debug> {
     pub key: T,
+    /// How many times the key was inserted; always 1 outside multiset mode.
+    count: usize,
     color: NodeColor,
     parent: OptionNode<T>,
     left: OptionNode<T>,
@@ -35,6 +41,9 @@ pub struct RBTreeNode<T: Ord + Copy + Debug> {
 #[derive(Debug)]
 pub struct RBTree<T: Ord + Copy + Debug> {
     root: OptionNode<T>,
+    /// The number of elements, duplicates included.
+    elements: usize,
+    multiset: bool,
 }
 
 impl<T: Ord + Copy + Debug> TreeNode<T> for RBTreeNode<T> {
@@ -51,11 +60,34 @@ impl<T: Ord + Copy + Debug> TreeNode<T> for RBTreeNode<T> {
     }
 }
 
+impl<T: Ord + Copy + Debug> BinaryNode<T> for RBTreeNode<T> {
+    fn left_child(&self) -> &OptionNode<T> {
+        &self.left
+    }
+
+    fn right_child(&self) -> &OptionNode<T> {
+        &self.right
+    }
+
+    fn node_key(&self) -> T {
+        self.key
+    }
+}
+
+impl<T: Ord + Copy + Debug> BinaryTree<T> for RBTree<T> {
+    type Node = RBTreeNode<T>;
+
+    fn root_node(&self) -> &OptionNode<T> {
+        &self.root
+    }
+}
+
 impl<T: Ord + Copy + Debug> RBTreeNode<T> {
     fn new(value: T) -> RBTreeNode<T> {
         RBTreeNode {
             color: NodeColor::Red,
             key: value,
+            count: 1,
             parent: None,
             left: None,
             right: None,
@@ -68,9 +100,13 @@ impl<T: Ord + Copy + Debug> RBTreeNode<T> {
         } else {
             "Red"
         };
+        let count: String = match self.count {
+            1 => String::new(),
+            count => format!(" x{}", count),
+        };
         match prefix_space.len() {
-            6 => println!("|____ {} {:?} {}", child_prefix, self.key, color),
-            _ => println!("{}{} {:?} {}", prefix_space, child_prefix, self.key, color),
+            6 => println!("|____ {} {:?} {}{}", child_prefix, self.key, color, count),
+            _ => println!("{}{} {:?} {}{}", prefix_space, child_prefix, self.key, color, count),
         }
         let mut new_prefix_space: String = String::from(prefix_space);
         match is_right {
@@ -113,7 +149,118 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
     pub fn new() -> Self {
         RBTree {
             root: None,
+            elements: 0,
+            multiset: false,
+        }
+    }
+
+    /// Makes a new empty RBTree in multiset mode: inserting a key that is
+    /// already present bumps its count instead of being refused, and
+    /// `delete` takes away one occurrence at a time.
+    ///
+    /// Every key still takes a single node, so `count_nodes` counts
+    /// distinct keys while `len` counts every occurrence. Shapes and the
+    /// text and binary forms also keep one entry per key, together with its
+    /// count (`key*count` in text), so a loaded tree is again a multiset
+    /// with the same counts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = RBTree::new_multiset();
+    /// for i in [2, 1, 2, 3, 2] {
+    ///     tree.insert(i);
+    /// }
+    /// assert_eq!(tree.count(2), 3);
+    /// assert_eq!(tree.len(), 5);
+    /// assert_eq!(tree.count_nodes(), 3);
+    /// assert_eq!(tree.iter().collect::<Vec<i32>>(), vec![1, 2, 2, 2, 3]);
+    /// ```
+    pub fn new_multiset() -> Self {
+        let mut tree: RBTree<T> = RBTree::new();
+        tree.multiset = true;
+        tree
+    }
+
+    /// The number of elements in the tree. In multiset mode every
+    /// occurrence counts.
+    pub fn len(&self) -> usize {
+        self.elements
+    }
+
+    /// How many times `value` is stored: 0 or 1 outside multiset mode.
+    pub fn count(&self, value: T) -> usize {
+        let mut current: OptionNode<T> = self.root.clone();
+        while let Some(node) = current {
+            let node: Ref<RBTreeNode<T>> = node.borrow();
+            current = match value.cmp(&node.key) {
+                std::cmp::Ordering::Less => node.left.clone(),
+                std::cmp::Ordering::Greater => node.right.clone(),
+                std::cmp::Ordering::Equal => return node.count,
+            };
+        }
+        0
+    }
+
+    /// Every key with the number of times it is stored, in key order.
+    pub fn counts(&self) -> Vec<(T, usize)> {
+        let mut counts: Vec<(T, usize)> = Vec::new();
+        let mut stack: Vec<RcRefcellRBTNode<T>> = Vec::new();
+        let mut current: OptionNode<T> = self.root.clone();
+        while current.is_some() || !stack.is_empty() {
+            while let Some(node) = current {
+                current = node.borrow().left.clone();
+                stack.push(node);
+            }
+            if let Some(node) = stack.pop() {
+                let node: Ref<RBTreeNode<T>> = node.borrow();
+                counts.push((node.key, node.count));
+                current = node.right.clone();
+            }
+        }
+        counts
+    }
+
+    /// The keys in order, each repeated as many times as it is stored.
+    pub fn iter(&self) -> impl Iterator<Item = T> {
+        self.counts().into_iter().flat_map(|(key, count)| (0..count).map(move |_| key))
+    }
+
+    /// Takes away one occurrence of `value`; the node goes once its count
+    /// drops to 0.
+    ///
+    /// Returns `false` if the value is missing.
+    pub fn remove_one(&mut self, value: T) -> bool {
+        let (found, node) = self.search(value);
+        if !found {
+            return false;
+        }
+        let node: RcRefcellRBTNode<T> = node.unwrap();
+        self.elements -= 1;
+        if node.borrow().count > 1 {
+            node.borrow_mut().count -= 1;
+            return true;
+        }
+        let _ = self._delete_private(&mut &node);
+        true
+    }
+
+    /// Takes away every occurrence of `value`.
+    ///
+    /// Returns how many there were.
+    pub fn remove_all(&mut self, value: T) -> usize {
+        let (found, node) = self.search(value);
+        if !found {
+            return 0;
         }
+        let node: RcRefcellRBTNode<T> = node.unwrap();
+        let count: usize = node.borrow().count;
+        self.elements -= count;
+        let _ = self._delete_private(&mut &node);
+        count
     }
 
     fn _insert_repair(&mut self, new_child: RcRefcellRBTNode<T>) {
@@ -359,9 +506,13 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
         else if node.borrow().left.is_none() || node.borrow().right.is_none() {
             // node is root, the tree only has two nodes.
             if node.borrow().parent.is_none() {
-                let temp: T = replacement.as_ref().unwrap().borrow().key;
+                let (temp, count): (T, usize) = {
+                    let replacement: Ref<RBTreeNode<T>> = replacement.as_ref().unwrap().borrow();
+                    (replacement.key, replacement.count)
+                };
                 let mut root: RefMut<RBTreeNode<T>> = self.root.as_ref().unwrap().borrow_mut();
                 root.key = temp;
+                root.count = count;
                 root.left = None;
                 root.right = None;
             } else {
@@ -385,8 +536,12 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
         // 3. node has two children
         else {
             // actually delete replacement.
-            let temp: T = replacement.as_ref().unwrap().borrow().key;
+            let (temp, count): (T, usize) = {
+                let replacement: Ref<RBTreeNode<T>> = replacement.as_ref().unwrap().borrow();
+                (replacement.key, replacement.count)
+            };
             node.borrow_mut().key = temp;
+            node.borrow_mut().count = count;
             self._delete_private(&mut replacement.as_ref().unwrap()).unwrap();
         }
         Ok(())
@@ -506,11 +661,177 @@ impl<T: Ord + Copy + Debug> RBTree<T> {
     /// assert!(tree.is_empty());
     /// ```
     pub fn clear(&mut self) {
-        *self = RBTree::new();
+        *self = match self.multiset {
+            true => RBTree::new_multiset(),
+            false => RBTree::new(),
+        };
         println!("Clear operation is complete!");
     }
 }
 
+impl<T: Ord + Copy + Debug> RBTree<T> {
+    /// The shape of the tree with the colour of every node.
+    pub fn to_shape(&self) -> Shape<T> {
+        let mut shape: Shape<T> = Shape::new(true);
+        shape.multiset = self.multiset;
+        let mut stack: Vec<(RcRefcellRBTNode<T>, _)> =
+            self.root.iter().map(|root| (Rc::clone(root), None::<(usize, bool)>)).collect();
+        while let Some((node, parent)) = stack.pop() {
+            let node: Ref<RBTreeNode<T>> = node.borrow();
+            let index: usize = shape.add_node(parent, node.key, node.count, node.color == NodeColor::Red);
+            if let Some(right) = node.right.as_ref() {
+                stack.push((Rc::clone(right), Some((index, false))));
+            }
+            if let Some(left) = node.left.as_ref() {
+                stack.push((Rc::clone(left), Some((index, true))));
+            }
+        }
+        shape
+    }
+
+    /// Rebuilds a tree of exactly the given shape and colours, in multiset
+    /// mode if the shape is a multiset one.
+    ///
+    /// The shape must be coloured, ordered, have possible counts, and
+    /// satisfy the red-black invariants; otherwise the matching
+    /// `DecodeError` is returned.
+    pub fn from_shape(shape: &Shape<T>) -> Result<Self, DecodeError> {
+        if !shape.coloured {
+            return Err(DecodeError::MissingColours);
+        }
+        shape.check_order()?;
+        shape.check_red_black()?;
+        let nodes: Vec<RcRefcellRBTNode<T>> = shape.nodes
+            .iter()
+            .map(|node| {
+                let mut tree_node: RBTreeNode<T> = RBTreeNode::new(node.key);
+                tree_node.count = node.count;
+                if !node.red {
+                    tree_node.color = NodeColor::Black;
+                }
+                Rc::new(RefCell::new(tree_node))
+            })
+            .collect();
+        for (index, node) in shape.nodes.iter().enumerate() {
+            for (child, is_left) in [(node.left, true), (node.right, false)] {
+                if let Some(child) = child {
+                    nodes[child].borrow_mut().parent = Some(Rc::clone(&nodes[index]));
+                    let child: OptionNode<T> = Some(Rc::clone(&nodes[child]));
+                    match is_left {
+                        true => nodes[index].borrow_mut().left = child,
+                        false => nodes[index].borrow_mut().right = child,
+                    }
+                }
+            }
+        }
+        Ok(RBTree {
+            root: nodes.first().cloned(),
+            elements: shape.nodes.iter().map(|node| node.count).sum(),
+            multiset: shape.multiset,
+        })
+    }
+
+    /// Checks whether both trees branch the same way, whatever their keys
+    /// and colours.
+    pub fn same_shape(&self, other: &RBTree<T>) -> bool {
+        self.to_shape().same_shape(&other.to_shape())
+    }
+
+    /// Checks whether both trees hold the same keys in the same places,
+    /// stored as many times each and with the same colours. `==` only
+    /// compares the keys.
+    pub fn structural_eq(&self, other: &RBTree<T>) -> bool {
+        self.to_shape().structural_eq(&other.to_shape())
+    }
+
+    /// The keys inserted and removed from this tree to `other`, and the
+    /// keys of both whose children, colour or count differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut before = RBTree::new();
+    /// for i in [1, 2] {
+    ///     before.insert(i);
+    /// }
+    /// let mut after = RBTree::new();
+    /// for i in [1, 2, 3] {
+    ///     after.insert(i);
+    /// }
+    /// // Inserting 3 rotates 2 up to the root.
+    /// let diff = before.diff(&after);
+    /// assert_eq!(diff.inserted, vec![3]);
+    /// assert_eq!(diff.changed, vec![1, 2]);
+    /// ```
+    pub fn diff(&self, other: &RBTree<T>) -> TreeDiff<T> {
+        self.to_shape().diff(&other.to_shape())
+    }
+}
+
+/// Trees are equal when they hold the same keys, as many times each,
+/// however they are shaped.
+impl<T: Ord + Copy + Debug> PartialEq for RBTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.elements == other.elements && self.counts() == other.counts()
+    }
+}
+
+impl<T: Ord + Copy + Debug> Eq for RBTree<T> {}
+
+impl<T: Ord + Copy + Debug + Hash> Hash for RBTree<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.counts().hash(state);
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display + FromStr> RBTree<T> {
+    /// Preorder text with `#` null markers and colours, e.g. `2:B,1:R,#,#,#`.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::rbtree::RBTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = RBTree::new();
+    /// for i in [1, 2, 3] {
+    ///     tree.insert(i);
+    /// }
+    /// assert_eq!(tree.to_level_order_text(), "[2:B,1:R,3:R]");
+    /// let copy = RBTree::<i32>::from_preorder_text("2:B,1:R,#,#,3:R,#,#").unwrap();
+    /// assert_eq!(copy.to_level_order_text(), "[2:B,1:R,3:R]");
+    /// ```
+    pub fn to_preorder_text(&self) -> String {
+        self.to_shape().to_preorder_text()
+    }
+
+    pub fn from_preorder_text(text: &str) -> Result<Self, DecodeError> {
+        Self::from_shape(&Shape::from_preorder_text(text, true)?)
+    }
+
+    /// Level-order text in the LeetCode style with colours, e.g. `[2:B,1:R,3:R]`.
+    pub fn to_level_order_text(&self) -> String {
+        self.to_shape().to_level_order_text()
+    }
+
+    pub fn from_level_order_text(text: &str) -> Result<Self, DecodeError> {
+        Self::from_shape(&Shape::from_level_order_text(text, true)?)
+    }
+}
+
+impl<T: Ord + Copy + Debug + FixedSize> RBTree<T> {
+    /// The compact binary form with colours, see `Shape::to_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_shape().to_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_shape(&Shape::from_bytes(bytes)?)
+    }
+}
+
 impl<T: Ord + Copy + Debug> Tree<T, RBTreeNode<T>> for RBTree<T> {
     /// Return the root node of the RBTree.
     ///
@@ -564,12 +885,21 @@ impl<T: Ord + Copy + Debug> Tree<T, RBTreeNode<T>> for RBTree<T> {
                 let mut new_node = RBTreeNode::new(value);
                 new_node.color = NodeColor::Black;
                 self.root = Some(Rc::new(RefCell::new(new_node)));
+                self.elements += 1;
             }
             false => {
                 let (found, parent_option) = self.search(value);
                 match found {
-                    true => println!("The node already exists in the tree."), // 2. node already exists
+                    // 2. node already exists
+                    true => match self.multiset {
+                        true => {
+                            parent_option.as_ref().unwrap().borrow_mut().count += 1;
+                            self.elements += 1;
+                        }
+                        false => println!("The node already exists in the tree."),
+                    },
                     false => {
+                        self.elements += 1;
                         // 3. insert node
                         let child_belongs_on_left: bool = value < parent_option.as_ref().unwrap().borrow().key;
                         let new_child_node: RcRefcellRBTNode<T> = Rc::new(RefCell::new(RBTreeNode::new(value)));
@@ -616,16 +946,9 @@ impl<T: Ord + Copy + Debug> Tree<T, RBTreeNode<T>> for RBTree<T> {
     /// assert_eq!(tree.count_nodes(), 6);
     /// ```
     fn delete(&mut self, value: T) {
-        let (flag, searched_node) = self.search(value);
-        match flag {
-            false => {
-                println!("The node of value {:#?} doesn't exist.",value);
-            }
-            true => {
-                let mut searched_node_ref: &RcRefcellRBTNode<T> = searched_node.as_ref().unwrap();
-                let _ = self._delete_private(&mut searched_node_ref);
-            }
-        };
+        if !self.remove_one(value) {
+            println!("The node of value {:#?} doesn't exist.",value);
+        }
     }
 
     /// Print the RBTree.
@@ -659,4 +982,24 @@ impl<T: Ord + Copy + Debug> Tree<T, RBTreeNode<T>> for RBTree<T> {
             ),
         }
     }
+    // The metrics below go through `fold`, whose explicit stack keeps
+    // degenerate trees from overflowing the call stack.
+    fn get_height(&self) -> u32 {
+        self.fold(0, |_, left, right| max(left, right) + 1)
+    }
+
+    fn count_leaves(&self) -> u32 {
+        // (nodes, leaves) of every subtree
+        let (_, leaves): (u32, u32) = self.fold((0, 0), |_, left, right| {
+            match left.0 + right.0 {
+                0 => (1, 1),
+                nodes => (nodes + 1, left.1 + right.1),
+            }
+        });
+        leaves
+    }
+
+    fn count_nodes(&self) -> u32 {
+        self.fold(0, |_, left, right| left + right + 1)
+    }
 }
\ No newline at end of file
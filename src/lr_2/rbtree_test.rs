@@ -2,6 +2,10 @@
 mod test {
     use crate::lr_2::base::Tree;
     use crate::lr_2::rbtree;
+    use crate::common::{BinaryTree, DecodeError};
+    use std::collections::BTreeMap;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
 
     #[test]
     fn test_rbtree() {
@@ -49,4 +53,219 @@ mod test {
         rb_tree.clear();
         assert_eq!(rb_tree.is_empty(), true);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_rbtree_round_trip() {
+        let mut rb_tree: rbtree::RBTree<i32> = rbtree::RBTree::new();
+        for number in 1..=7 {
+            rb_tree.insert(number);
+        }
+        let level_order = rb_tree.to_level_order_text();
+        assert_eq!(level_order, "[2:B,1:B,4:R,null,null,3:B,6:B,null,null,5:R,7:R]");
+        let preorder = rb_tree.to_preorder_text();
+        assert_eq!(preorder, "2:B,1:B,#,#,4:R,3:B,#,#,6:B,5:R,#,#,7:R,#,#");
+        for copy in [
+            rbtree::RBTree::<i32>::from_preorder_text(&preorder).unwrap(),
+            rbtree::RBTree::<i32>::from_level_order_text(&level_order).unwrap(),
+            rbtree::RBTree::<i32>::from_bytes(&rb_tree.to_bytes()).unwrap(),
+        ] {
+            assert_eq!(copy.to_level_order_text(), level_order);
+            assert_eq!(copy.count_nodes(), 7);
+        }
+
+        // The loaded tree keeps its parent links, so it can be modified.
+        let mut copy = rbtree::RBTree::<i32>::from_level_order_text(&level_order).unwrap();
+        for number in [2, 4, 6] {
+            copy.delete(number);
+        }
+        copy.insert(8);
+        assert!(copy.to_shape().check_red_black().is_ok());
+        assert_eq!(copy.count_nodes(), 5);
+        for number in [1, 3, 5, 7, 8] {
+            assert!(copy.contain(number));
+        }
+    }
+
+    #[test]
+    fn test_rbtree_multiset_round_trip() {
+        let mut rb_tree: rbtree::RBTree<i32> = rbtree::RBTree::new_multiset();
+        for number in [2, 2, 2, 1, 3, 3] {
+            rb_tree.insert(number);
+        }
+        let level_order = rb_tree.to_level_order_text();
+        assert_eq!(level_order, "[2*3:B,1*1:R,3*2:R]");
+        for copy in [
+            rbtree::RBTree::<i32>::from_preorder_text(&rb_tree.to_preorder_text()).unwrap(),
+            rbtree::RBTree::<i32>::from_level_order_text(&level_order).unwrap(),
+            rbtree::RBTree::<i32>::from_bytes(&rb_tree.to_bytes()).unwrap(),
+        ] {
+            assert_eq!(copy.len(), 6);
+            assert_eq!(copy.count(2), 3);
+            assert_eq!(copy.count(3), 2);
+            assert!(copy.structural_eq(&rb_tree));
+        }
+
+        // The copy is still a multiset: one more 2 is counted, not refused.
+        let mut copy = rbtree::RBTree::<i32>::from_bytes(&rb_tree.to_bytes()).unwrap();
+        copy.insert(2);
+        assert_eq!(copy.count(2), 4);
+        assert!(!copy.structural_eq(&rb_tree));
+        assert_eq!(rb_tree.diff(&copy).changed, vec![2]);
+    }
+
+    #[test]
+    fn test_rbtree_load_rejects_invalid_trees() {
+        let load = |text: &str| rbtree::RBTree::<i32>::from_level_order_text(text).err();
+        assert_eq!(load("[2:B,1:B,3:B]"), None);
+        assert_eq!(load("[2:B,3:R,1:R]"), Some(DecodeError::OutOfOrder));
+        assert_eq!(load("[2:R,1:B,3:B]"), Some(DecodeError::RedRoot));
+        assert_eq!(load("[2:B,1:R,4:B,null,null,3:R]"), Some(DecodeError::BlackHeightMismatch));
+        assert_eq!(load("[1:B,null,2:R,null,3:R]"), Some(DecodeError::DoubleRed));
+        assert_eq!(load("[2,1,3]"), Some(DecodeError::BadToken("2".to_string())));
+        assert_eq!(rbtree::RBTree::<i32>::from_shape(&crate::common::Shape::new(false)).err(), Some(DecodeError::MissingColours));
+        assert!(rbtree::RBTree::<i32>::from_level_order_text("[]").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_rbtree_fold() {
+        let mut rb_tree: rbtree::RBTree<i32> = rbtree::RBTree::new();
+        for number in 1..=7 {
+            rb_tree.insert(number);
+        }
+        let (sum, nodes) = rb_tree.fold((0, 0), |key, left, right| (left.0 + right.0 + key, left.1 + right.1 + 1));
+        assert_eq!((sum, nodes), (28, 7));
+        let sums = rb_tree.fold_with_context(0, |sum, key| sum + key, Vec::new(), |_, sum, left, right| {
+            match (left.is_empty(), right.is_empty()) {
+                (true, true) => vec![*sum],
+                _ => [left, right].concat(),
+            }
+        });
+        assert_eq!(sums, vec![3, 9, 17, 19]);
+    }
+
+    #[test]
+    fn test_rbtree_structural_queries() {
+        let mut rb_tree: rbtree::RBTree<i32> = rbtree::RBTree::new();
+        //                  2
+        //               1     4
+        //                   3   6
+        //                      5 7
+        for number in 1..=7 {
+            rb_tree.insert(number);
+        }
+        assert_eq!(rb_tree.depth(5), Some(3));
+        assert_eq!(rb_tree.lca(3, 7), Some(4));
+        assert_eq!(rb_tree.path(1, 7), Some(vec![1, 2, 4, 6, 7]));
+        assert_eq!(rb_tree.level(3), vec![5, 7]);
+        assert_eq!(rb_tree.diameter(), 4);
+        assert!(!rb_tree.is_balanced());
+
+        let mut rb_tree: rbtree::RBTree<i32> = rbtree::RBTree::new();
+        for number in [4, 2, 6, 1, 3, 5, 7] {
+            rb_tree.insert(number);
+        }
+        assert!(rb_tree.is_balanced());
+        assert_eq!(rb_tree.level(2), vec![1, 3, 5, 7]);
+    }
+
+    #[test]
+    fn test_rbtree_equality() {
+        let mut ascending: rbtree::RBTree<i32> = rbtree::RBTree::new();
+        let mut descending: rbtree::RBTree<i32> = rbtree::RBTree::new();
+        for number in 1..=7 {
+            ascending.insert(number);
+        }
+        for number in (1..=7).rev() {
+            descending.insert(number);
+        }
+        assert!(ascending == descending);
+        let hash_of = |tree: &rbtree::RBTree<i32>| {
+            let mut hasher: DefaultHasher = DefaultHasher::new();
+            tree.hash(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash_of(&ascending), hash_of(&descending));
+        assert!(!ascending.structural_eq(&descending));
+        assert!(!ascending.same_shape(&descending));
+
+        let copy: rbtree::RBTree<i32> = rbtree::RBTree::from_shape(&ascending.to_shape()).unwrap();
+        assert!(copy.structural_eq(&ascending));
+        assert!(copy.diff(&ascending).is_empty());
+
+        let diff = ascending.diff(&descending);
+        assert!(diff.inserted.is_empty() && diff.removed.is_empty());
+        assert!(!diff.changed.is_empty());
+
+        descending.delete(7);
+        assert!(ascending != descending);
+        assert_eq!(ascending.diff(&descending).removed, vec![7]);
+    }
+
+    #[test]
+    fn test_rbtree_multiset() {
+        let mut rb_tree: rbtree::RBTree<i32> = rbtree::RBTree::new_multiset();
+        for number in [5, 3, 5, 8, 3, 5, 1] {
+            rb_tree.insert(number);
+        }
+        assert_eq!(rb_tree.len(), 7);
+        assert_eq!(rb_tree.count_nodes(), 4);
+        assert_eq!(rb_tree.counts(), vec![(1, 1), (3, 2), (5, 3), (8, 1)]);
+        assert_eq!(rb_tree.iter().collect::<Vec<i32>>(), vec![1, 3, 3, 5, 5, 5, 8]);
+
+        rb_tree.delete(3);
+        assert_eq!(rb_tree.count(3), 1);
+        // 3 is an inner node: its replacement must carry its own count.
+        assert_eq!(rb_tree.remove_all(3), 1);
+        assert_eq!(rb_tree.counts(), vec![(1, 1), (5, 3), (8, 1)]);
+        assert_eq!(rb_tree.remove_all(5), 3);
+        assert!(rb_tree.remove_one(8));
+        assert!(!rb_tree.remove_one(8));
+        assert_eq!(rb_tree.counts(), vec![(1, 1)]);
+        assert_eq!(rb_tree.len(), 1);
+
+        let mut bag: rbtree::RBTree<i32> = rbtree::RBTree::new_multiset();
+        let mut set: rbtree::RBTree<i32> = rbtree::RBTree::new();
+        for number in [2, 2, 1] {
+            bag.insert(number);
+            set.insert(number);
+        }
+        assert!(bag != set);
+        assert_eq!(set.len(), 2);
+        bag.remove_one(2);
+        assert!(bag == set);
+    }
+
+    #[test]
+    fn test_rbtree_multiset_matches_btreemap() {
+        let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+        let mut next_random = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let mut rb_tree: rbtree::RBTree<i32> = rbtree::RBTree::new_multiset();
+        let mut model: BTreeMap<i32, usize> = BTreeMap::new();
+        for _ in 0..3000 {
+            let value = (next_random() % 32) as i32;
+            match next_random() % 5 {
+                0 => assert_eq!(rb_tree.remove_all(value), model.remove(&value).unwrap_or(0)),
+                1 => {
+                    let present = model.contains_key(&value);
+                    if present {
+                        *model.get_mut(&value).unwrap() -= 1;
+                        model.retain(|_, count| *count > 0);
+                    }
+                    assert_eq!(rb_tree.remove_one(value), present);
+                }
+                _ => {
+                    rb_tree.insert(value);
+                    *model.entry(value).or_insert(0) += 1;
+                }
+            }
+            assert_eq!(rb_tree.len(), model.values().sum::<usize>());
+        }
+        assert_eq!(rb_tree.counts(), model.into_iter().collect::<Vec<(i32, usize)>>());
+        assert!(rb_tree.to_shape().check_red_black().is_ok());
+    }
+}
@@ -163,9 +163,13 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// assert_eq!(tree.get_min(), Some(0));
     /// ```
     fn get_min(&self) -> Option<T> {
-        match &self.get_root() {
-            None => None,
-            Some(node) => Some(node.borrow().get_min()),
+        let mut node: Rc<RefCell<TN>> = Rc::clone(self.get_root().as_ref()?);
+        loop {
+            let left: Option<Rc<RefCell<TN>>> = node.borrow().get_left().clone();
+            match left {
+                None => return Some(node.borrow().get_data()),
+                Some(left) => node = left,
+            }
         }
     }
 
@@ -190,9 +194,13 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// assert_eq!(tree.get_max(), Some(2));
     /// ```
     fn get_max(&self) -> Option<T> {
-        match &self.get_root() {
-            None => None,
-            Some(node) => Some(node.borrow().get_max()),
+        let mut node: Rc<RefCell<TN>> = Rc::clone(self.get_root().as_ref()?);
+        loop {
+            let right: Option<Rc<RefCell<TN>>> = node.borrow().get_right().clone();
+            match right {
+                None => return Some(node.borrow().get_data()),
+                Some(right) => node = right,
+            }
         }
     }
 
@@ -221,6 +229,9 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
 
     /// Returns the number of elements in the tree.
     ///
+    /// In multiset mode a key stored several times takes one node and is
+    /// counted once; `len` counts every occurrence.
+    ///
     /// This function can be used in RBTree, AVLTree and BSTree.
     ///
     /// # Examples
@@ -363,10 +374,16 @@ pub trait Tree<T: Ord + Copy + Debug, TN: TreeNode<T>> {
     /// assert!(!tree.contain(999));
     /// ```
     fn contain(&self, value: T) -> bool {
-        match &self.get_root() {
-            None => false,
-            Some(node) => node.borrow().contain(value),
+        let mut current: Option<Rc<RefCell<TN>>> = self.get_root().clone();
+        while let Some(node) = current {
+            let node = node.borrow();
+            current = match node.get_data().cmp(&value) {
+                Ordering::Greater => node.get_left().clone(),
+                Ordering::Less => node.get_right().clone(),
+                Ordering::Equal => return true,
+            };
         }
+        false
     }
 
     /// Сhecking if the tree is empty.
@@ -0,0 +1,384 @@
+use std::fmt::Debug;
+use std::marker::PhantomData;
+use std::ops::{Bound, RangeBounds};
+use std::path::Path;
+use crate::common::FixedSize;
+use crate::lr_3::pager::{BufferPool, Page, PageError, PageStore, PAGE_SIZE};
+
+const MAGIC: &[u8; 4] = b"BPT1";
+/// The meta page: checksum, magic, key size, value size, root, length.
+const META_PAGE: u32 = 0;
+/// Page number that stands for "no page", since page 0 is the meta page.
+const NO_PAGE: u32 = 0;
+
+/// Node pages: checksum, kind, key count, next leaf.
+const HEADER_SIZE: usize = 12;
+const LEAF: u8 = 1;
+const INTERNAL: u8 = 2;
+
+/// Default number of pages kept in memory.
+const DEFAULT_POOL_PAGES: usize = 64;
+
+/// A node decoded from its page.
+enum Node<K, V> {
+    Leaf { keys: Vec<K>, values: Vec<V>, next: u32 },
+    Internal { keys: Vec<K>, children: Vec<u32> },
+}
+
+/// A B+ tree whose nodes live in fixed-size pages of a local file.
+///
+/// Keys and values are stored in the leaves only; the leaves are chained
+/// left to right, so range scans walk the sibling links instead of the
+/// tree. Pages are cached in a `BufferPool` and every page carries a
+/// checksum, so a page torn by an interrupted write is detected on open.
+///
+/// Changes become durable on `flush` or `close`.
+pub struct BPlusTree<K: FixedSize + Ord + Debug, V: FixedSize> {
+    pool: BufferPool,
+    root: u32,
+    len: u64,
+    leaf_capacity: usize,
+    internal_capacity: usize,
+    _marker: PhantomData<(K, V)>,
+}
+
+impl<K: FixedSize + Ord + Debug, V: FixedSize> BPlusTree<K, V> {
+    /// Opens the tree stored at `path`, creating an empty one if the file
+    /// does not exist yet.
+    ///
+    /// Every page is checked against its checksum, so a file left behind by
+    /// an interrupted write is rejected with `PageError::TornPage`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::bplus_tree::BPlusTree;
+    ///
+    /// let path = std::env::temp_dir().join("index.db");
+    /// let mut tree: BPlusTree<u64, u64> = BPlusTree::open(&path).unwrap();
+    /// tree.insert(1, 10).unwrap();
+    /// tree.close().unwrap();
+    ///
+    /// let mut tree: BPlusTree<u64, u64> = BPlusTree::open(&path).unwrap();
+    /// assert_eq!(tree.get(&1).unwrap(), Some(10));
+    /// ```
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PageError> {
+        Self::open_with_pool(path, DEFAULT_POOL_PAGES)
+    }
+
+    /// Opens the tree keeping at most `pool_pages` pages in memory.
+    pub fn open_with_pool<P: AsRef<Path>>(path: P, pool_pages: usize) -> Result<Self, PageError> {
+        let store: PageStore = PageStore::open(path)?;
+        let mut tree: BPlusTree<K, V> = BPlusTree {
+            pool: BufferPool::new(store, pool_pages),
+            root: NO_PAGE,
+            len: 0,
+            leaf_capacity: (PAGE_SIZE - HEADER_SIZE) / (K::SIZE + V::SIZE),
+            internal_capacity: (PAGE_SIZE - HEADER_SIZE - 4) / (K::SIZE + 4),
+            _marker: PhantomData,
+        };
+        if tree.leaf_capacity < 3 || tree.internal_capacity < 3 {
+            return Err(PageError::BadFormat("keys and values are too large for a page".to_string()));
+        }
+        match tree.pool.page_count() {
+            0 => {
+                tree.pool.allocate()?;
+                tree.root = tree.pool.allocate()?;
+                tree._write_node(tree.root, &Node::Leaf { keys: Vec::new(), values: Vec::new(), next: NO_PAGE })?;
+                tree.flush()?;
+            }
+            _ => {
+                tree.pool.verify()?;
+                tree._read_meta()?;
+            }
+        }
+        Ok(tree)
+    }
+
+    /// Writes all changes to the file and syncs it.
+    pub fn flush(&mut self) -> Result<(), PageError> {
+        self._write_meta()?;
+        self.pool.flush()
+    }
+
+    /// Flushes the tree and closes the file.
+    pub fn close(mut self) -> Result<(), PageError> {
+        self.flush()
+    }
+
+    /// The number of entries in the tree.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of pages in the file, the meta page included.
+    pub fn page_count(&self) -> u32 {
+        self.pool.page_count()
+    }
+
+    /// The number of levels from the root down to the leaves.
+    pub fn get_height(&mut self) -> Result<u32, PageError> {
+        let mut height: u32 = 1;
+        let mut page_id: u32 = self.root;
+        while let Node::Internal { children, .. } = self._read_node(page_id)? {
+            page_id = children[0];
+            height += 1;
+        }
+        Ok(height)
+    }
+
+    /// Returns the value stored under `key`.
+    pub fn get(&mut self, key: &K) -> Result<Option<V>, PageError> {
+        let leaf: u32 = self._find_leaf(key)?;
+        match self._read_node(leaf)? {
+            Node::Leaf { keys, values, .. } => Ok(keys.binary_search(key).ok().map(|index| values[index])),
+            Node::Internal { .. } => unreachable!(),
+        }
+    }
+
+    pub fn contain(&mut self, key: &K) -> Result<bool, PageError> {
+        Ok(self.get(key)?.is_some())
+    }
+
+    /// Inserts a key-value pair, splitting full pages on the way back up.
+    ///
+    /// Returns the previous value if the key was already present.
+    pub fn insert(&mut self, key: K, value: V) -> Result<Option<V>, PageError> {
+        let (old, split) = self._insert_into(self.root, key, value)?;
+        if let Some((separator, right)) = split {
+            let new_root: u32 = self.pool.allocate()?;
+            self._write_node(new_root, &Node::Internal { keys: vec![separator], children: vec![self.root, right] })?;
+            self.root = new_root;
+        }
+        if old.is_none() {
+            self.len += 1;
+        }
+        Ok(old)
+    }
+
+    /// Removes `key` from its leaf and returns its value.
+    ///
+    /// Leaves are not merged, so a leaf may stay underfull or empty until
+    /// new keys arrive; lookups and scans are unaffected.
+    pub fn remove(&mut self, key: &K) -> Result<Option<V>, PageError> {
+        let leaf: u32 = self._find_leaf(key)?;
+        let mut node: Node<K, V> = self._read_node(leaf)?;
+        let removed: Option<V> = match &mut node {
+            Node::Leaf { keys, values, .. } => match keys.binary_search(key) {
+                Ok(index) => {
+                    keys.remove(index);
+                    Some(values.remove(index))
+                }
+                Err(_) => None,
+            },
+            Node::Internal { .. } => unreachable!(),
+        };
+        if removed.is_some() {
+            self._write_node(leaf, &node)?;
+            self.len -= 1;
+        }
+        Ok(removed)
+    }
+
+    /// Returns all entries whose keys fall inside `range`, in ascending
+    /// order, by following the leaf sibling links.
+    pub fn range<R: RangeBounds<K>>(&mut self, range: R) -> Result<Vec<(K, V)>, PageError> {
+        let mut page_id: u32 = match range.start_bound() {
+            Bound::Included(start) | Bound::Excluded(start) => self._find_leaf(start)?,
+            Bound::Unbounded => self._leftmost_leaf()?,
+        };
+        let mut result: Vec<(K, V)> = Vec::new();
+        while page_id != NO_PAGE {
+            let (keys, values, next) = match self._read_node(page_id)? {
+                Node::Leaf { keys, values, next } => (keys, values, next),
+                Node::Internal { .. } => unreachable!(),
+            };
+            for (key, value) in keys.into_iter().zip(values) {
+                let past_end: bool = match range.end_bound() {
+                    Bound::Included(end) => key > *end,
+                    Bound::Excluded(end) => key >= *end,
+                    Bound::Unbounded => false,
+                };
+                if past_end {
+                    return Ok(result);
+                }
+                if range.contains(&key) {
+                    result.push((key, value));
+                }
+            }
+            page_id = next;
+        }
+        Ok(result)
+    }
+
+    fn _child_index(keys: &[K], key: &K) -> usize {
+        match keys.binary_search(key) {
+            Ok(index) => index + 1,
+            Err(index) => index,
+        }
+    }
+
+    fn _find_leaf(&mut self, key: &K) -> Result<u32, PageError> {
+        let mut page_id: u32 = self.root;
+        while let Node::Internal { keys, children } = self._read_node(page_id)? {
+            page_id = children[Self::_child_index(&keys, key)];
+        }
+        Ok(page_id)
+    }
+
+    fn _leftmost_leaf(&mut self) -> Result<u32, PageError> {
+        let mut page_id: u32 = self.root;
+        while let Node::Internal { children, .. } = self._read_node(page_id)? {
+            page_id = children[0];
+        }
+        Ok(page_id)
+    }
+
+    /// Inserts below `page_id`. If the page had to split, returns the
+    /// separator key and the page number of the new right sibling.
+    #[allow(clippy::type_complexity)]
+    fn _insert_into(&mut self, page_id: u32, key: K, value: V) -> Result<(Option<V>, Option<(K, u32)>), PageError> {
+        match self._read_node(page_id)? {
+            Node::Leaf { mut keys, mut values, next } => {
+                match keys.binary_search(&key) {
+                    Ok(index) => {
+                        let old: V = std::mem::replace(&mut values[index], value);
+                        self._write_node(page_id, &Node::Leaf { keys, values, next })?;
+                        return Ok((Some(old), None));
+                    }
+                    Err(index) => {
+                        keys.insert(index, key);
+                        values.insert(index, value);
+                    }
+                }
+                if keys.len() <= self.leaf_capacity {
+                    self._write_node(page_id, &Node::Leaf { keys, values, next })?;
+                    return Ok((None, None));
+                }
+                let middle: usize = keys.len() / 2;
+                let right_keys: Vec<K> = keys.split_off(middle);
+                let right_values: Vec<V> = values.split_off(middle);
+                let separator: K = right_keys[0];
+                let right: u32 = self.pool.allocate()?;
+                self._write_node(right, &Node::Leaf { keys: right_keys, values: right_values, next })?;
+                self._write_node(page_id, &Node::Leaf { keys, values, next: right })?;
+                Ok((None, Some((separator, right))))
+            }
+            Node::Internal { mut keys, mut children } => {
+                let index: usize = Self::_child_index(&keys, &key);
+                let (old, split) = self._insert_into(children[index], key, value)?;
+                let (separator, new_child) = match split {
+                    None => return Ok((old, None)),
+                    Some(split) => split,
+                };
+                keys.insert(index, separator);
+                children.insert(index + 1, new_child);
+                if keys.len() <= self.internal_capacity {
+                    self._write_node(page_id, &Node::Internal { keys, children })?;
+                    return Ok((old, None));
+                }
+                let middle: usize = keys.len() / 2;
+                let right_keys: Vec<K> = keys.split_off(middle + 1);
+                let separator: K = keys.pop().unwrap();
+                let right_children: Vec<u32> = children.split_off(middle + 1);
+                let right: u32 = self.pool.allocate()?;
+                self._write_node(right, &Node::Internal { keys: right_keys, children: right_children })?;
+                self._write_node(page_id, &Node::Internal { keys, children })?;
+                Ok((old, Some((separator, right))))
+            }
+        }
+    }
+
+    fn _read_node(&mut self, page_id: u32) -> Result<Node<K, V>, PageError> {
+        let page: &Page = self.pool.page(page_id)?;
+        let count: usize = u16::from_le_bytes([page[5], page[6]]) as usize;
+        let size: usize = match page[4] {
+            LEAF => HEADER_SIZE + count * (K::SIZE + V::SIZE),
+            _ => HEADER_SIZE + count * (K::SIZE + 4) + 4,
+        };
+        if size > PAGE_SIZE {
+            return Err(PageError::BadFormat(format!("page {} holds {} keys, which do not fit in a page", page_id, count)));
+        }
+        let keys: Vec<K> = (0..count)
+            .map(|index| K::decode(&page[HEADER_SIZE + index * K::SIZE..]))
+            .collect();
+        let body: usize = HEADER_SIZE + count * K::SIZE;
+        match page[4] {
+            LEAF => Ok(Node::Leaf {
+                keys,
+                values: (0..count).map(|index| V::decode(&page[body + index * V::SIZE..])).collect(),
+                next: u32::from_le_bytes(page[7..11].try_into().unwrap()),
+            }),
+            INTERNAL => Ok(Node::Internal {
+                keys,
+                children: (0..=count)
+                    .map(|index| u32::from_le_bytes(page[body + index * 4..body + index * 4 + 4].try_into().unwrap()))
+                    .collect(),
+            }),
+            kind => Err(PageError::BadFormat(format!("page {} has unknown kind {}", page_id, kind))),
+        }
+    }
+
+    fn _write_node(&mut self, page_id: u32, node: &Node<K, V>) -> Result<(), PageError> {
+        let page: &mut Page = self.pool.page_mut(page_id)?;
+        page.fill(0);
+        let (kind, keys, next) = match node {
+            Node::Leaf { keys, next, .. } => (LEAF, keys, *next),
+            Node::Internal { keys, .. } => (INTERNAL, keys, NO_PAGE),
+        };
+        page[4] = kind;
+        page[5..7].copy_from_slice(&(keys.len() as u16).to_le_bytes());
+        page[7..11].copy_from_slice(&next.to_le_bytes());
+        for (index, key) in keys.iter().enumerate() {
+            key.encode(&mut page[HEADER_SIZE + index * K::SIZE..]);
+        }
+        let body: usize = HEADER_SIZE + keys.len() * K::SIZE;
+        match node {
+            Node::Leaf { values, .. } => {
+                for (index, value) in values.iter().enumerate() {
+                    value.encode(&mut page[body + index * V::SIZE..]);
+                }
+            }
+            Node::Internal { children, .. } => {
+                for (index, child) in children.iter().enumerate() {
+                    page[body + index * 4..body + index * 4 + 4].copy_from_slice(&child.to_le_bytes());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn _read_meta(&mut self) -> Result<(), PageError> {
+        let page: &Page = self.pool.page(META_PAGE)?;
+        if &page[4..8] != MAGIC {
+            return Err(PageError::BadFormat("not a B+ tree file".to_string()));
+        }
+        let key_size: usize = u16::from_le_bytes([page[8], page[9]]) as usize;
+        let value_size: usize = u16::from_le_bytes([page[10], page[11]]) as usize;
+        if key_size != K::SIZE || value_size != V::SIZE {
+            return Err(PageError::BadFormat(format!(
+                "file holds {}-byte keys and {}-byte values, expected {} and {}",
+                key_size, value_size, K::SIZE, V::SIZE,
+            )));
+        }
+        self.root = u32::from_le_bytes(page[12..16].try_into().unwrap());
+        self.len = u64::from_le_bytes(page[16..24].try_into().unwrap());
+        Ok(())
+    }
+
+    fn _write_meta(&mut self) -> Result<(), PageError> {
+        let (root, len) = (self.root, self.len);
+        let page: &mut Page = self.pool.page_mut(META_PAGE)?;
+        page.fill(0);
+        page[4..8].copy_from_slice(MAGIC);
+        page[8..10].copy_from_slice(&(K::SIZE as u16).to_le_bytes());
+        page[10..12].copy_from_slice(&(V::SIZE as u16).to_le_bytes());
+        page[12..16].copy_from_slice(&root.to_le_bytes());
+        page[16..24].copy_from_slice(&len.to_le_bytes());
+        Ok(())
+    }
+}
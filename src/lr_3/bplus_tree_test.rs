@@ -0,0 +1,140 @@
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+    use std::fs::{self, OpenOptions};
+    use std::io::{Seek, SeekFrom, Write};
+    use crate::common::test_utils::temp_file;
+    use crate::lr_3::bplus_tree::BPlusTree;
+    use crate::common::crc32;
+    use crate::lr_3::pager::{PageError, PAGE_SIZE};
+
+    #[test]
+    fn test_crc32() {
+        assert_eq!(crc32(b""), 0);
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+    }
+
+    #[test]
+    fn test_bplus_tree() {
+        let path = temp_file("bplus_tree.db");
+        let mut tree: BPlusTree<u64, u64> = BPlusTree::open(&path).unwrap();
+        assert!(tree.is_empty());
+        assert_eq!(tree.get(&1).unwrap(), None);
+        assert_eq!(tree.range(..).unwrap(), vec![]);
+        for key in (0..5000).rev() {
+            assert_eq!(tree.insert(key, key * 10).unwrap(), None);
+        }
+        assert_eq!(tree.insert(7, 700).unwrap(), Some(70));
+        assert_eq!(tree.len(), 5000);
+        assert!(tree.get_height().unwrap() >= 2);
+        assert_eq!(tree.get(&7).unwrap(), Some(700));
+        assert_eq!(tree.get(&4999).unwrap(), Some(49990));
+        assert!(!tree.contain(&5000).unwrap());
+        assert_eq!(tree.range(10..13).unwrap(), vec![(10, 100), (11, 110), (12, 120)]);
+        assert_eq!(tree.range(4998..).unwrap(), vec![(4998, 49980), (4999, 49990)]);
+        assert_eq!(tree.range(..).unwrap().len(), 5000);
+
+        for key in (0..5000).step_by(2) {
+            assert_eq!(tree.remove(&key).unwrap(), Some(key * 10));
+        }
+        assert_eq!(tree.remove(&0).unwrap(), None);
+        assert_eq!(tree.len(), 2500);
+        assert_eq!(tree.range(..6).unwrap(), vec![(1, 10), (3, 30), (5, 50)]);
+        tree.close().unwrap();
+
+        let mut tree: BPlusTree<u64, u64> = BPlusTree::open(&path).unwrap();
+        assert_eq!(tree.len(), 2500);
+        assert_eq!(tree.get(&7).unwrap(), Some(700));
+        assert_eq!(tree.get(&8).unwrap(), None);
+        assert_eq!(tree.range(..).unwrap().len(), 2500);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bplus_tree_small_pool_matches_btreemap() {
+        let path = temp_file("bplus_tree_pool.db");
+        let mut tree: BPlusTree<i32, u32> = BPlusTree::open_with_pool(&path, 3).unwrap();
+        let mut model: BTreeMap<i32, u32> = BTreeMap::new();
+        let mut state: u64 = 42;
+        for step in 0..6000u32 {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            let key = (state % 4000) as i32 - 2000;
+            match state % 5 {
+                0 => assert_eq!(tree.remove(&key).unwrap(), model.remove(&key)),
+                _ => assert_eq!(tree.insert(key, step).unwrap(), model.insert(key, step)),
+            }
+        }
+        assert_eq!(tree.range(..).unwrap(), model.clone().into_iter().collect::<Vec<(i32, u32)>>());
+        assert_eq!(
+            tree.range(-100..=100).unwrap(),
+            model.range(-100..=100).map(|(key, value)| (*key, *value)).collect::<Vec<(i32, u32)>>(),
+        );
+        tree.flush().unwrap();
+        drop(tree);
+
+        let mut tree: BPlusTree<i32, u32> = BPlusTree::open_with_pool(&path, 3).unwrap();
+        assert_eq!(tree.len() as usize, model.len());
+        for (key, value) in &model {
+            assert_eq!(tree.get(key).unwrap(), Some(*value));
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bplus_tree_detects_torn_page() {
+        let path = temp_file("bplus_tree_torn.db");
+        let mut tree: BPlusTree<u32, u32> = BPlusTree::open(&path).unwrap();
+        for key in 0..2000 {
+            tree.insert(key, key).unwrap();
+        }
+        let pages = tree.page_count();
+        tree.close().unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), pages as u64 * PAGE_SIZE as u64);
+
+        let mut file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.seek(SeekFrom::Start(2 * PAGE_SIZE as u64 + 100)).unwrap();
+        file.write_all(&[0xAB; 16]).unwrap();
+        drop(file);
+        match BPlusTree::<u32, u32>::open(&path) {
+            Err(PageError::TornPage(2)) => {}
+            Err(error) => panic!("unexpected error: {}", error),
+            Ok(_) => panic!("the torn page was not detected"),
+        }
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bplus_tree_rejects_impossible_key_count() {
+        let path = temp_file("bplus_tree_count.db");
+        let mut tree: BPlusTree<u32, u32> = BPlusTree::open(&path).unwrap();
+        for key in 0..2000 {
+            tree.insert(key, key).unwrap();
+        }
+        let pages = tree.page_count();
+        tree.close().unwrap();
+
+        // The key count is broken on every node page, and the checksums are
+        // resealed, so only the bounds check can notice.
+        let mut bytes: Vec<u8> = fs::read(&path).unwrap();
+        for page in bytes.chunks_mut(PAGE_SIZE).skip(1).take(pages as usize - 1) {
+            page[5..7].copy_from_slice(&u16::MAX.to_le_bytes());
+            let checksum: u32 = crc32(&page[4..]);
+            page[..4].copy_from_slice(&checksum.to_le_bytes());
+        }
+        fs::write(&path, &bytes).unwrap();
+        let mut tree: BPlusTree<u32, u32> = BPlusTree::open(&path).unwrap();
+        assert!(matches!(tree.get(&7), Err(PageError::BadFormat(_))));
+        drop(tree);
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bplus_tree_rejects_other_layout() {
+        let path = temp_file("bplus_tree_layout.db");
+        BPlusTree::<u32, u32>::open(&path).unwrap().close().unwrap();
+        assert!(matches!(BPlusTree::<u64, u32>::open(&path), Err(PageError::BadFormat(_))));
+        fs::remove_file(&path).unwrap();
+    }
+}
\ No newline at end of file
@@ -1,8 +1,12 @@
 use std::cell::RefCell;
 use std::rc::Rc;
-use std::fmt::Debug;
-use std::cmp::{Ord, Ordering};
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::cmp::{max, Ord, Ordering};
+use std::ops::{Bound, RangeBounds};
+use std::str::FromStr;
 use crate::lr_3::base::{Tree, TreeNode};
+use crate::common::{BinaryNode, BinaryTree, DecodeError, FixedSize, Shape, TreeDiff};
 
 
 type RcRefcellBSNode<T>= Rc<RefCell<BSTreeNode<T>>>;
@@ -10,12 +14,43 @@ type OptionNode<T>= Option<RcRefcellBSNode<T>>;
 
 pub struct BSTreeNode<T: Ord + Copy + Debug> {
     key: T,
+    /// How many times the key was inserted; always 1 outside multiset mode.
+    count: usize,
     left: OptionNode<T>,
     right: OptionNode<T>,
 }
 
+/// Which neighbour takes the place of a deleted node that has two children.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DeletePolicy {
+    /// The smallest key of the right subtree.
+    Successor,
+    /// The largest key of the left subtree.
+    Predecessor,
+}
+
+/// Heights of a tree, or of the rebuilt subtree, around a rebalance.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RebalanceReport {
+    pub height_before: u32,
+    pub height_after: u32,
+    /// The number of nodes that were rearranged.
+    pub nodes: usize,
+}
+
 pub struct BSTree<T: Ord + Copy + Debug> {
-    root: OptionNode<T>
+    root: OptionNode<T>,
+    policy: DeletePolicy,
+    /// The number of nodes.
+    size: usize,
+    /// The number of elements, duplicates included.
+    elements: usize,
+    multiset: bool,
+    /// Balance factor of the scapegoat mode, `None` when it is off.
+    scapegoat: Option<f64>,
+    /// The largest size since the last full rebuild in scapegoat mode.
+    max_size: usize,
+    last_rebalance: Option<RebalanceReport>,
 }
 
 impl<T: Ord + Copy + Debug> TreeNode<T> for BSTreeNode<T> {
@@ -32,93 +67,46 @@ impl<T: Ord + Copy + Debug> TreeNode<T> for BSTreeNode<T> {
     }
 }
 
-impl <T: Ord + Copy + Debug> BSTreeNode<T> {
-    fn new(value: T) -> BSTreeNode<T> {
-        BSTreeNode {
-            key: value,
-            left: None,
-            right: None,
-        }
+impl<T: Ord + Copy + Debug> BinaryNode<T> for BSTreeNode<T> {
+    fn left_child(&self) -> &OptionNode<T> {
+        &self.left
     }
 
-    fn insert(&mut self, value: T) {
-        if self.key == value {
-            println!("The node already exists.");
-            return
-        }
-        let new_node =
-            if value < self.key {&mut self.left}
-            else {&mut self.right};
-        match new_node {
-            Some(node) => node.borrow_mut().insert(value),
-            None => {
-                *new_node = Some(Rc::new(RefCell::new(BSTreeNode::new(value))));
-            }
-        }
+    fn right_child(&self) -> &OptionNode<T> {
+        &self.right
     }
 
-    fn delete(&mut self, value: T) {
-        match self.key.cmp(&value) {
-            Ordering::Greater => self._delete_left(value),
-            Ordering::Less => self._delete_right(value),
-            _ => unreachable!(),
-        }
-    }
-
-    fn _delete_node_have_two_children(left: &RcRefcellBSNode<T>) {
-        let right_min = left.borrow().right.as_ref().unwrap().borrow().get_min();
-        left.borrow_mut().delete(right_min);
-        left.borrow_mut().key = right_min;
-    }
-
-    fn _delete_right(&mut self, value: T) {
-        if let Some(right) = self.right.as_ref() {
-            if right.borrow().key == value {
-                if right.borrow().left.is_none() && right.borrow().right.is_none() {
-                    self.right = None;
-                } else if right.borrow().left.is_none() && !right.borrow().right.is_none() {
-                    self.right.take().map(|node| {
-                        self.right = node.borrow().right.clone()
-                    });
-                } else if !right.borrow().left.is_none() && right.borrow().right.is_none() {
-                    self.right.take().map(|node| {
-                        self.right = node.borrow().left.clone()
-                    });
-                } else {
-                    Self::_delete_node_have_two_children(right);
-                }
-            } else {
-                right.borrow_mut().delete(value);
-            }
-        }
+    fn node_key(&self) -> T {
+        self.key
     }
+}
 
-    fn _delete_left(&mut self, value: T) {
-        if let Some(left) = self.left.as_ref() {
-            if left.borrow().key == value {
-                if left.borrow().left.is_none() && left.borrow().right.is_none() {
-                    self.left = None;
-                } else if left.borrow().left.is_none() && !left.borrow().right.is_none() {
-                    self.left.take().map(|node| {
-                        self.left = node.borrow().right.clone()
-                    });
-                } else if !left.borrow().left.is_none() && left.borrow().right.is_none() {
-                    self.left.take().map(|node| {
-                        self.left = node.borrow().left.clone()
-                    });
-                } else {
-                    Self::_delete_node_have_two_children(left);
-                }
-            } else {
-                left.borrow_mut().delete(value);
-            }
+impl<T: Ord + Copy + Debug> BinaryTree<T> for BSTree<T> {
+    type Node = BSTreeNode<T>;
+
+    fn root_node(&self) -> &OptionNode<T> {
+        &self.root
+    }
+}
+
+impl <T: Ord + Copy + Debug> BSTreeNode<T> {
+    fn new(value: T) -> BSTreeNode<T> {
+        BSTreeNode {
+            key: value,
+            count: 1,
+            left: None,
+            right: None,
         }
     }
 
     fn print_node(&self, prefix_space: &String, child_prefix: String, is_right: bool) {
+        let count: String = match self.count {
+            1 => String::new(),
+            count => format!(" x{}", count),
+        };
         match prefix_space.len() {
-            6 => println!("|____ {} {:?}", child_prefix, self.key),
-            _ => println!("{}{} {:?}", prefix_space, child_prefix, self.key),
+            6 => println!("|____ {} {:?}{}", child_prefix, self.key, count),
+            _ => println!("{}{} {:?}{}", prefix_space, child_prefix, self.key, count),
         }
         let mut new_prefix_space: String = String::from(prefix_space);
         match is_right {
@@ -159,40 +147,64 @@ impl <T: Ord + Copy + Debug> Tree<T, BSTreeNode<T>> for BSTree<T> {
     }
 
     fn insert(&mut self, value: T) {
-        if self.root.is_none() {
-            self.root = Some(Rc::new(RefCell::new(BSTreeNode::new(value))));
-        } else {
-            self.root.as_ref().unwrap().borrow_mut().insert(value);
-        }
-    }
-
-    fn delete(&mut self, value:T){
-        if self.root.is_none()||!self.contain(value) {
-            println!("The node of value {:#?} doesn't exist.",value);
-            return ;
-        } else {
-            if let Some(root) = self.root.as_ref() {
-                if root.borrow().key == value {
-                    if root.borrow().left.is_none() && root.borrow().right.is_none() {
-                        self.root = None;
-                    } else if root.borrow().left.is_none() && !root.borrow().right.is_none() {
-                        self.root.take().map(|node| {
-                            self.root = node.borrow().right.clone()
-                        });
-                    } else if !root.borrow().left.is_none() && root.borrow().right.is_none() {
-                        self.root.take().map(|node| {
-                            self.root = node.borrow().left.clone()
-                        });
-                    } else {
-                        BSTreeNode::_delete_node_have_two_children(root);
+        let mut current: RcRefcellBSNode<T> = match self.root.as_ref() {
+            None => {
+                self.root = Some(Rc::new(RefCell::new(BSTreeNode::new(value))));
+                self.elements += 1;
+                self._grow();
+                return;
+            }
+            Some(root) => Rc::clone(root),
+        };
+        // Ancestors of the new node, only tracked in scapegoat mode.
+        let mut path: Vec<RcRefcellBSNode<T>> = Vec::new();
+        loop {
+            if self.scapegoat.is_some() {
+                path.push(Rc::clone(&current));
+            }
+            let next: RcRefcellBSNode<T> = {
+                let mut node = current.borrow_mut();
+                let child: &mut OptionNode<T> = match value.cmp(&node.key) {
+                    Ordering::Less => &mut node.left,
+                    Ordering::Greater => &mut node.right,
+                    Ordering::Equal => {
+                        match self.multiset {
+                            true => {
+                                node.count += 1;
+                                self.elements += 1;
+                            }
+                            false => println!("The node already exists."),
+                        }
+                        return;
+                    }
+                };
+                match child {
+                    Some(next) => Rc::clone(next),
+                    None => {
+                        *child = Some(Rc::new(RefCell::new(BSTreeNode::new(value))));
+                        break;
                     }
-                } else {
-                    root.borrow_mut().delete(value);
                 }
+            };
+            current = next;
+        }
+        self.elements += 1;
+        self._grow();
+        if let Some(alpha) = self.scapegoat {
+            // The new node sits one level below the deepest tracked ancestor.
+            let bound: f64 = (self.size as f64).ln() / (1.0 / alpha).ln();
+            if path.len() as f64 > bound {
+                self._rebuild_scapegoat(path, value, alpha);
             }
         }
     }
 
+    fn delete(&mut self, value: T) {
+        if self.remove(value).is_none() {
+            println!("The node of value {:#?} doesn't exist.", value);
+        }
+    }
+
     fn print_tree(&self) {
         match &self.get_root() {
             None => println!("This tree is empty!"),
@@ -203,17 +215,1141 @@ impl <T: Ord + Copy + Debug> Tree<T, BSTreeNode<T>> for BSTree<T> {
             ),
         }
     }
+    // The metrics below go through `fold`, whose explicit stack keeps
+    // degenerate trees from overflowing the call stack.
+    fn get_height(&self) -> u32 {
+        self.fold(0, |_, left, right| max(left, right) + 1)
+    }
+
+    fn count_leaves(&self) -> u32 {
+        // (nodes, leaves) of every subtree
+        let (_, leaves): (u32, u32) = self.fold((0, 0), |_, left, right| {
+            match left.0 + right.0 {
+                0 => (1, 1),
+                nodes => (nodes + 1, left.1 + right.1),
+            }
+        });
+        leaves
+    }
+
+    fn count_nodes(&self) -> u32 {
+        self.fold(0, |_, left, right| left + right + 1)
+    }
 }
 
-impl<T:Ord + Copy +Debug> BSTree<T> {
+impl<T: Ord + Copy + Debug> BSTree<T> {
     pub fn new() -> Self {
+        Self::with_policy(DeletePolicy::Successor)
+    }
+
+    /// Makes a new empty BSTree that replaces deleted two-child nodes
+    /// according to `policy`.
+    pub fn with_policy(policy: DeletePolicy) -> Self {
         BSTree {
-            root:None,
+            root: None,
+            policy,
+            size: 0,
+            elements: 0,
+            multiset: false,
+            scapegoat: None,
+            max_size: 0,
+            last_rebalance: None,
+        }
+    }
+
+    /// Makes a new empty BSTree in multiset mode: inserting a key that is
+    /// already present bumps its count instead of being refused, and
+    /// `remove` and `delete` take away one occurrence at a time.
+    ///
+    /// Every key still takes a single node, so `count_nodes` counts
+    /// distinct keys while `len` counts every occurrence. Shapes and the
+    /// text and binary forms also keep one entry per key, together with its
+    /// count (`key*count` in text), so a loaded tree is again a multiset
+    /// with the same counts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::btree::BSTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = BSTree::new_multiset();
+    /// for i in [2, 1, 2, 3, 2] {
+    ///     tree.insert(i);
+    /// }
+    /// assert_eq!(tree.count(2), 3);
+    /// assert_eq!(tree.len(), 5);
+    /// assert_eq!(tree.count_nodes(), 3);
+    /// assert_eq!(tree.iter().collect::<Vec<i32>>(), vec![1, 2, 2, 2, 3]);
+    /// ```
+    pub fn new_multiset() -> Self {
+        let mut tree: BSTree<T> = BSTree::new();
+        tree.multiset = true;
+        tree
+    }
+
+    pub fn is_multiset(&self) -> bool {
+        self.multiset
+    }
+
+    /// Makes a new empty BSTree in scapegoat mode, see `set_scapegoat`.
+    pub fn with_scapegoat(alpha: f64) -> Self {
+        let mut tree: BSTree<T> = BSTree::new();
+        tree.set_scapegoat(Some(alpha));
+        tree
+    }
+
+    /// Turns the scapegoat mode on with balance factor `alpha`, or off.
+    ///
+    /// In scapegoat mode an insert that lands deeper than
+    /// `log(len) / log(1 / alpha)` rebuilds the highest ancestor whose
+    /// subtree is out of balance, and removals that shrink the tree below
+    /// `alpha` times its peak size rebuild the whole tree. Smaller `alpha`
+    /// keeps the tree flatter at the cost of more frequent rebuilds.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `alpha` is not strictly between 0.5 and 1.
+    pub fn set_scapegoat(&mut self, alpha: Option<f64>) {
+        if let Some(alpha) = alpha {
+            assert!(alpha > 0.5 && alpha < 1.0, "The scapegoat balance factor must lie in (0.5, 1).");
+        }
+        self.scapegoat = alpha;
+        self.max_size = self.size;
+    }
+
+    pub fn get_scapegoat(&self) -> Option<f64> {
+        self.scapegoat
+    }
+
+    /// The number of elements in the tree, kept up to date by every
+    /// insert and removal. In multiset mode every occurrence counts.
+    pub fn len(&self) -> usize {
+        self.elements
+    }
+
+    /// How many times `value` is stored: 0 or 1 outside multiset mode.
+    pub fn count(&self, value: T) -> usize {
+        self._find(value).map_or(0, |node| node.borrow().count)
+    }
+
+    /// Every key with the number of times it is stored, in key order.
+    pub fn counts(&self) -> Vec<(T, usize)> {
+        let mut counts: Vec<(T, usize)> = Vec::new();
+        let mut stack: Vec<RcRefcellBSNode<T>> = Vec::new();
+        let mut current: OptionNode<T> = self.root.clone();
+        while current.is_some() || !stack.is_empty() {
+            while let Some(node) = current {
+                current = node.borrow().left.clone();
+                stack.push(node);
+            }
+            if let Some(node) = stack.pop() {
+                let node = node.borrow();
+                counts.push((node.key, node.count));
+                current = node.right.clone();
+            }
+        }
+        counts
+    }
+
+    /// The keys in order, each repeated as many times as it is stored.
+    pub fn iter(&self) -> impl Iterator<Item = T> {
+        self.counts().into_iter().flat_map(|(key, count)| (0..count).map(move |_| key))
+    }
+
+    /// Takes away one occurrence of `value`.
+    ///
+    /// Returns `false` if the value is missing.
+    pub fn remove_one(&mut self, value: T) -> bool {
+        self.remove(value).is_some()
+    }
+
+    /// Takes away every occurrence of `value`.
+    ///
+    /// Returns how many there were.
+    pub fn remove_all(&mut self, value: T) -> usize {
+        let count: usize = match self._find(value) {
+            None => return 0,
+            Some(node) => std::mem::replace(&mut node.borrow_mut().count, 1),
+        };
+        self.elements -= count - 1;
+        self.remove(value);
+        count
+    }
+
+    fn _find(&self, value: T) -> OptionNode<T> {
+        let mut current: OptionNode<T> = self.root.clone();
+        while let Some(node) = current {
+            let key: T = node.borrow().key;
+            current = match value.cmp(&key) {
+                Ordering::Less => node.borrow().left.clone(),
+                Ordering::Greater => node.borrow().right.clone(),
+                Ordering::Equal => return Some(node),
+            };
+        }
+        None
+    }
+
+    /// The heights around the most recent rebalance, whether it was
+    /// requested through `rebalance` or triggered by the scapegoat mode.
+    /// For a scapegoat rebuild the heights are those of the rebuilt subtree.
+    pub fn last_rebalance(&self) -> Option<RebalanceReport> {
+        self.last_rebalance
+    }
+
+    /// Rebuilds the tree into a balanced shape in O(n) time, reusing the
+    /// existing nodes.
+    ///
+    /// Returns the height of the tree before and after.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::btree::BSTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = BSTree::new();
+    /// for i in 0..7 {
+    ///     tree.insert(i);
+    /// }
+    /// let report = tree.rebalance();
+    /// assert_eq!(report.height_before, 7);
+    /// assert_eq!(report.height_after, 3);
+    /// ```
+    pub fn rebalance(&mut self) -> RebalanceReport {
+        let root: OptionNode<T> = self.root.take();
+        let (root, report) = Self::_rebuild(root);
+        self.root = root;
+        self.max_size = self.size;
+        self.last_rebalance = Some(report);
+        report
+    }
+
+    fn _grow(&mut self) {
+        self.size += 1;
+        self.max_size = self.max_size.max(self.size);
+    }
+
+    /// Walks up from the node just inserted below `path` and rebuilds the
+    /// first ancestor whose child on the path holds more than `alpha` of its
+    /// subtree.
+    fn _rebuild_scapegoat(&mut self, path: Vec<RcRefcellBSNode<T>>, value: T, alpha: f64) {
+        let mut child_size: usize = 1;
+        for index in (0..path.len()).rev() {
+            let sibling: OptionNode<T> = {
+                let node = path[index].borrow();
+                if value < node.key { node.right.clone() } else { node.left.clone() }
+            };
+            let size: usize = 1 + child_size + Self::_subtree_size(&sibling);
+            if child_size as f64 > alpha * size as f64 {
+                let (subtree, report) = Self::_rebuild(Some(Rc::clone(&path[index])));
+                let link: Option<(RcRefcellBSNode<T>, bool)> = match index {
+                    0 => None,
+                    _ => {
+                        let parent: &RcRefcellBSNode<T> = &path[index - 1];
+                        let is_left: bool = value < parent.borrow().key;
+                        Some((Rc::clone(parent), is_left))
+                    }
+                };
+                self._replace_link(link, subtree);
+                self.last_rebalance = Some(report);
+                return;
+            }
+            child_size = size;
+        }
+    }
+
+    /// Relinks the nodes of a subtree into a perfectly balanced shape.
+    fn _rebuild(root: OptionNode<T>) -> (OptionNode<T>, RebalanceReport) {
+        let height_before: u32 = Self::_subtree_height(&root);
+        let nodes: Vec<RcRefcellBSNode<T>> = Self::_inorder_nodes(root);
+        let root: OptionNode<T> = Self::_build_balanced(&nodes);
+        let report: RebalanceReport = RebalanceReport {
+            height_before,
+            height_after: Self::_subtree_height(&root),
+            nodes: nodes.len(),
+        };
+        (root, report)
+    }
+
+    fn _build_balanced(nodes: &[RcRefcellBSNode<T>]) -> OptionNode<T> {
+        if nodes.is_empty() {
+            return None;
+        }
+        let middle: usize = nodes.len() / 2;
+        let left: OptionNode<T> = Self::_build_balanced(&nodes[..middle]);
+        let right: OptionNode<T> = Self::_build_balanced(&nodes[middle + 1..]);
+        {
+            let mut node = nodes[middle].borrow_mut();
+            node.left = left;
+            node.right = right;
+        }
+        Some(Rc::clone(&nodes[middle]))
+    }
+
+    /// Detaches the nodes of a subtree and returns them in key order.
+    fn _inorder_nodes(root: OptionNode<T>) -> Vec<RcRefcellBSNode<T>> {
+        let mut nodes: Vec<RcRefcellBSNode<T>> = Vec::new();
+        let mut stack: Vec<RcRefcellBSNode<T>> = Vec::new();
+        let mut current: OptionNode<T> = root;
+        while current.is_some() || !stack.is_empty() {
+            while let Some(node) = current {
+                current = node.borrow_mut().left.take();
+                stack.push(node);
+            }
+            if let Some(node) = stack.pop() {
+                current = node.borrow_mut().right.take();
+                nodes.push(node);
+            }
+        }
+        nodes
+    }
+
+    fn _subtree_size(root: &OptionNode<T>) -> usize {
+        let mut size: usize = 0;
+        let mut stack: Vec<RcRefcellBSNode<T>> = root.iter().cloned().collect();
+        while let Some(node) = stack.pop() {
+            size += 1;
+            let node = node.borrow();
+            stack.extend(node.left.clone());
+            stack.extend(node.right.clone());
+        }
+        size
+    }
+
+    /// The height of a subtree, measured level by level so that degenerate
+    /// trees do not recurse.
+    fn _subtree_height(root: &OptionNode<T>) -> u32 {
+        let mut height: u32 = 0;
+        let mut level: Vec<RcRefcellBSNode<T>> = root.iter().cloned().collect();
+        while !level.is_empty() {
+            height += 1;
+            let mut next_level: Vec<RcRefcellBSNode<T>> = Vec::new();
+            for node in level {
+                let node = node.borrow();
+                next_level.extend(node.left.clone());
+                next_level.extend(node.right.clone());
+            }
+            level = next_level;
+        }
+        height
+    }
+
+    pub fn get_policy(&self) -> DeletePolicy {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: DeletePolicy) {
+        self.policy = policy;
+    }
+
+    /// Remove the element with the target value. In multiset mode only one
+    /// occurrence is removed, and the node goes once its count drops to 0.
+    ///
+    /// The tree is walked iteratively, so degenerate trees of any depth are
+    /// handled without recursion. A node with two children takes the key of
+    /// its inorder successor or predecessor, depending on the tree's policy.
+    ///
+    /// Returns the removed key, or `None` if the value is missing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::btree::BSTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = BSTree::new();
+    /// tree.insert(2);
+    /// tree.insert(1);
+    /// tree.insert(3);
+    ///
+    /// assert_eq!(tree.remove(2), Some(2));
+    /// assert_eq!(tree.remove(2), None);
+    /// assert_eq!(tree.count_nodes(), 2);
+    /// ```
+    pub fn remove(&mut self, value: T) -> Option<T> {
+        // (parent, is the node the parent's left child)
+        let mut link: Option<(RcRefcellBSNode<T>, bool)> = None;
+        let mut current: RcRefcellBSNode<T> = Rc::clone(self.root.as_ref()?);
+        loop {
+            let key: T = current.borrow().key;
+            let go_left: bool = match value.cmp(&key) {
+                Ordering::Equal => break,
+                Ordering::Less => true,
+                Ordering::Greater => false,
+            };
+            let next: RcRefcellBSNode<T> = {
+                let node = current.borrow();
+                let child: &OptionNode<T> = if go_left { &node.left } else { &node.right };
+                Rc::clone(child.as_ref()?)
+            };
+            link = Some((current, go_left));
+            current = next;
+        }
+
+        let removed: T = current.borrow().key;
+        self.elements -= 1;
+        if current.borrow().count > 1 {
+            current.borrow_mut().count -= 1;
+            return Some(removed);
+        }
+        let (left, right): (OptionNode<T>, OptionNode<T>) = {
+            let node = current.borrow();
+            (node.left.clone(), node.right.clone())
+        };
+        match (left, right) {
+            (Some(left), Some(right)) => {
+                let (key, count): (T, usize) = match self.policy {
+                    DeletePolicy::Successor => Self::_detach_extreme(&current, right, true),
+                    DeletePolicy::Predecessor => Self::_detach_extreme(&current, left, false),
+                };
+                let mut node = current.borrow_mut();
+                node.key = key;
+                node.count = count;
+            }
+            (child, None) | (None, child) => self._replace_link(link, child),
+        }
+        self.size -= 1;
+        if let Some(alpha) = self.scapegoat {
+            if (self.size as f64) < alpha * self.max_size as f64 {
+                self.rebalance();
+            }
+        }
+        Some(removed)
+    }
+
+    /// Unlinks the leftmost (`leftmost == true`) or rightmost node of the
+    /// subtree `start`, which hangs off `owner` on the opposite side, and
+    /// returns its key and count.
+    fn _detach_extreme(owner: &RcRefcellBSNode<T>, start: RcRefcellBSNode<T>, leftmost: bool) -> (T, usize) {
+        let mut parent: RcRefcellBSNode<T> = Rc::clone(owner);
+        let mut current: RcRefcellBSNode<T> = start;
+        let mut from_owner: bool = true;
+        loop {
+            let next: OptionNode<T> = {
+                let node = current.borrow();
+                if leftmost { node.left.clone() } else { node.right.clone() }
+            };
+            match next {
+                Some(next) => {
+                    parent = current;
+                    current = next;
+                    from_owner = false;
+                }
+                None => break,
+            }
+        }
+        let (key, count, rest): (T, usize, OptionNode<T>) = {
+            let mut node = current.borrow_mut();
+            let rest = if leftmost { node.right.take() } else { node.left.take() };
+            (node.key, node.count, rest)
+        };
+        let mut parent_node = parent.borrow_mut();
+        // The extreme node is the first step away from the owner, or lies
+        // on the leftmost/rightmost spine below it.
+        match (from_owner, leftmost) {
+            (true, true) | (false, false) => parent_node.right = rest,
+            (true, false) | (false, true) => parent_node.left = rest,
+        }
+        (key, count)
+    }
+
+    fn _replace_link(&mut self, link: Option<(RcRefcellBSNode<T>, bool)>, child: OptionNode<T>) {
+        match link {
+            None => self.root = child,
+            Some((parent, true)) => parent.borrow_mut().left = child,
+            Some((parent, false)) => parent.borrow_mut().right = child,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        let mut tree: BSTree<T> = BSTree::with_policy(self.policy);
+        tree.scapegoat = self.scapegoat;
+        tree.multiset = self.multiset;
+        *self = tree;
+        println!("Clear operation is complete!");
+    }
+}
+
+impl<T: Ord + Copy + Debug> BSTree<T> {
+    /// The shape of the tree, see `Shape`.
+    pub fn to_shape(&self) -> Shape<T> {
+        let mut shape: Shape<T> = Shape::new(false);
+        shape.multiset = self.multiset;
+        let mut stack: Vec<(RcRefcellBSNode<T>, _)> =
+            self.root.iter().map(|root| (Rc::clone(root), None::<(usize, bool)>)).collect();
+        while let Some((node, parent)) = stack.pop() {
+            let node = node.borrow();
+            let index: usize = shape.add_node(parent, node.key, node.count, false);
+            if let Some(right) = node.right.as_ref() {
+                stack.push((Rc::clone(right), Some((index, false))));
+            }
+            if let Some(left) = node.left.as_ref() {
+                stack.push((Rc::clone(left), Some((index, true))));
+            }
+        }
+        shape
+    }
+
+    /// Rebuilds a tree of exactly the given shape, in multiset mode if the
+    /// shape is a multiset one. Colours are ignored.
+    ///
+    /// Returns `DecodeError::OutOfOrder` if the keys do not form a binary
+    /// search tree, and `DecodeError::BadCount` if a count is impossible.
+    pub fn from_shape(shape: &Shape<T>) -> Result<Self, DecodeError> {
+        shape.check_order()?;
+        let nodes: Vec<RcRefcellBSNode<T>> = shape.nodes
+            .iter()
+            .map(|node| {
+                let mut tree_node: BSTreeNode<T> = BSTreeNode::new(node.key);
+                tree_node.count = node.count;
+                Rc::new(RefCell::new(tree_node))
+            })
+            .collect();
+        for (index, node) in shape.nodes.iter().enumerate() {
+            let mut tree_node = nodes[index].borrow_mut();
+            tree_node.left = node.left.map(|left| Rc::clone(&nodes[left]));
+            tree_node.right = node.right.map(|right| Rc::clone(&nodes[right]));
+        }
+        let mut tree: BSTree<T> = match shape.multiset {
+            true => BSTree::new_multiset(),
+            false => BSTree::new(),
+        };
+        tree.root = nodes.first().cloned();
+        tree.size = nodes.len();
+        tree.elements = shape.nodes.iter().map(|node| node.count).sum();
+        tree.max_size = nodes.len();
+        Ok(tree)
+    }
+
+    /// Checks whether both trees branch the same way, whatever their keys.
+    pub fn same_shape(&self, other: &BSTree<T>) -> bool {
+        self.to_shape().same_shape(&other.to_shape())
+    }
+
+    /// Checks whether both trees hold the same keys in the same places,
+    /// stored as many times each. `==` only compares the keys.
+    pub fn structural_eq(&self, other: &BSTree<T>) -> bool {
+        self.to_shape().structural_eq(&other.to_shape())
+    }
+
+    /// The keys inserted and removed from this tree to `other`, and the
+    /// keys of both whose children or counts differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::btree::BSTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut before = BSTree::new();
+    /// for i in [2, 1, 3] {
+    ///     before.insert(i);
+    /// }
+    /// let mut after = BSTree::new();
+    /// for i in [2, 1, 4] {
+    ///     after.insert(i);
+    /// }
+    /// let diff = before.diff(&after);
+    /// assert_eq!(diff.inserted, vec![4]);
+    /// assert_eq!(diff.removed, vec![3]);
+    /// // 2 has another right child.
+    /// assert_eq!(diff.changed, vec![2]);
+    /// ```
+    pub fn diff(&self, other: &BSTree<T>) -> TreeDiff<T> {
+        self.to_shape().diff(&other.to_shape())
+    }
+}
+
+/// Trees are equal when they hold the same keys, as many times each,
+/// however they are shaped.
+impl<T: Ord + Copy + Debug> PartialEq for BSTree<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.elements == other.elements && self.counts() == other.counts()
+    }
+}
+
+impl<T: Ord + Copy + Debug> Eq for BSTree<T> {}
+
+impl<T: Ord + Copy + Debug + Hash> Hash for BSTree<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.counts().hash(state);
+    }
+}
+
+impl<T: Ord + Copy + Debug + Display + FromStr> BSTree<T> {
+    /// Preorder text with `#` null markers, e.g. `2,1,#,#,3,#,#`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::btree::BSTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = BSTree::new();
+    /// for i in [2, 1, 3] {
+    ///     tree.insert(i);
+    /// }
+    /// assert_eq!(tree.to_preorder_text(), "2,1,#,#,3,#,#");
+    /// assert_eq!(tree.to_level_order_text(), "[2,1,3]");
+    /// let copy = BSTree::<i32>::from_level_order_text("[2,1,3]").unwrap();
+    /// assert_eq!(copy.to_preorder_text(), "2,1,#,#,3,#,#");
+    /// ```
+    pub fn to_preorder_text(&self) -> String {
+        self.to_shape().to_preorder_text()
+    }
+
+    pub fn from_preorder_text(text: &str) -> Result<Self, DecodeError> {
+        Self::from_shape(&Shape::from_preorder_text(text, false)?)
+    }
+
+    /// Level-order text in the LeetCode style, e.g. `[2,1,3,null,4]`.
+    pub fn to_level_order_text(&self) -> String {
+        self.to_shape().to_level_order_text()
+    }
+
+    pub fn from_level_order_text(text: &str) -> Result<Self, DecodeError> {
+        Self::from_shape(&Shape::from_level_order_text(text, false)?)
+    }
+}
+
+impl<T: Ord + Copy + Debug + FixedSize> BSTree<T> {
+    /// The compact binary form, see `Shape::to_bytes`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        self.to_shape().to_bytes()
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DecodeError> {
+        Self::from_shape(&Shape::from_bytes(bytes)?)
+    }
+}
+
+impl<T: Ord + Copy + Debug> Drop for BSTree<T> {
+    /// Tears the tree down iteratively so that dropping a degenerate tree
+    /// does not recurse once per level.
+    fn drop(&mut self) {
+        let mut stack: Vec<RcRefcellBSNode<T>> = self.root.take().into_iter().collect();
+        while let Some(node) = stack.pop() {
+            // Subtrees still shared with a caller are left to their owner.
+            if let Ok(cell) = Rc::try_unwrap(node) {
+                let mut node: BSTreeNode<T> = cell.into_inner();
+                stack.extend(node.left.take());
+                stack.extend(node.right.take());
+            }
+        }
+    }
+}
+
+type RcRefcellBNode<T> = Rc<RefCell<BTreeNode<T>>>;
+
+/// BTreeNode is a node of the BTree.
+/// keys are kept sorted; an internal node has one child more than keys,
+/// a leaf has no children.
+pub struct BTreeNode<T: Ord + Copy + Debug> {
+    keys: Vec<T>,
+    children: Vec<RcRefcellBNode<T>>,
+}
+
+/// A B-tree is a self-balancing search tree whose nodes hold between
+/// `min_degree - 1` and `2 * min_degree - 1` sorted keys (the root may hold
+/// fewer), so it stays wide and shallow. All leaves are on the same level.
+pub struct BTree<T: Ord + Copy + Debug> {
+    root: Option<RcRefcellBNode<T>>,
+    min_degree: usize,
+}
+
+impl<T: Ord + Copy + Debug> TreeNode<T> for BTreeNode<T> {
+    /// B-tree nodes are not binary, so there is no left child.
+    fn get_left(&self) -> &Option<RcRefcellBNode<T>> {
+        &None
+    }
+
+    /// B-tree nodes are not binary, so there is no right child.
+    fn get_right(&self) -> &Option<RcRefcellBNode<T>> {
+        &None
+    }
+
+    /// The smallest key stored directly in this node.
+    fn get_data(&self) -> T {
+        self.keys[0]
+    }
+
+    fn get_height(&self) -> u32 {
+        match self.children.first() {
+            None => 1,
+            Some(child) => child.borrow().get_height() + 1,
+        }
+    }
+
+    fn get_min(&self) -> T {
+        match self.children.first() {
+            None => self.keys[0],
+            Some(child) => child.borrow().get_min(),
+        }
+    }
+
+    fn get_max(&self) -> T {
+        match self.children.last() {
+            None => self.keys[self.keys.len() - 1],
+            Some(child) => child.borrow().get_max(),
+        }
+    }
+
+    fn count_leaves(&self) -> u32 {
+        match self.is_leaf() {
+            true => 1,
+            false => self.children.iter().map(|child| child.borrow().count_leaves()).sum(),
+        }
+    }
+
+    /// The number of keys stored in this subtree.
+    fn count_nodes(&self) -> u32 {
+        self.keys.len() as u32
+            + self.children.iter().map(|child| child.borrow().count_nodes()).sum::<u32>()
+    }
+
+    fn traverse_inorder(&self) {
+        for (index, key) in self.keys.iter().enumerate() {
+            if let Some(child) = self.children.get(index) {
+                child.borrow().traverse_inorder();
+            }
+            println!("{:#?}", key);
+        }
+        if let Some(child) = self.children.get(self.keys.len()) {
+            child.borrow().traverse_inorder();
+        }
+    }
+
+    fn traverse_preorder(&self) {
+        for key in &self.keys {
+            println!("{:#?}", key);
+        }
+        for child in &self.children {
+            child.borrow().traverse_preorder();
+        }
+    }
+
+    fn traverse_postorder(&self) {
+        for child in &self.children {
+            child.borrow().traverse_postorder();
+        }
+        for key in &self.keys {
+            println!("{:#?}", key);
+        }
+    }
+
+    fn contain(&self, value: T) -> bool {
+        match self.keys.binary_search(&value) {
+            Ok(_) => true,
+            Err(index) => match self.children.get(index) {
+                None => false,
+                Some(child) => child.borrow().contain(value),
+            },
+        }
+    }
+}
+
+impl<T: Ord + Copy + Debug> BTreeNode<T> {
+    fn new(keys: Vec<T>, children: Vec<RcRefcellBNode<T>>) -> BTreeNode<T> {
+        BTreeNode { keys, children }
+    }
+
+    fn is_leaf(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// The sorted keys stored directly in this node.
+    pub fn get_keys(&self) -> &Vec<T> {
+        &self.keys
+    }
+
+    /// The children of this node, empty for a leaf.
+    pub fn get_children(&self) -> &Vec<RcRefcellBNode<T>> {
+        &self.children
+    }
+
+    /// Splits the full child at `index` around its median key, which moves
+    /// up into this node.
+    fn _split_child(&mut self, index: usize, min_degree: usize) {
+        let right: BTreeNode<T> = {
+            let mut child = self.children[index].borrow_mut();
+            let keys: Vec<T> = child.keys.split_off(min_degree);
+            let children: Vec<RcRefcellBNode<T>> = match child.is_leaf() {
+                true => Vec::new(),
+                false => child.children.split_off(min_degree),
+            };
+            let median: T = child.keys.pop().unwrap();
+            self.keys.insert(index, median);
+            BTreeNode::new(keys, children)
+        };
+        self.children.insert(index + 1, Rc::new(RefCell::new(right)));
+    }
+
+    /// Inserts into a node that is known not to be full, splitting full
+    /// children on the way down.
+    ///
+    /// Returns `false` if the value is already present.
+    fn _insert_non_full(&mut self, value: T, min_degree: usize) -> bool {
+        let mut index: usize = match self.keys.binary_search(&value) {
+            Ok(_) => return false,
+            Err(index) => index,
+        };
+        if self.is_leaf() {
+            self.keys.insert(index, value);
+            return true;
+        }
+        if self.children[index].borrow().keys.len() == 2 * min_degree - 1 {
+            self._split_child(index, min_degree);
+            match value.cmp(&self.keys[index]) {
+                Ordering::Equal => return false,
+                Ordering::Greater => index += 1,
+                Ordering::Less => {}
+            }
+        }
+        let child: RcRefcellBNode<T> = Rc::clone(&self.children[index]);
+        let inserted: bool = child.borrow_mut()._insert_non_full(value, min_degree);
+        inserted
+    }
+
+    /// Removes `value` from the subtree rooted here. Every node visited on
+    /// the way down is first topped up to `min_degree` keys, so a removal
+    /// never leaves a node underfull.
+    ///
+    /// Returns `false` if the value is missing.
+    fn _delete(&mut self, value: T, min_degree: usize) -> bool {
+        match self.keys.binary_search(&value) {
+            Ok(index) if self.is_leaf() => {
+                self.keys.remove(index);
+                true
+            }
+            Ok(index) => {
+                let left: RcRefcellBNode<T> = Rc::clone(&self.children[index]);
+                let right: RcRefcellBNode<T> = Rc::clone(&self.children[index + 1]);
+                if left.borrow().keys.len() >= min_degree {
+                    let predecessor: T = left.borrow().get_max();
+                    self.keys[index] = predecessor;
+                    left.borrow_mut()._delete(predecessor, min_degree)
+                } else if right.borrow().keys.len() >= min_degree {
+                    let successor: T = right.borrow().get_min();
+                    self.keys[index] = successor;
+                    right.borrow_mut()._delete(successor, min_degree)
+                } else {
+                    self._merge_children(index);
+                    left.borrow_mut()._delete(value, min_degree)
+                }
+            }
+            Err(_) if self.is_leaf() => false,
+            Err(index) => {
+                let index: usize = self._fill_child(index, min_degree);
+                let child: RcRefcellBNode<T> = Rc::clone(&self.children[index]);
+                let deleted: bool = child.borrow_mut()._delete(value, min_degree);
+                deleted
+            }
+        }
+    }
+
+    /// Makes sure the child at `index` holds at least `min_degree` keys by
+    /// borrowing from a sibling or merging with one.
+    ///
+    /// Returns the index of the child that now covers the original range.
+    fn _fill_child(&mut self, index: usize, min_degree: usize) -> usize {
+        if self.children[index].borrow().keys.len() >= min_degree {
+            return index;
+        }
+        if index > 0 && self.children[index - 1].borrow().keys.len() >= min_degree {
+            self._borrow_from_previous(index);
+            index
+        } else if index < self.keys.len() && self.children[index + 1].borrow().keys.len() >= min_degree {
+            self._borrow_from_next(index);
+            index
+        } else if index < self.keys.len() {
+            self._merge_children(index);
+            index
+        } else {
+            self._merge_children(index - 1);
+            index - 1
+        }
+    }
+
+    fn _borrow_from_previous(&mut self, index: usize) {
+        let mut child = self.children[index].borrow_mut();
+        let mut sibling = self.children[index - 1].borrow_mut();
+        let separator: T = std::mem::replace(&mut self.keys[index - 1], sibling.keys.pop().unwrap());
+        child.keys.insert(0, separator);
+        if let Some(last) = sibling.children.pop() {
+            child.children.insert(0, last);
+        }
+    }
+
+    fn _borrow_from_next(&mut self, index: usize) {
+        let mut child = self.children[index].borrow_mut();
+        let mut sibling = self.children[index + 1].borrow_mut();
+        let separator: T = std::mem::replace(&mut self.keys[index], sibling.keys.remove(0));
+        child.keys.push(separator);
+        if !sibling.is_leaf() {
+            child.children.push(sibling.children.remove(0));
+        }
+    }
+
+    /// Merges the child at `index + 1` and the separating key into the
+    /// child at `index`.
+    fn _merge_children(&mut self, index: usize) {
+        let sibling: RcRefcellBNode<T> = self.children.remove(index + 1);
+        let separator: T = self.keys.remove(index);
+        let mut sibling = sibling.borrow_mut();
+        let mut child = self.children[index].borrow_mut();
+        child.keys.push(separator);
+        child.keys.append(&mut sibling.keys);
+        child.children.append(&mut sibling.children);
+    }
+
+    fn _collect_range<R: RangeBounds<T>>(&self, range: &R, result: &mut Vec<T>) {
+        for (index, key) in self.keys.iter().enumerate() {
+            // The child left of `key` only holds smaller keys.
+            let below_start: bool = match range.start_bound() {
+                Bound::Included(start) => key < start,
+                Bound::Excluded(start) => key <= start,
+                Bound::Unbounded => false,
+            };
+            if !below_start {
+                if let Some(child) = self.children.get(index) {
+                    child.borrow()._collect_range(range, result);
+                }
+            }
+            let above_end: bool = match range.end_bound() {
+                Bound::Included(end) => key > end,
+                Bound::Excluded(end) => key >= end,
+                Bound::Unbounded => false,
+            };
+            if above_end {
+                return;
+            }
+            if range.contains(key) {
+                result.push(*key);
+            }
+        }
+        if let Some(child) = self.children.get(self.keys.len()) {
+            child.borrow()._collect_range(range, result);
         }
     }
 
+    /// Prints the children of this node, each below the given prefix.
+    fn print_children(&self, prefix_space: &String) {
+        for (index, child) in self.children.iter().enumerate() {
+            let child = child.borrow();
+            println!("{}|____ {} {:?}", prefix_space, index, child.keys);
+            let mut new_prefix_space: String = String::from(prefix_space);
+            match index + 1 == self.children.len() {
+                true => new_prefix_space.push_str("      "),
+                false => new_prefix_space.push_str("|     "),
+            }
+            child.print_children(&new_prefix_space);
+        }
+    }
+}
+
+/// B-tree nodes are not binary, so the metrics of the `Tree` trait, which
+/// walk left and right children, are answered by the nodes themselves.
+/// Folds and the structural metrics of `BinaryTree` need a binary tree,
+/// so BTree does not implement it.
+impl<T: Ord + Copy + Debug> Tree<T, BTreeNode<T>> for BTree<T> {
+    fn get_root(&self) -> &Option<RcRefcellBNode<T>> {
+        &self.root
+    }
+
+    fn get_height(&self) -> u32 {
+        match &self.root {
+            None => 0,
+            Some(node) => node.borrow().get_height(),
+        }
+    }
+
+    fn get_min(&self) -> Option<T> {
+        self.root.as_ref().map(|node| node.borrow().get_min())
+    }
+
+    fn get_max(&self) -> Option<T> {
+        self.root.as_ref().map(|node| node.borrow().get_max())
+    }
+
+    fn count_leaves(&self) -> u32 {
+        match &self.root {
+            None => 0,
+            Some(node) => node.borrow().count_leaves(),
+        }
+    }
+
+    fn count_nodes(&self) -> u32 {
+        match &self.root {
+            None => 0,
+            Some(node) => node.borrow().count_nodes(),
+        }
+    }
+
+    fn contain(&self, value: T) -> bool {
+        match &self.root {
+            None => false,
+            Some(node) => node.borrow().contain(value),
+        }
+    }
+
+    /// Inserting a new element.
+    ///
+    /// A full root is split before descending, which is the only way the
+    /// tree grows in height.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::btree::BTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = BTree::new(2);
+    /// for i in 0..10 {
+    ///     tree.insert(i);
+    /// }
+    /// assert_eq!(tree.count_nodes(), 10);
+    /// assert_eq!(tree.get_height(), 3);
+    /// ```
+    fn insert(&mut self, value: T) {
+        let min_degree: usize = self.min_degree;
+        let root: RcRefcellBNode<T> = match self.root.as_ref() {
+            None => {
+                self.root = Some(Rc::new(RefCell::new(BTreeNode::new(vec![value], Vec::new()))));
+                return;
+            }
+            Some(root) => Rc::clone(root),
+        };
+        let is_full: bool = root.borrow().keys.len() == 2 * min_degree - 1;
+        let root: RcRefcellBNode<T> = match is_full {
+            false => root,
+            true => {
+                let mut new_root: BTreeNode<T> = BTreeNode::new(Vec::new(), vec![Rc::clone(&root)]);
+                new_root._split_child(0, min_degree);
+                Rc::new(RefCell::new(new_root))
+            }
+        };
+        self.root = Some(Rc::clone(&root));
+        if !root.borrow_mut()._insert_non_full(value, min_degree) {
+            println!("The node already exists.");
+        }
+    }
+
+    /// Remove the element with the target value.
+    ///
+    /// Underfull nodes borrow a key from a sibling or merge with it; an
+    /// emptied root is replaced by its only child.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::btree::BTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = BTree::new(2);
+    /// for i in 0..10 {
+    ///     tree.insert(i);
+    /// }
+    /// tree.delete(4);
+    /// assert!(!tree.contain(4));
+    /// assert_eq!(tree.count_nodes(), 9);
+    /// ```
+    fn delete(&mut self, value: T) {
+        let root: RcRefcellBNode<T> = match self.root.as_ref() {
+            None => {
+                println!("The node of value {:#?} doesn't exist.", value);
+                return;
+            }
+            Some(root) => Rc::clone(root),
+        };
+        if !root.borrow_mut()._delete(value, self.min_degree) {
+            println!("The node of value {:#?} doesn't exist.", value);
+        }
+        let root_node = root.borrow();
+        if root_node.keys.is_empty() {
+            self.root = root_node.children.first().cloned();
+        }
+    }
+
+    /// Print the BTree, one node per line with its keys.
+    ///
+    /// # Examples
+    /// ```
+    /// use trees::btree::BTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = BTree::new(2);
+    /// for i in 0..10 {
+    ///     tree.insert(i);
+    /// }
+    /// // The result is like this.
+    /// // Root [3]
+    /// // |____ 0 [1]
+    /// // |     |____ 0 [0]
+    /// // |     |____ 1 [2]
+    /// // |____ 1 [5, 7]
+    /// //       |____ 0 [4]
+    /// //       |____ 1 [6]
+    /// //       |____ 2 [8, 9]
+    /// tree.print_tree();
+    /// ```
+    fn print_tree(&self) {
+        match &self.get_root() {
+            None => println!("This tree is empty!"),
+            Some(root) => {
+                let root = root.borrow();
+                println!("Root {:?}", root.keys);
+                root.print_children(&"".to_string());
+            }
+        }
+    }
+}
+
+impl<T: Ord + Copy + Debug> BTree<T> {
+    /// Makes a new empty BTree whose nodes hold at most
+    /// `2 * min_degree - 1` keys.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_degree` is less than 2.
+    pub fn new(min_degree: usize) -> Self {
+        assert!(min_degree >= 2, "The minimum degree of a B-tree must be at least 2.");
+        BTree {
+            root: None,
+            min_degree,
+        }
+    }
+
+    pub fn get_min_degree(&self) -> usize {
+        self.min_degree
+    }
+
+    /// Returns all keys that fall inside `range`, in ascending order.
+    /// Subtrees that lie entirely outside the range are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use trees::btree::BTree;
+    /// use crate::trees::base::Tree;
+    ///
+    /// let mut tree = BTree::new(3);
+    /// for i in 0..100 {
+    ///     tree.insert(i);
+    /// }
+    /// assert_eq!(tree.range(10..15), vec![10, 11, 12, 13, 14]);
+    /// assert_eq!(tree.range(..=2), vec![0, 1, 2]);
+    /// ```
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Vec<T> {
+        let mut result: Vec<T> = Vec::new();
+        if let Some(root) = self.root.as_ref() {
+            root.borrow()._collect_range(&range, &mut result);
+        }
+        result
+    }
+
     pub fn clear(&mut self) {
-        *self = BSTree::new();
+        *self = BTree::new(self.min_degree);
         println!("Clear operation is complete!");
     }
-}
\ No newline at end of file
+}
@@ -1,7 +1,10 @@
 #[cfg(test)]
 mod test {
-    use crate::lr_3::base::Tree;
-    use crate::lr_3::btree::BSTree;
+    use std::collections::{BTreeMap, BTreeSet};
+    use crate::common::test_utils::next_random;
+    use crate::common::BinaryTree;
+    use crate::lr_3::base::{Tree, TreeNode};
+    use crate::lr_3::btree::{BSTree, BTree, BTreeNode, DeletePolicy, RebalanceReport};
 
     #[test]
     fn test_bstree() {
@@ -47,4 +50,515 @@ mod test {
         bs_tree.clear();
         assert_eq!(bs_tree.is_empty(), true);
     }
-}
\ No newline at end of file
+
+    fn inorder_keys(tree: &BSTree<i32>) -> Vec<i32> {
+        let mut keys = Vec::new();
+        let mut stack = Vec::new();
+        let mut current = tree.get_root().clone();
+        while current.is_some() || !stack.is_empty() {
+            while let Some(node) = current {
+                current = node.borrow().get_left().clone();
+                stack.push(node);
+            }
+            let node = stack.pop().unwrap();
+            keys.push(node.borrow().get_data());
+            current = node.borrow().get_right().clone();
+        }
+        keys
+    }
+
+    fn check_against_btreeset(policy: DeletePolicy, seed: u64) {
+        let mut state = seed;
+        let mut bs_tree: BSTree<i32> = BSTree::with_policy(policy);
+        let mut model: BTreeSet<i32> = BTreeSet::new();
+        for _ in 0..2000 {
+            let value = (next_random(&mut state) % 64) as i32;
+            match next_random(&mut state) % 3 {
+                0 => {
+                    let expected = if model.remove(&value) { Some(value) } else { None };
+                    assert_eq!(bs_tree.remove(value), expected);
+                }
+                _ => {
+                    bs_tree.insert(value);
+                    model.insert(value);
+                }
+            }
+            assert_eq!(bs_tree.contain(value), model.contains(&value));
+        }
+        assert_eq!(inorder_keys(&bs_tree), model.iter().copied().collect::<Vec<i32>>());
+        assert_eq!(bs_tree.count_nodes() as usize, model.len());
+        while let Some(value) = model.pop_first() {
+            assert_eq!(bs_tree.remove(value), Some(value));
+            assert_eq!(inorder_keys(&bs_tree), model.iter().copied().collect::<Vec<i32>>());
+        }
+        assert!(bs_tree.is_empty());
+    }
+
+    #[test]
+    fn test_bstree_remove_matches_btreeset() {
+        for seed in 1..=20 {
+            check_against_btreeset(DeletePolicy::Successor, seed);
+            check_against_btreeset(DeletePolicy::Predecessor, seed);
+        }
+    }
+
+    #[test]
+    fn test_bstree_remove_policy() {
+        let mut bs_tree: BSTree<i32> = BSTree::new();
+        assert_eq!(bs_tree.get_policy(), DeletePolicy::Successor);
+        for number in [4, 2, 6, 1, 3, 5, 7] {
+            bs_tree.insert(number);
+        }
+        assert_eq!(bs_tree.remove(4), Some(4));
+        assert_eq!(bs_tree.get_root().as_ref().unwrap().borrow().get_data(), 5);
+
+        bs_tree.set_policy(DeletePolicy::Predecessor);
+        assert_eq!(bs_tree.remove(5), Some(5));
+        assert_eq!(bs_tree.get_root().as_ref().unwrap().borrow().get_data(), 3);
+        assert_eq!(bs_tree.remove(42), None);
+        assert_eq!(inorder_keys(&bs_tree), vec![1, 2, 3, 6, 7]);
+
+        bs_tree.clear();
+        assert_eq!(bs_tree.get_policy(), DeletePolicy::Predecessor);
+        assert_eq!(bs_tree.remove(1), None);
+    }
+
+    #[test]
+    fn test_bstree_degenerate_depth() {
+        let mut bs_tree: BSTree<i32> = BSTree::new();
+        for number in 0..10_000 {
+            bs_tree.insert(number);
+        }
+        for number in (0..10_000).rev().step_by(2) {
+            assert_eq!(bs_tree.remove(number), Some(number));
+        }
+        assert_eq!(bs_tree.remove(9_999), None);
+        assert_eq!(bs_tree.remove(9_998), Some(9_998));
+    }
+
+    #[test]
+    fn test_bstree_rebalance() {
+        let mut bs_tree: BSTree<i32> = BSTree::new();
+        assert_eq!(bs_tree.last_rebalance(), None);
+        assert_eq!(bs_tree.rebalance(), RebalanceReport { height_before: 0, height_after: 0, nodes: 0 });
+        for number in 0..1023 {
+            bs_tree.insert(number);
+        }
+        assert_eq!(bs_tree.len(), 1023);
+        let report = bs_tree.rebalance();
+        assert_eq!(report, RebalanceReport { height_before: 1023, height_after: 10, nodes: 1023 });
+        assert_eq!(bs_tree.last_rebalance(), Some(report));
+        assert_eq!(bs_tree.get_height(), 10);
+        assert_eq!(bs_tree.count_leaves(), 512);
+        assert_eq!(inorder_keys(&bs_tree), (0..1023).collect::<Vec<i32>>());
+        assert_eq!(bs_tree.rebalance().height_before, 10);
+
+        // A degenerate tree too deep for the recursive metrics.
+        let mut bs_tree: BSTree<i32> = BSTree::new();
+        for number in (0..10_000).rev() {
+            bs_tree.insert(number);
+        }
+        let report = bs_tree.rebalance();
+        assert_eq!(report.height_before, 10_000);
+        assert_eq!(report.height_after, 14);
+        assert_eq!(bs_tree.count_nodes(), 10_000);
+    }
+
+    #[test]
+    fn test_bstree_scapegoat() {
+        let alpha = 0.7;
+        let mut bs_tree: BSTree<i32> = BSTree::with_scapegoat(alpha);
+        assert_eq!(bs_tree.get_scapegoat(), Some(alpha));
+        for number in 0..5000 {
+            bs_tree.insert(number);
+            let bound = ((bs_tree.len() as f64).ln() / (1.0 / alpha).ln()).floor() as u32 + 1;
+            assert!(bs_tree.get_height() <= bound + 1);
+        }
+        let report = bs_tree.last_rebalance().unwrap();
+        assert!(report.height_after < report.height_before);
+        assert_eq!(inorder_keys(&bs_tree), (0..5000).collect::<Vec<i32>>());
+
+        for number in 0..4000 {
+            assert_eq!(bs_tree.remove(number), Some(number));
+        }
+        assert_eq!(bs_tree.len(), 1000);
+        assert!(bs_tree.get_height() <= 12);
+
+        let mut state = 7;
+        let mut model: BTreeSet<i32> = (4000..5000).collect();
+        for _ in 0..5000 {
+            let value = (next_random(&mut state) % 3000) as i32;
+            match next_random(&mut state) % 2 {
+                0 => assert_eq!(bs_tree.remove(value), model.take(&value)),
+                _ => {
+                    bs_tree.insert(value);
+                    model.insert(value);
+                }
+            }
+        }
+        assert_eq!(bs_tree.len(), model.len());
+        assert_eq!(inorder_keys(&bs_tree), model.iter().copied().collect::<Vec<i32>>());
+
+        bs_tree.clear();
+        assert_eq!(bs_tree.len(), 0);
+        assert_eq!(bs_tree.get_scapegoat(), Some(alpha));
+        bs_tree.set_scapegoat(None);
+        for number in 0..100 {
+            bs_tree.insert(number);
+        }
+        assert_eq!(bs_tree.get_height(), 100);
+    }
+
+    #[test]
+    fn test_bstree_fold() {
+        let mut bs_tree: BSTree<i32> = BSTree::new();
+        assert_eq!(bs_tree.fold(0, |key, left, right| left + right + key), 0);
+        //          8
+        //       3     10
+        //     1   6      14
+        //        4 7   13
+        for number in [8, 3, 10, 1, 6, 14, 4, 7, 13] {
+            bs_tree.insert(number);
+        }
+        assert_eq!(bs_tree.fold(0, |key, left, right| left + right + key), 66);
+
+        // Height, node count and sum in one pass.
+        let (height, nodes, sum) = bs_tree.fold((0, 0, 0), |key, left, right| {
+            (left.0.max(right.0) + 1, left.1 + right.1 + 1, left.2 + right.2 + key)
+        });
+        assert_eq!((height, nodes, sum), (4, 9, 66));
+
+        // Diameter in edges: (height, longest path) of every subtree.
+        let (_, diameter) = bs_tree.fold((0, 0), |_, left: (u32, u32), right: (u32, u32)| {
+            (left.0.max(right.0) + 1, left.1.max(right.1).max(left.0 + right.0))
+        });
+        assert_eq!(diameter, 6);
+
+        // Width of every level, with the depth carried down as the context.
+        let mut widths: Vec<u32> = Vec::new();
+        bs_tree.fold_with_context(0, |depth, _| depth + 1, (), |_, depth, _, _| {
+            if widths.len() < *depth {
+                widths.resize(*depth, 0);
+            }
+            widths[*depth - 1] += 1;
+        });
+        assert_eq!(widths, vec![1, 2, 3, 3]);
+
+        // Root-to-leaf path sums.
+        let sums = bs_tree.fold_with_context(0, |sum, key| sum + key, Vec::new(), |_, sum, left, right| {
+            match (left.is_empty(), right.is_empty()) {
+                (true, true) => vec![*sum],
+                _ => [left, right].concat(),
+            }
+        });
+        assert_eq!(sums, vec![12, 21, 24, 45]);
+    }
+
+    #[test]
+    fn test_bstree_metrics_on_degenerate_tree() {
+        let mut bs_tree: BSTree<i32> = BSTree::new();
+        for number in 0..10_000 {
+            bs_tree.insert(number);
+        }
+        assert_eq!(bs_tree.get_height(), 10_000);
+        assert_eq!(bs_tree.count_nodes(), 10_000);
+        assert_eq!(bs_tree.count_leaves(), 1);
+        assert_eq!(bs_tree.get_min(), Some(0));
+        assert_eq!(bs_tree.get_max(), Some(9_999));
+        assert!(bs_tree.contain(9_999));
+        assert!(!bs_tree.contain(10_000));
+        assert_eq!(bs_tree.fold(0i64, |key, left, right| left + right + key as i64), 49_995_000);
+    }
+
+    #[test]
+    fn test_bstree_structural_queries() {
+        let mut bs_tree: BSTree<i32> = BSTree::new();
+        assert_eq!(bs_tree.lca(1, 1), None);
+        assert_eq!(bs_tree.level(0), vec![]);
+        assert_eq!(bs_tree.diameter(), 0);
+        assert!(bs_tree.is_balanced());
+        //          8
+        //       3     10
+        //     1   6      14
+        //        4 7   13
+        for number in [8, 3, 10, 1, 6, 14, 4, 7, 13] {
+            bs_tree.insert(number);
+        }
+        assert_eq!(bs_tree.depth(8), Some(0));
+        assert_eq!(bs_tree.depth(13), Some(3));
+        assert_eq!(bs_tree.depth(5), None);
+
+        assert_eq!(bs_tree.lca(4, 7), Some(6));
+        assert_eq!(bs_tree.lca(1, 7), Some(3));
+        assert_eq!(bs_tree.lca(7, 13), Some(8));
+        assert_eq!(bs_tree.lca(3, 4), Some(3));
+        assert_eq!(bs_tree.lca(6, 6), Some(6));
+        assert_eq!(bs_tree.lca(4, 5), None);
+
+        assert_eq!(bs_tree.path(4, 13), Some(vec![4, 6, 3, 8, 10, 14, 13]));
+        assert_eq!(bs_tree.path(13, 4), Some(vec![13, 14, 10, 8, 3, 6, 4]));
+        assert_eq!(bs_tree.path(3, 7), Some(vec![3, 6, 7]));
+        assert_eq!(bs_tree.path(7, 3), Some(vec![7, 6, 3]));
+        assert_eq!(bs_tree.path(10, 10), Some(vec![10]));
+        assert_eq!(bs_tree.path(10, 11), None);
+
+        assert_eq!(bs_tree.level(0), vec![8]);
+        assert_eq!(bs_tree.level(1), vec![3, 10]);
+        assert_eq!(bs_tree.level(2), vec![1, 6, 14]);
+        assert_eq!(bs_tree.level(3), vec![4, 7, 13]);
+        assert_eq!(bs_tree.level(4), vec![]);
+
+        assert_eq!(bs_tree.diameter(), 6);
+        assert!(!bs_tree.is_balanced());
+        bs_tree.rebalance();
+        assert!(bs_tree.is_balanced());
+        assert_eq!(bs_tree.diameter(), 6);
+    }
+
+    #[test]
+    fn test_bstree_structural_queries_on_degenerate_tree() {
+        let mut bs_tree: BSTree<i32> = BSTree::new();
+        for number in 0..10_000 {
+            bs_tree.insert(number);
+        }
+        assert_eq!(bs_tree.depth(9_999), Some(9_999));
+        assert_eq!(bs_tree.lca(5_000, 9_999), Some(5_000));
+        assert_eq!(bs_tree.path(9_999, 9_997), Some(vec![9_999, 9_998, 9_997]));
+        assert_eq!(bs_tree.level(9_999), vec![9_999]);
+        assert_eq!(bs_tree.diameter(), 9_999);
+        assert!(!bs_tree.is_balanced());
+    }
+
+    #[test]
+    fn test_bstree_multiset() {
+        let mut bs_tree: BSTree<i32> = BSTree::new_multiset();
+        assert!(bs_tree.is_multiset());
+        for number in [5, 3, 5, 8, 3, 5, 1] {
+            bs_tree.insert(number);
+        }
+        assert_eq!(bs_tree.len(), 7);
+        assert_eq!(bs_tree.count_nodes(), 4);
+        assert_eq!(bs_tree.count(5), 3);
+        assert_eq!(bs_tree.count(4), 0);
+        assert_eq!(bs_tree.counts(), vec![(1, 1), (3, 2), (5, 3), (8, 1)]);
+        assert_eq!(bs_tree.iter().collect::<Vec<i32>>(), vec![1, 3, 3, 5, 5, 5, 8]);
+
+        // The root has two children: its count must follow its key.
+        assert!(bs_tree.remove_one(5));
+        assert_eq!(bs_tree.count(5), 2);
+        assert_eq!(bs_tree.remove_all(5), 2);
+        assert_eq!(bs_tree.count(5), 0);
+        assert_eq!(bs_tree.counts(), vec![(1, 1), (3, 2), (8, 1)]);
+        assert_eq!(bs_tree.len(), 4);
+        assert_eq!(bs_tree.remove_all(5), 0);
+        assert!(!bs_tree.remove_one(5));
+
+        bs_tree.delete(3);
+        assert_eq!(bs_tree.count(3), 1);
+        bs_tree.clear();
+        assert!(bs_tree.is_multiset());
+        assert_eq!(bs_tree.len(), 0);
+
+        // Outside multiset mode duplicates are still refused.
+        let mut set: BSTree<i32> = BSTree::new();
+        set.insert(1);
+        set.insert(1);
+        assert_eq!((set.len(), set.count(1)), (1, 1));
+        assert_eq!(set.remove_all(1), 1);
+    }
+
+    #[test]
+    fn test_bstree_multiset_matches_btreemap() {
+        for policy in [DeletePolicy::Successor, DeletePolicy::Predecessor] {
+            let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+            let mut bs_tree: BSTree<i32> = BSTree::new_multiset();
+            bs_tree.set_policy(policy);
+            let mut model: BTreeMap<i32, usize> = BTreeMap::new();
+            for _ in 0..3000 {
+                let value = (next_random(&mut state) % 32) as i32;
+                match next_random(&mut state) % 5 {
+                    0 => {
+                        let expected = model.remove(&value).unwrap_or(0);
+                        assert_eq!(bs_tree.remove_all(value), expected);
+                    }
+                    1 => {
+                        let present = model.contains_key(&value);
+                        if present {
+                            *model.get_mut(&value).unwrap() -= 1;
+                            model.retain(|_, count| *count > 0);
+                        }
+                        assert_eq!(bs_tree.remove_one(value), present);
+                    }
+                    _ => {
+                        bs_tree.insert(value);
+                        *model.entry(value).or_insert(0) += 1;
+                    }
+                }
+                assert_eq!(bs_tree.len(), model.values().sum::<usize>());
+            }
+            assert_eq!(bs_tree.counts(), model.into_iter().collect::<Vec<(i32, usize)>>());
+        }
+    }
+
+    /// Checks the B-tree invariants below `node` and returns the depth of
+    /// its leaves.
+    fn check_btree_node(node: &BTreeNode<i32>, min_degree: usize, is_root: bool) -> usize {
+        let keys = node.get_keys();
+        let children = node.get_children();
+        assert!(keys.len() < 2 * min_degree);
+        assert!(is_root || keys.len() + 1 >= min_degree);
+        assert!(keys.windows(2).all(|pair| pair[0] < pair[1]));
+        if children.is_empty() {
+            return 1;
+        }
+        assert_eq!(children.len(), keys.len() + 1);
+        let mut depths = Vec::new();
+        for (index, child) in children.iter().enumerate() {
+            let child = child.borrow();
+            if index > 0 {
+                assert!(child.get_min() > keys[index - 1]);
+            }
+            if index < keys.len() {
+                assert!(child.get_max() < keys[index]);
+            }
+            depths.push(check_btree_node(&child, min_degree, false));
+        }
+        assert!(depths.iter().all(|depth| *depth == depths[0]));
+        depths[0] + 1
+    }
+
+    fn check_btree(b_tree: &BTree<i32>, model: &BTreeSet<i32>) {
+        if let Some(root) = b_tree.get_root() {
+            let depth = check_btree_node(&root.borrow(), b_tree.get_min_degree(), true);
+            assert_eq!(depth as u32, b_tree.get_height());
+        }
+        assert_eq!(b_tree.range(..), model.iter().copied().collect::<Vec<i32>>());
+        assert_eq!(b_tree.count_nodes() as usize, model.len());
+    }
+
+    #[test]
+    fn test_btree() {
+        let mut b_tree: BTree<i32> = BTree::new(2);
+        assert_eq!(b_tree.get_height(), 0);
+        assert!(b_tree.is_empty());
+        assert_eq!(b_tree.count_nodes(), 0);
+        assert_eq!(b_tree.get_min(), None);
+        for number in 0..10 {
+            b_tree.insert(number);
+        }
+        b_tree.insert(5);
+        assert_eq!(b_tree.count_nodes(), 10);
+        assert_eq!(b_tree.get_min(), Some(0));
+        assert_eq!(b_tree.get_max(), Some(9));
+        assert_eq!(b_tree.get_height(), 3);
+        assert_eq!(b_tree.count_leaves(), 5);
+        for number in 0..10 {
+            assert!(b_tree.contain(number));
+        }
+        assert!(!b_tree.contain(10));
+        assert_eq!(b_tree.range(3..6), vec![3, 4, 5]);
+        assert_eq!(b_tree.range(7..), vec![7, 8, 9]);
+        assert_eq!(b_tree.range(..=1), vec![0, 1]);
+        assert_eq!(b_tree.range(20..30), Vec::<i32>::new());
+        b_tree.print_tree();
+
+        for number in 0..5 {
+            b_tree.delete(number);
+        }
+        b_tree.delete(0);
+        assert_eq!(b_tree.count_nodes(), 5);
+        assert_eq!(b_tree.get_min(), Some(5));
+        for number in 5..10 {
+            b_tree.delete(number);
+        }
+        assert!(b_tree.is_empty());
+
+        for number in 0..10 {
+            b_tree.insert(number);
+        }
+        b_tree.clear();
+        assert!(b_tree.is_empty());
+        assert_eq!(b_tree.get_min_degree(), 2);
+    }
+
+    #[test]
+    fn test_btree_matches_btreeset() {
+        for min_degree in [2, 3, 5] {
+            for seed in 1..=10 {
+                let mut state = seed;
+                let mut b_tree: BTree<i32> = BTree::new(min_degree);
+                let mut model: BTreeSet<i32> = BTreeSet::new();
+                for _ in 0..1500 {
+                    let value = (next_random(&mut state) % 200) as i32;
+                    match next_random(&mut state) % 3 {
+                        0 => {
+                            b_tree.delete(value);
+                            model.remove(&value);
+                        }
+                        _ => {
+                            b_tree.insert(value);
+                            model.insert(value);
+                        }
+                    }
+                    assert_eq!(b_tree.contain(value), model.contains(&value));
+                }
+                check_btree(&b_tree, &model);
+                let low = (next_random(&mut state) % 200) as i32;
+                assert_eq!(b_tree.range(low..low + 40), model.range(low..low + 40).copied().collect::<Vec<i32>>());
+                while let Some(value) = model.pop_first() {
+                    b_tree.delete(value);
+                    if value % 7 == 0 {
+                        check_btree(&b_tree, &model);
+                    }
+                }
+                assert!(b_tree.is_empty());
+            }
+        }
+    }
+
+    /// (leaf nodes, keys, deepest level) below `node`, found by walking
+    /// the children directly.
+    fn walk_btree_node(node: &BTreeNode<i32>) -> (u32, u32, u32) {
+        let keys = node.get_keys().len() as u32;
+        let children = node.get_children();
+        if children.is_empty() {
+            return (1, keys, 1);
+        }
+        let mut totals = (0, keys, 0);
+        for child in children.iter() {
+            let (leaves, keys, height) = walk_btree_node(&child.borrow());
+            totals = (totals.0 + leaves, totals.1 + keys, totals.2.max(height + 1));
+        }
+        totals
+    }
+
+    #[test]
+    fn test_btree_metrics_on_tall_tree() {
+        let mut b_tree: BTree<i32> = BTree::new(2);
+        for number in (0..1000).rev() {
+            b_tree.insert(number);
+        }
+        let root = b_tree.get_root().clone().unwrap();
+        assert!(!root.borrow().get_children().is_empty());
+        let (leaves, keys, height) = walk_btree_node(&root.borrow());
+        assert!(height > 2);
+        assert_eq!(b_tree.get_height(), height);
+        assert_eq!(b_tree.count_leaves(), leaves);
+        assert_eq!(b_tree.count_nodes(), keys);
+        assert_eq!(b_tree.count_nodes(), 1000);
+        assert_eq!(b_tree.get_min(), Some(0));
+        assert_eq!(b_tree.get_max(), Some(999));
+        assert!((0..1000).all(|number| b_tree.contain(number)));
+        assert!(!b_tree.contain(1000));
+    }
+
+    #[test]
+    fn test_btree_wide_nodes() {
+        let mut b_tree: BTree<i32> = BTree::new(16);
+        for number in 0..10_000 {
+            b_tree.insert(number);
+        }
+        assert_eq!(b_tree.count_nodes(), 10_000);
+        assert!(b_tree.get_height() <= 4);
+    }
+}
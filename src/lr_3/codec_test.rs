@@ -0,0 +1,208 @@
+#[cfg(test)]
+mod test {
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use crate::lr_3::base::Tree;
+    use crate::lr_3::btree::BSTree;
+    use crate::common::{DecodeError, Shape, TreeDiff};
+
+    fn hash_of<H: Hash>(value: &H) -> u64 {
+        let mut hasher: DefaultHasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn test_shape_text_formats() {
+        let shape: Shape<i32> = Shape::from_level_order_text("[2, 1, 4, null, null, 3, 6]", false).unwrap();
+        assert_eq!(shape.nodes.len(), 5);
+        assert_eq!(shape.to_preorder_text(), "2,1,#,#,4,3,#,#,6,#,#");
+        assert_eq!(shape.to_level_order_text(), "[2,1,4,null,null,3,6]");
+        assert_eq!(Shape::<i32>::from_preorder_text("2,1,#,#,4,3,#,#,6,#,#", false).unwrap().to_level_order_text(), "[2,1,4,null,null,3,6]");
+        assert_eq!(Shape::<i32>::from_bytes(&shape.to_bytes()).unwrap().to_preorder_text(), shape.to_preorder_text());
+
+        let empty: Shape<i32> = Shape::from_level_order_text("[]", false).unwrap();
+        assert!(empty.nodes.is_empty());
+        assert_eq!(empty.to_preorder_text(), "#");
+        assert_eq!(empty.to_level_order_text(), "[]");
+        assert_eq!(Shape::<i32>::from_preorder_text("#", false).unwrap(), empty);
+        assert_eq!(Shape::<i32>::from_bytes(&empty.to_bytes()).unwrap(), empty);
+
+        let coloured: Shape<i32> = Shape::from_level_order_text("[2:B,1:R,3:R]", true).unwrap();
+        assert!(coloured.nodes[1].red);
+        assert_eq!(coloured.to_preorder_text(), "2:B,1:R,#,#,3:R,#,#");
+        assert_eq!(Shape::<i32>::from_bytes(&coloured.to_bytes()).unwrap(), coloured);
+    }
+
+    #[test]
+    fn test_shape_rejects_bad_input() {
+        assert_eq!(Shape::<i32>::from_preorder_text("2,x,#,#,#", false), Err(DecodeError::BadToken("x".to_string())));
+        assert_eq!(Shape::<i32>::from_preorder_text("2,1,#,#", false), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(Shape::<i32>::from_preorder_text("2,#,#,#", false), Err(DecodeError::TrailingData));
+        assert_eq!(Shape::<i32>::from_level_order_text("2,1", false), Err(DecodeError::BadToken("2,1".to_string())));
+        assert_eq!(Shape::<i32>::from_level_order_text("[1,null,null,5]", false), Err(DecodeError::TrailingData));
+        assert_eq!(Shape::<i32>::from_level_order_text("[1,null,null,null]", false).unwrap().nodes.len(), 1);
+        assert_eq!(Shape::<i32>::from_level_order_text("[2:B,1]", true), Err(DecodeError::BadToken("1".to_string())));
+
+        let bytes = Shape::<i32>::from_level_order_text("[2,1,3]", false).unwrap().to_bytes();
+        assert_eq!(Shape::<i32>::from_bytes(&bytes[..bytes.len() - 1]), Err(DecodeError::UnexpectedEnd));
+        assert_eq!(Shape::<i32>::from_bytes(&[bytes.clone(), vec![0]].concat()), Err(DecodeError::TrailingData));
+        assert_eq!(Shape::<i32>::from_bytes(&bytes[..6]), Err(DecodeError::BadHeader));
+        assert_eq!(Shape::<i64>::from_bytes(&bytes), Err(DecodeError::UnexpectedEnd));
+    }
+
+    #[test]
+    fn test_shape_validation() {
+        assert_eq!(Shape::<i32>::from_level_order_text("[2,1,3]", false).unwrap().check_order(), Ok(()));
+        assert_eq!(Shape::<i32>::from_level_order_text("[2,3,1]", false).unwrap().check_order(), Err(DecodeError::OutOfOrder));
+        assert_eq!(Shape::<i32>::from_level_order_text("[5,3,8,1,6]", false).unwrap().check_order(), Err(DecodeError::OutOfOrder));
+        assert_eq!(Shape::<i32>::from_level_order_text("[2,2]", false).unwrap().check_order(), Err(DecodeError::OutOfOrder));
+
+        let red_black = |text: &str| Shape::<i32>::from_level_order_text(text, true).unwrap().check_red_black();
+        assert_eq!(red_black("[2:B,1:R,4:B,null,null,3:R]"), Err(DecodeError::BlackHeightMismatch));
+        assert_eq!(red_black("[2:B,1:B,4:R,null,null,3:B,5:B]"), Ok(()));
+        assert_eq!(red_black("[2:R,1:B,3:B]"), Err(DecodeError::RedRoot));
+        assert_eq!(red_black("[2:B,1:B,4:R,null,null,3:R,5:R]"), Err(DecodeError::DoubleRed));
+        assert_eq!(red_black("[2:B,1:B]"), Err(DecodeError::BlackHeightMismatch));
+    }
+
+    #[test]
+    fn test_bstree_round_trip() {
+        let mut bs_tree: BSTree<i32> = BSTree::new();
+        for number in [50, 30, 70, 20, 40, 60, 80, 35, 65] {
+            bs_tree.insert(number);
+        }
+        let preorder = bs_tree.to_preorder_text();
+        let level_order = bs_tree.to_level_order_text();
+        assert_eq!(level_order, "[50,30,70,20,40,60,80,null,null,35,null,null,65]");
+        for copy in [
+            BSTree::<i32>::from_preorder_text(&preorder).unwrap(),
+            BSTree::<i32>::from_level_order_text(&level_order).unwrap(),
+            BSTree::<i32>::from_bytes(&bs_tree.to_bytes()).unwrap(),
+        ] {
+            assert_eq!(copy.to_preorder_text(), preorder);
+            assert_eq!(copy.len(), 9);
+            assert_eq!(copy.get_height(), 4);
+        }
+
+        let mut copy = BSTree::<i32>::from_level_order_text(&level_order).unwrap();
+        assert_eq!(copy.remove(30), Some(30));
+        copy.insert(36);
+        assert_eq!(copy.to_level_order_text(), "[50,35,70,20,40,60,80,null,null,36,null,null,65]");
+
+        assert_eq!(BSTree::<i32>::from_level_order_text("[2,3,1]").err(), Some(DecodeError::OutOfOrder));
+        assert_eq!(BSTree::<i32>::from_level_order_text("[2:B]").err(), Some(DecodeError::BadToken("2:B".to_string())));
+        assert!(BSTree::<i32>::from_level_order_text("[]").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_bstree_multiset_round_trip() {
+        let mut bs_tree: BSTree<i32> = BSTree::new_multiset();
+        for number in [2, 2, 2, 1, 3, 3] {
+            bs_tree.insert(number);
+        }
+        let level_order = bs_tree.to_level_order_text();
+        assert_eq!(level_order, "[2*3,1*1,3*2]");
+        assert_eq!(bs_tree.to_preorder_text(), "2*3,1*1,#,#,3*2,#,#");
+        for copy in [
+            BSTree::<i32>::from_preorder_text(&bs_tree.to_preorder_text()).unwrap(),
+            BSTree::<i32>::from_level_order_text(&level_order).unwrap(),
+            BSTree::<i32>::from_bytes(&bs_tree.to_bytes()).unwrap(),
+        ] {
+            assert!(copy.is_multiset());
+            assert_eq!(copy.len(), 6);
+            assert_eq!(copy.counts(), vec![(1, 1), (2, 3), (3, 2)]);
+            assert!(copy.structural_eq(&bs_tree));
+        }
+
+        let mut single: BSTree<i32> = BSTree::new_multiset();
+        for _ in 0..3 {
+            single.insert(2);
+        }
+        let copy = BSTree::<i32>::from_bytes(&single.to_bytes()).unwrap();
+        assert_eq!(copy.len(), 3);
+        assert_eq!(copy.to_level_order_text(), "[2*3]");
+
+        // A key loses one of its occurrences.
+        let mut fewer = BSTree::<i32>::from_level_order_text(&level_order).unwrap();
+        fewer.remove_one(3);
+        assert!(!fewer.structural_eq(&bs_tree));
+        assert_eq!(bs_tree.diff(&fewer), TreeDiff { inserted: vec![], removed: vec![], changed: vec![3] });
+
+        assert_eq!(Shape::<i32>::from_level_order_text("[2*0]", false).unwrap().check_order(), Err(DecodeError::BadCount));
+        assert_eq!(BSTree::<i32>::from_level_order_text("[2*x]").err(), Some(DecodeError::BadToken("2*x".to_string())));
+        let mut plain: Shape<i32> = Shape::new(false);
+        plain.add_node(None, 2, 2, false);
+        assert_eq!(BSTree::from_shape(&plain).err(), Some(DecodeError::BadCount));
+    }
+
+    #[test]
+    fn test_bstree_round_trip_degenerate() {
+        let mut bs_tree: BSTree<u32> = BSTree::new();
+        for number in 0..5000 {
+            bs_tree.insert(number);
+        }
+        let copy = BSTree::<u32>::from_bytes(&bs_tree.to_bytes()).unwrap();
+        assert_eq!(copy.len(), 5000);
+        assert_eq!(copy.to_level_order_text(), bs_tree.to_level_order_text());
+        assert_eq!(BSTree::<u32>::from_preorder_text(&bs_tree.to_preorder_text()).unwrap().len(), 5000);
+    }
+
+    #[test]
+    fn test_shape_comparison() {
+        let shape: Shape<i32> = Shape::from_level_order_text("[2,1,4,null,null,3,6]", false).unwrap();
+        let renamed: Shape<i32> = Shape::from_level_order_text("[20,10,40,null,null,30,60]", false).unwrap();
+        let mirrored: Shape<i32> = Shape::from_level_order_text("[5,3,6,1,4]", false).unwrap();
+        assert_eq!(shape.keys(), vec![1, 2, 3, 4, 6]);
+        assert!(shape.same_shape(&renamed));
+        assert!(!shape.structural_eq(&renamed));
+        assert!(!shape.same_shape(&mirrored));
+        assert!(shape.structural_eq(&Shape::from_preorder_text("2,1,#,#,4,3,#,#,6,#,#", false).unwrap()));
+        assert!(Shape::<i32>::new(false).same_shape(&Shape::new(true)));
+
+        let black: Shape<i32> = Shape::from_level_order_text("[2:B,1:B,3:B]", true).unwrap();
+        let red: Shape<i32> = Shape::from_level_order_text("[2:B,1:R,3:R]", true).unwrap();
+        let plain: Shape<i32> = Shape::from_level_order_text("[2,1,3]", false).unwrap();
+        assert!(!black.structural_eq(&red));
+        assert!(black.structural_eq(&plain));
+        assert_eq!(black.diff(&red).changed, vec![1, 3]);
+        assert!(black.diff(&plain).is_empty());
+
+        // 4 loses its left child, 5 is hung under 6.
+        let other: Shape<i32> = Shape::from_level_order_text("[2,1,4,null,null,null,6,5]", false).unwrap();
+        assert_eq!(shape.diff(&other), TreeDiff { inserted: vec![5], removed: vec![3], changed: vec![4, 6] });
+        assert_eq!(other.diff(&shape), TreeDiff { inserted: vec![3], removed: vec![5], changed: vec![4, 6] });
+        assert!(shape.diff(&shape).is_empty());
+    }
+
+    #[test]
+    fn test_bstree_equality() {
+        let mut left_leaning: BSTree<i32> = BSTree::new();
+        let mut right_leaning: BSTree<i32> = BSTree::new();
+        for number in [3, 2, 1] {
+            left_leaning.insert(number);
+        }
+        for number in [1, 2, 3] {
+            right_leaning.insert(number);
+        }
+        assert!(left_leaning == right_leaning);
+        assert_eq!(hash_of(&left_leaning), hash_of(&right_leaning));
+        assert!(!left_leaning.same_shape(&right_leaning));
+        assert!(!left_leaning.structural_eq(&right_leaning));
+        assert_eq!(left_leaning.diff(&right_leaning), TreeDiff { inserted: vec![], removed: vec![], changed: vec![1, 2, 3] });
+
+        right_leaning.rebalance();
+        left_leaning.rebalance();
+        assert!(left_leaning.structural_eq(&right_leaning));
+        assert!(left_leaning.diff(&right_leaning).is_empty());
+
+        right_leaning.insert(4);
+        assert!(left_leaning != right_leaning);
+        assert_ne!(hash_of(&left_leaning), hash_of(&right_leaning));
+        assert!(!left_leaning.same_shape(&right_leaning));
+        assert_eq!(left_leaning.diff(&right_leaning), TreeDiff { inserted: vec![4], removed: vec![], changed: vec![3] });
+
+        assert!(BSTree::<i32>::new() == BSTree::new());
+        assert!(BSTree::<i32>::new() != right_leaning);
+    }
+}
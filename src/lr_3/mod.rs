@@ -1,3 +1,9 @@
 mod btree;
 mod btree_test;
-mod base;
\ No newline at end of file
+mod base;
+mod pager;
+mod bplus_tree;
+mod bplus_tree_test;
+mod codec_test;
+mod radix_tree;
+mod radix_tree_test;
\ No newline at end of file
@@ -0,0 +1,267 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use crate::common::crc32;
+
+/// Size of every page in the file, in bytes. Must be a power of two.
+pub const PAGE_SIZE: usize = 4096;
+
+/// The first four bytes of every page hold the CRC32 of the rest of it.
+const CHECKSUM_SIZE: usize = 4;
+
+pub type Page = [u8; PAGE_SIZE];
+
+/// Errors of the paged storage.
+#[derive(Debug)]
+pub enum PageError {
+    Io(io::Error),
+    /// The checksum of the page does not match its contents, e.g. because
+    /// a write was interrupted half way.
+    TornPage(u32),
+    /// The file does not hold what the reader expects.
+    BadFormat(String),
+}
+
+impl fmt::Display for PageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PageError::Io(error) => write!(f, "I/O error: {}", error),
+            PageError::TornPage(page_id) => write!(f, "page {} failed its checksum", page_id),
+            PageError::BadFormat(message) => write!(f, "bad format: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for PageError {}
+
+impl From<io::Error> for PageError {
+    fn from(error: io::Error) -> Self {
+        PageError::Io(error)
+    }
+}
+
+/// Stores the checksum of the page body in its first bytes.
+fn seal(page: &mut Page) {
+    let checksum: u32 = crc32(&page[CHECKSUM_SIZE..]);
+    page[..CHECKSUM_SIZE].copy_from_slice(&checksum.to_le_bytes());
+}
+
+fn is_intact(page: &Page) -> bool {
+    let stored: u32 = u32::from_le_bytes(page[..CHECKSUM_SIZE].try_into().unwrap());
+    stored == crc32(&page[CHECKSUM_SIZE..])
+}
+
+/// A file split into fixed-size pages, addressed by page number.
+pub struct PageStore {
+    file: File,
+    page_count: u32,
+}
+
+impl PageStore {
+    /// Opens the file at `path`, creating it if it does not exist.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, PageError> {
+        let file: File = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(path)?;
+        let length: u64 = file.metadata()?.len();
+        if length & (PAGE_SIZE as u64 - 1) != 0 {
+            return Err(PageError::BadFormat(format!("file length {} is not a whole number of pages", length)));
+        }
+        Ok(PageStore {
+            file,
+            page_count: (length / PAGE_SIZE as u64) as u32,
+        })
+    }
+
+    pub fn page_count(&self) -> u32 {
+        self.page_count
+    }
+
+    /// Reads a page and checks it against its checksum.
+    pub fn read_page(&mut self, page_id: u32, page: &mut Page) -> Result<(), PageError> {
+        if page_id >= self.page_count {
+            return Err(PageError::BadFormat(format!("page {} is past the end of the file", page_id)));
+        }
+        self.file.seek(SeekFrom::Start(page_id as u64 * PAGE_SIZE as u64))?;
+        self.file.read_exact(page)?;
+        match is_intact(page) {
+            true => Ok(()),
+            false => Err(PageError::TornPage(page_id)),
+        }
+    }
+
+    /// Seals the page with its checksum and writes it, growing the file if
+    /// the page lies past its end.
+    pub fn write_page(&mut self, page_id: u32, page: &mut Page) -> Result<(), PageError> {
+        seal(page);
+        self.file.seek(SeekFrom::Start(page_id as u64 * PAGE_SIZE as u64))?;
+        self.file.write_all(page)?;
+        self.page_count = self.page_count.max(page_id + 1);
+        Ok(())
+    }
+
+    pub fn sync(&mut self) -> Result<(), PageError> {
+        self.file.sync_all()?;
+        Ok(())
+    }
+}
+
+struct Frame {
+    page_id: u32,
+    page: Box<Page>,
+    dirty: bool,
+    last_used: u64,
+}
+
+/// Keeps up to `capacity` pages of a `PageStore` in memory. When the pool
+/// is full the least recently used page is evicted, and written back first
+/// if it was modified.
+pub struct BufferPool {
+    store: PageStore,
+    frames: Vec<Frame>,
+    page_table: HashMap<u32, usize>,
+    capacity: usize,
+    page_count: u32,
+    clock: u64,
+}
+
+impl BufferPool {
+    /// # Panics
+    ///
+    /// Panics if `capacity` is zero.
+    pub fn new(store: PageStore, capacity: usize) -> Self {
+        assert!(capacity > 0, "The buffer pool needs room for at least one page.");
+        let page_count: u32 = store.page_count();
+        BufferPool {
+            store,
+            frames: Vec::with_capacity(capacity),
+            page_table: HashMap::new(),
+            capacity,
+            page_count,
+            clock: 0,
+        }
+    }
+
+    /// The number of pages in the file, including ones not yet written.
+    pub fn page_count(&self) -> u32 {
+        self.page_count
+    }
+
+    /// Reads every page of the file and checks its checksum.
+    pub fn verify(&mut self) -> Result<(), PageError> {
+        let mut page: Box<Page> = Box::new([0; PAGE_SIZE]);
+        for page_id in 0..self.store.page_count() {
+            if !self.page_table.contains_key(&page_id) {
+                self.store.read_page(page_id, &mut page)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns a page for reading.
+    pub fn page(&mut self, page_id: u32) -> Result<&Page, PageError> {
+        let index: usize = self._fetch(page_id)?;
+        Ok(&self.frames[index].page)
+    }
+
+    /// Returns a page for writing and marks it dirty.
+    pub fn page_mut(&mut self, page_id: u32) -> Result<&mut Page, PageError> {
+        let index: usize = self._fetch(page_id)?;
+        let frame: &mut Frame = &mut self.frames[index];
+        frame.dirty = true;
+        Ok(&mut frame.page)
+    }
+
+    /// Appends a zeroed page to the file and returns its number. The page
+    /// reaches the disk on the next flush or eviction.
+    pub fn allocate(&mut self) -> Result<u32, PageError> {
+        let page_id: u32 = self.page_count;
+        let index: usize = self._free_frame()?;
+        let frame: Frame = Frame {
+            page_id,
+            page: Box::new([0; PAGE_SIZE]),
+            dirty: true,
+            last_used: self._tick(),
+        };
+        match index == self.frames.len() {
+            true => self.frames.push(frame),
+            false => self.frames[index] = frame,
+        }
+        self.page_table.insert(page_id, index);
+        self.page_count += 1;
+        Ok(page_id)
+    }
+
+    /// Writes every dirty page back and syncs the file.
+    pub fn flush(&mut self) -> Result<(), PageError> {
+        // Pages are written in order, so a freshly allocated page never
+        // lands past a gap in the file.
+        let mut dirty: Vec<usize> = (0..self.frames.len()).filter(|index| self.frames[*index].dirty).collect();
+        dirty.sort_by_key(|index| self.frames[*index].page_id);
+        for index in dirty {
+            let frame: &mut Frame = &mut self.frames[index];
+            self.store.write_page(frame.page_id, &mut frame.page)?;
+            frame.dirty = false;
+        }
+        self.store.sync()
+    }
+
+    fn _tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    fn _fetch(&mut self, page_id: u32) -> Result<usize, PageError> {
+        let tick: u64 = self._tick();
+        if let Some(index) = self.page_table.get(&page_id) {
+            self.frames[*index].last_used = tick;
+            return Ok(*index);
+        }
+        if page_id >= self.page_count {
+            return Err(PageError::BadFormat(format!("page {} was never allocated", page_id)));
+        }
+        let index: usize = self._free_frame()?;
+        let mut page: Box<Page> = Box::new([0; PAGE_SIZE]);
+        self.store.read_page(page_id, &mut page)?;
+        let frame: Frame = Frame { page_id, page, dirty: false, last_used: tick };
+        match index == self.frames.len() {
+            true => self.frames.push(frame),
+            false => self.frames[index] = frame,
+        }
+        self.page_table.insert(page_id, index);
+        Ok(index)
+    }
+
+    /// Finds a slot for one more page, evicting the least recently used one
+    /// if the pool is full. The returned index may be one past the end.
+    fn _free_frame(&mut self) -> Result<usize, PageError> {
+        if self.frames.len() < self.capacity {
+            return Ok(self.frames.len());
+        }
+        let index: usize = (0..self.frames.len()).min_by_key(|index| self.frames[*index].last_used).unwrap();
+        if self.frames[index].dirty {
+            // A dirty page may have been allocated after pages that were
+            // never written; those must exist in the file before it.
+            self._write_gap(self.frames[index].page_id)?;
+            let frame: &mut Frame = &mut self.frames[index];
+            self.store.write_page(frame.page_id, &mut frame.page)?;
+            frame.dirty = false;
+        }
+        self.page_table.remove(&self.frames[index].page_id);
+        Ok(index)
+    }
+
+    /// Writes the resident dirty pages that lie between the end of the file
+    /// and `page_id`.
+    fn _write_gap(&mut self, page_id: u32) -> Result<(), PageError> {
+        for missing in self.store.page_count()..page_id {
+            if let Some(index) = self.page_table.get(&missing).copied() {
+                let frame: &mut Frame = &mut self.frames[index];
+                self.store.write_page(frame.page_id, &mut frame.page)?;
+                frame.dirty = false;
+            }
+        }
+        Ok(())
+    }
+}
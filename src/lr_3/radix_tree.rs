@@ -0,0 +1,327 @@
+use std::fmt::Debug;
+use std::mem;
+
+/// A node of a `RadixTree`. The edge leading to a node is labelled with
+/// `prefix`; the key of a node is the concatenation of the labels from
+/// the root down to it.
+struct RadixNode<V> {
+    prefix: Vec<u8>,
+    value: Option<V>,
+    /// Sorted by the first byte of their labels, which are all different.
+    children: Vec<RadixNode<V>>,
+}
+
+/// A compressed trie keyed by byte strings.
+///
+/// Every chain of nodes that neither holds a value nor branches is merged
+/// into a single edge, so the tree has at most two nodes per key. Keys are
+/// visited in lexicographic byte order.
+///
+/// # Examples
+///
+/// ```
+/// use trees::radix_tree::RadixTree;
+///
+/// let mut routes = RadixTree::new();
+/// routes.insert("/api", 1);
+/// routes.insert("/api/users", 2);
+/// routes.insert("/static", 3);
+///
+/// assert_eq!(routes.get("/api"), Some(&1));
+/// assert_eq!(routes.longest_prefix("/api/users/42"), Some((b"/api/users".to_vec(), &2)));
+/// assert_eq!(routes.prefix_iter("/api").count(), 2);
+/// ```
+pub struct RadixTree<V> {
+    root: RadixNode<V>,
+    len: usize,
+}
+
+/// Iterates over the keys of a `RadixTree`, or of a part of it, in
+/// lexicographic order.
+pub struct Iter<'a, V> {
+    /// Nodes still to visit, each with its full key.
+    stack: Vec<(&'a RadixNode<V>, Vec<u8>)>,
+}
+
+impl<V> RadixNode<V> {
+    fn new(prefix: Vec<u8>, value: Option<V>) -> Self {
+        RadixNode {
+            prefix,
+            value,
+            children: Vec::new(),
+        }
+    }
+
+    /// The index of the child whose label starts with `byte`.
+    fn _child_index(&self, byte: u8) -> Result<usize, usize> {
+        self.children.binary_search_by_key(&byte, |child| child.prefix[0])
+    }
+
+    /// Cuts the label after `at` bytes; the rest moves to a new only child.
+    fn _split(&mut self, at: usize) {
+        let mut child: RadixNode<V> = RadixNode::new(self.prefix.split_off(at), self.value.take());
+        child.children = mem::take(&mut self.children);
+        self.children.push(child);
+    }
+
+    /// Absorbs the only child of a node that holds no value.
+    fn _merge_child(&mut self) {
+        let child: RadixNode<V> = self.children.pop().unwrap();
+        self.prefix.extend_from_slice(&child.prefix);
+        self.value = child.value;
+        self.children = child.children;
+    }
+}
+
+impl<'a, V> Iterator for Iter<'a, V> {
+    type Item = (Vec<u8>, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, key)) = self.stack.pop() {
+            for child in node.children.iter().rev() {
+                let mut child_key: Vec<u8> = key.clone();
+                child_key.extend_from_slice(&child.prefix);
+                self.stack.push((child, child_key));
+            }
+            if let Some(value) = node.value.as_ref() {
+                return Some((key, value));
+            }
+        }
+        None
+    }
+}
+
+fn common_prefix_len(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+impl<V> RadixTree<V> {
+    pub fn new() -> Self {
+        RadixTree {
+            root: RadixNode::new(Vec::new(), None),
+            len: 0,
+        }
+    }
+
+    /// The number of keys in the tree.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// The number of nodes, the root included.
+    pub fn node_count(&self) -> usize {
+        let mut count: usize = 0;
+        let mut stack: Vec<&RadixNode<V>> = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            count += 1;
+            stack.extend(node.children.iter());
+        }
+        count
+    }
+
+    /// Inserts `value` under `key`.
+    ///
+    /// Returns the value that was stored under `key` before, if any.
+    pub fn insert<K: AsRef<[u8]>>(&mut self, key: K, value: V) -> Option<V> {
+        let mut rest: &[u8] = key.as_ref();
+        let mut node: &mut RadixNode<V> = &mut self.root;
+        while !rest.is_empty() {
+            let index: usize = match node._child_index(rest[0]) {
+                Ok(index) => index,
+                Err(index) => {
+                    node.children.insert(index, RadixNode::new(rest.to_vec(), Some(value)));
+                    self.len += 1;
+                    return None;
+                }
+            };
+            let child: &mut RadixNode<V> = &mut node.children[index];
+            let common: usize = common_prefix_len(&child.prefix, rest);
+            if common < child.prefix.len() {
+                child._split(common);
+            }
+            rest = &rest[common..];
+            node = child;
+        }
+        let old: Option<V> = node.value.replace(value);
+        if old.is_none() {
+            self.len += 1;
+        }
+        old
+    }
+
+    /// The node whose key is exactly `key`, if there is one.
+    fn _find(&self, key: &[u8]) -> Option<&RadixNode<V>> {
+        let mut rest: &[u8] = key;
+        let mut node: &RadixNode<V> = &self.root;
+        while !rest.is_empty() {
+            node = &node.children[node._child_index(rest[0]).ok()?];
+            rest = rest.strip_prefix(node.prefix.as_slice())?;
+        }
+        Some(node)
+    }
+
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Option<&V> {
+        self._find(key.as_ref())?.value.as_ref()
+    }
+
+    pub fn get_mut<K: AsRef<[u8]>>(&mut self, key: K) -> Option<&mut V> {
+        let mut rest: &[u8] = key.as_ref();
+        let mut node: &mut RadixNode<V> = &mut self.root;
+        while !rest.is_empty() {
+            let index: usize = node._child_index(rest[0]).ok()?;
+            node = &mut node.children[index];
+            rest = rest.strip_prefix(node.prefix.as_slice())?;
+        }
+        node.value.as_mut()
+    }
+
+    pub fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> bool {
+        self.get(key).is_some()
+    }
+
+    /// Removes `key` and merges the nodes that no longer need to be apart.
+    ///
+    /// Returns the removed value, or `None` if the key is missing.
+    pub fn remove<K: AsRef<[u8]>>(&mut self, key: K) -> Option<V> {
+        // Child indices from the root down to the node of the key.
+        let mut path: Vec<usize> = Vec::new();
+        let mut rest: &[u8] = key.as_ref();
+        let mut node: &RadixNode<V> = &self.root;
+        while !rest.is_empty() {
+            let index: usize = node._child_index(rest[0]).ok()?;
+            node = &node.children[index];
+            rest = rest.strip_prefix(node.prefix.as_slice())?;
+            path.push(index);
+        }
+        node.value.as_ref()?;
+
+        let (last, parents): (&usize, &[usize]) = match path.split_last() {
+            None => {
+                self.len -= 1;
+                return self.root.value.take();
+            }
+            Some(split) => split,
+        };
+        let mut parent: &mut RadixNode<V> = &mut self.root;
+        for index in parents {
+            parent = &mut parent.children[*index];
+        }
+        let node: &mut RadixNode<V> = &mut parent.children[*last];
+        let value: Option<V> = node.value.take();
+        match node.children.len() {
+            0 => {
+                parent.children.remove(*last);
+            }
+            1 => node._merge_child(),
+            _ => {}
+        }
+        // The parent may be left as a bare link between two edges.
+        if !parents.is_empty() && parent.value.is_none() && parent.children.len() == 1 {
+            parent._merge_child();
+        }
+        self.len -= 1;
+        value
+    }
+
+    /// The longest key in the tree that is a prefix of `key`, with its
+    /// value.
+    pub fn longest_prefix<K: AsRef<[u8]>>(&self, key: K) -> Option<(Vec<u8>, &V)> {
+        let key: &[u8] = key.as_ref();
+        let mut best: Option<(usize, &V)> = self.root.value.as_ref().map(|value| (0, value));
+        let mut matched: usize = 0;
+        let mut node: &RadixNode<V> = &self.root;
+        while matched < key.len() {
+            let index: usize = match node._child_index(key[matched]) {
+                Ok(index) => index,
+                Err(_) => break,
+            };
+            node = &node.children[index];
+            if !key[matched..].starts_with(&node.prefix) {
+                break;
+            }
+            matched += node.prefix.len();
+            if let Some(value) = node.value.as_ref() {
+                best = Some((matched, value));
+            }
+        }
+        best.map(|(length, value)| (key[..length].to_vec(), value))
+    }
+
+    /// All keys with their values, in lexicographic order.
+    pub fn iter(&self) -> Iter<'_, V> {
+        Iter {
+            stack: vec![(&self.root, Vec::new())],
+        }
+    }
+
+    /// The keys that start with `prefix`, with their values, in
+    /// lexicographic order.
+    pub fn prefix_iter<K: AsRef<[u8]>>(&self, prefix: K) -> Iter<'_, V> {
+        let mut rest: &[u8] = prefix.as_ref();
+        let mut key: Vec<u8> = Vec::new();
+        let mut node: &RadixNode<V> = &self.root;
+        while !rest.is_empty() {
+            let child: &RadixNode<V> = match node._child_index(rest[0]) {
+                Ok(index) => &node.children[index],
+                Err(_) => return Iter { stack: Vec::new() },
+            };
+            key.extend_from_slice(&child.prefix);
+            // The prefix may end half way along the label of the child.
+            match (rest.strip_prefix(child.prefix.as_slice()), child.prefix.starts_with(rest)) {
+                (Some(tail), _) => rest = tail,
+                (None, true) => rest = &[],
+                (None, false) => return Iter { stack: Vec::new() },
+            }
+            node = child;
+        }
+        Iter {
+            stack: vec![(node, key)],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        *self = RadixTree::new();
+    }
+}
+
+impl<V: Debug> RadixTree<V> {
+    /// Prints every edge label, and the value of the nodes that hold one.
+    pub fn print_tree(&self) {
+        if self.root.children.is_empty() && self.root.value.is_none() {
+            println!("This tree is empty!");
+            return;
+        }
+        match self.root.value.as_ref() {
+            None => println!("Root"),
+            Some(value) => println!("Root = {:?}", value),
+        }
+        let mut stack: Vec<(&RadixNode<V>, usize)> = self.root.children.iter().rev().map(|child| (child, 0)).collect();
+        while let Some((node, depth)) = stack.pop() {
+            let label: String = String::from_utf8_lossy(&node.prefix).into_owned();
+            match node.value.as_ref() {
+                None => println!("{}|____ {:?}", "      ".repeat(depth), label),
+                Some(value) => println!("{}|____ {:?} = {:?}", "      ".repeat(depth), label, value),
+            }
+            stack.extend(node.children.iter().rev().map(|child| (child, depth + 1)));
+        }
+    }
+}
+
+impl<V> Default for RadixTree<V> {
+    fn default() -> Self {
+        RadixTree::new()
+    }
+}
+
+impl<'a, V> IntoIterator for &'a RadixTree<V> {
+    type Item = (Vec<u8>, &'a V);
+    type IntoIter = Iter<'a, V>;
+
+    fn into_iter(self) -> Iter<'a, V> {
+        self.iter()
+    }
+}
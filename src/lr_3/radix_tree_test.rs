@@ -0,0 +1,130 @@
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+    use crate::common::test_utils::next_random;
+    use crate::lr_3::radix_tree::RadixTree;
+
+    fn keys(tree: &RadixTree<u32>) -> Vec<String> {
+        tree.iter().map(|(key, _)| String::from_utf8(key).unwrap()).collect()
+    }
+
+    #[test]
+    fn test_radix_tree() {
+        let mut tree: RadixTree<u32> = RadixTree::new();
+        assert!(tree.is_empty());
+        assert_eq!(tree.get("a"), None);
+        assert_eq!(tree.longest_prefix("abc"), None);
+        assert_eq!(tree.iter().count(), 0);
+
+        for (value, key) in ["romane", "romanus", "romulus", "rubens", "ruber", "rubicon", "rubicundus"].iter().enumerate() {
+            assert_eq!(tree.insert(key, value as u32), None);
+        }
+        assert_eq!(tree.len(), 7);
+        assert_eq!(tree.get("romulus"), Some(&2));
+        assert_eq!(tree.get("rom"), None);
+        assert_eq!(tree.get("romanesque"), None);
+        assert!(!tree.contains_key("r"));
+        assert_eq!(keys(&tree), vec!["romane", "romanus", "romulus", "rubens", "ruber", "rubicon", "rubicundus"]);
+
+        // Inserting an existing key replaces the value.
+        assert_eq!(tree.insert("ruber", 40), Some(4));
+        *tree.get_mut("rubens").unwrap() += 30;
+        assert_eq!(tree.get("rubens"), Some(&33));
+        assert_eq!(tree.len(), 7);
+
+        // A key that ends inside an edge splits it.
+        assert_eq!(tree.insert("rub", 9), None);
+        assert_eq!(tree.get("rub"), Some(&9));
+        assert_eq!(tree.insert("", 10), None);
+        assert_eq!(tree.get(""), Some(&10));
+        assert_eq!(tree.len(), 9);
+
+        assert_eq!(tree.remove("rom"), None);
+        assert_eq!(tree.remove("romanus"), Some(1));
+        assert_eq!(tree.remove("romanus"), None);
+        assert_eq!(tree.remove(""), Some(10));
+        assert_eq!(keys(&tree), vec!["romane", "romulus", "rub", "rubens", "ruber", "rubicon", "rubicundus"]);
+        assert_eq!(tree.len(), 7);
+
+        tree.clear();
+        assert!(tree.is_empty());
+        assert_eq!(tree.node_count(), 1);
+    }
+
+    #[test]
+    fn test_radix_tree_longest_prefix() {
+        let mut routes: RadixTree<&str> = RadixTree::new();
+        routes.insert([10], "10.0.0.0/8");
+        routes.insert([10, 1], "10.1.0.0/16");
+        routes.insert([10, 1, 2], "10.1.2.0/24");
+        routes.insert([192, 168], "192.168.0.0/16");
+
+        assert_eq!(routes.longest_prefix([10, 1, 2, 3]), Some((vec![10, 1, 2], &"10.1.2.0/24")));
+        assert_eq!(routes.longest_prefix([10, 1, 9, 9]), Some((vec![10, 1], &"10.1.0.0/16")));
+        assert_eq!(routes.longest_prefix([10, 7, 0, 1]), Some((vec![10], &"10.0.0.0/8")));
+        assert_eq!(routes.longest_prefix([192, 169, 0, 1]), None);
+        assert_eq!(routes.longest_prefix([172, 16, 0, 1]), None);
+
+        routes.insert([], "default");
+        assert_eq!(routes.longest_prefix([172, 16, 0, 1]), Some((vec![], &"default")));
+        routes.remove([10, 1]);
+        assert_eq!(routes.longest_prefix([10, 1, 9, 9]), Some((vec![10], &"10.0.0.0/8")));
+    }
+
+    #[test]
+    fn test_radix_tree_prefix_iter() {
+        let mut words: RadixTree<u32> = RadixTree::new();
+        for (value, word) in ["car", "card", "care", "careful", "cart", "cat", "dog"].iter().enumerate() {
+            words.insert(word, value as u32);
+        }
+        let complete = |prefix: &str| -> Vec<String> {
+            words.prefix_iter(prefix).map(|(key, _)| String::from_utf8(key).unwrap()).collect()
+        };
+        assert_eq!(complete("car"), vec!["car", "card", "care", "careful", "cart"]);
+        // The prefix ends in the middle of the edge "eful".
+        assert_eq!(complete("caref"), vec!["careful"]);
+        assert_eq!(complete("ca"), vec!["car", "card", "care", "careful", "cart", "cat"]);
+        assert_eq!(complete(""), keys(&words));
+        assert_eq!(complete("cab"), Vec::<String>::new());
+        assert_eq!(complete("carefully"), Vec::<String>::new());
+        assert_eq!(words.prefix_iter("do").next(), Some((b"dog".to_vec(), &6)));
+    }
+
+    #[test]
+    fn test_radix_tree_matches_btreemap() {
+        let mut state: u64 = 0x1234_5678_9ABC_DEF1;
+        let mut tree: RadixTree<u32> = RadixTree::new();
+        let mut model: BTreeMap<Vec<u8>, u32> = BTreeMap::new();
+        for step in 0..5000 {
+            // Short keys over a tiny alphabet share many prefixes.
+            let length: u64 = next_random(&mut state) % 6;
+            let key: Vec<u8> = (0..length).map(|_| b'a' + (next_random(&mut state) % 3) as u8).collect();
+            match next_random(&mut state) % 3 {
+                0 => assert_eq!(tree.remove(&key), model.remove(&key)),
+                _ => assert_eq!(tree.insert(&key, step), model.insert(key.clone(), step)),
+            }
+            assert_eq!(tree.len(), model.len());
+            // Merging keeps the tree compressed: at most two nodes per key.
+            assert!(tree.node_count() <= 2 * model.len() + 1);
+
+            let expected = model
+                .iter()
+                .rev()
+                .find(|(stored, _)| key.starts_with(stored))
+                .map(|(stored, value)| (stored.clone(), value));
+            assert_eq!(tree.longest_prefix(&key), expected);
+        }
+        let entries: Vec<(Vec<u8>, u32)> = tree.iter().map(|(key, value)| (key, *value)).collect();
+        assert_eq!(entries, model.clone().into_iter().collect::<Vec<(Vec<u8>, u32)>>());
+        for prefix in [&b"a"[..], b"ab", b"cab", b"ccc"] {
+            let expected: Vec<&Vec<u8>> = model.keys().filter(|key| key.starts_with(prefix)).collect();
+            let found: Vec<Vec<u8>> = tree.prefix_iter(prefix).map(|(key, _)| key).collect();
+            assert_eq!(found.iter().collect::<Vec<&Vec<u8>>>(), expected);
+        }
+        for key in model.keys() {
+            tree.remove(key);
+        }
+        assert!(tree.is_empty());
+        assert_eq!(tree.node_count(), 1);
+    }
+}
@@ -0,0 +1,386 @@
+use std::ffi::OsString;
+use std::fmt;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+
+use super::hash_map::HashMap;
+use crate::common::crc32;
+
+/// Заголовок записи: CRC32 остальной части записи, длина ключа и длина
+/// значения.
+const HEADER_SIZE: usize = 12;
+
+/// Длина значения, которой помечается надгробие — запись об удалении ключа.
+const TOMBSTONE: u32 = u32::MAX;
+
+/// Ошибки хранилища `Bitcask`.
+#[derive(Debug)]
+pub enum BitcaskError {
+    /// Ошибка ввода-вывода при работе с журналом.
+    Io(io::Error),
+    /// Ключ или значение не помещается в запись; содержит его длину.
+    TooLarge(usize),
+    /// Целиком прочитанная запись не сходится с CRC; содержит ее смещение
+    /// в журнале.
+    Corrupt(u64),
+}
+
+impl fmt::Display for BitcaskError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BitcaskError::Io(error) => write!(f, "I/O error: {}", error),
+            BitcaskError::TooLarge(length) => write!(f, "{} bytes do not fit in a record", length),
+            BitcaskError::Corrupt(offset) => write!(f, "record at offset {} is corrupt", offset),
+        }
+    }
+}
+
+impl std::error::Error for BitcaskError {}
+
+impl From<io::Error> for BitcaskError {
+    fn from(error: io::Error) -> Self {
+        BitcaskError::Io(error)
+    }
+}
+
+/// Где в файле лежит значение ключа.
+#[derive(Clone, Copy)]
+struct Location {
+    offset: u64, // Начало значения
+    length: u32, // Длина значения
+    record_size: u64, // Размер всей записи вместе с заголовком и ключом
+}
+
+/// Структура `Bitcask` — хранилище ключ-значение на диске в духе Bitcask.
+///
+/// Все изменения дописываются в конец одного файла-журнала, а в памяти
+/// хранится `HashMap` — каталог ключей, указывающий, где в файле лежит
+/// последнее значение каждого ключа. Поэтому `get` делает одно чтение с
+/// диска, а `put` и `delete` — одну запись в конец файла. Удаление
+/// записывает надгробие.
+///
+/// Каждая запись снабжена CRC32. При открытии журнал читается с начала и
+/// каталог ключей строится заново. Последняя запись, обрезанная при сбое
+/// во время записи, отбрасывается, и файл укорачивается до конца
+/// предыдущей. Если же не сходится с CRC целая запись, открытие
+/// возвращает `BitcaskError::Corrupt`, а файл не меняется: записи за ней
+/// могут быть целы, и стирать их нельзя.
+///
+/// Перезаписанные значения и надгробия остаются в файле, пока не вызван
+/// `compact`.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::Bitcask;
+/// let path = std::env::temp_dir().join("example.bitcask");
+/// let mut store = Bitcask::open(&path).unwrap();
+/// store.put("ключ", "значение").unwrap();
+/// store.sync().unwrap();
+/// drop(store);
+///
+/// let mut store = Bitcask::open(&path).unwrap();
+/// assert_eq!(store.get("ключ").unwrap(), Some("значение".as_bytes().to_vec()));
+/// ```
+pub struct Bitcask {
+    path: PathBuf, // Путь к файлу-журналу
+    file: File, // Журнал, открытый на чтение и запись
+    key_dir: HashMap<Vec<u8>, Location>, // Каталог ключей
+    length: u64, // Длина журнала
+    dead_bytes: u64, // Размер записей, которые больше не нужны
+    discarded_bytes: u64, // Сколько байтов отброшено при открытии
+}
+
+impl Bitcask {
+    /// Открывает журнал по пути `path`, создавая его, если его нет, и
+    /// восстанавливает каталог ключей.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, BitcaskError> {
+        let path: PathBuf = path.as_ref().to_path_buf();
+        // Остаток сжатия, прерванного до переименования, не нужен.
+        match fs::remove_file(compaction_path(&path)) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error.into()),
+            _ => {}
+        }
+        let file: File = OpenOptions::new().read(true).write(true).create(true).truncate(false).open(&path)?;
+        let mut store: Bitcask = Bitcask {
+            path,
+            file,
+            key_dir: HashMap::new(),
+            length: 0,
+            dead_bytes: 0,
+            discarded_bytes: 0,
+        };
+        store.replay()?;
+        Ok(store)
+    }
+
+    /// Возвращает количество ключей.
+    pub fn len(&self) -> usize {
+        self.key_dir.len()
+    }
+
+    /// Проверяет, пусто ли хранилище.
+    pub fn is_empty(&self) -> bool {
+        self.key_dir.is_empty()
+    }
+
+    /// Проверяет, есть ли ключ в хранилище. Диск не читается.
+    pub fn contains_key<K: AsRef<[u8]>>(&self, key: K) -> bool {
+        self.key_dir.contains_key(key.as_ref())
+    }
+
+    /// Возвращает итератор по ключам в произвольном порядке.
+    pub fn keys(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        self.key_dir.keys().map(|key| key.as_slice())
+    }
+
+    /// Возвращает длину журнала в байтах.
+    pub fn file_size(&self) -> u64 {
+        self.length
+    }
+
+    /// Возвращает, сколько байтов журнала занято перезаписанными значениями
+    /// и надгробиями, то есть сколько освободит `compact`.
+    pub fn dead_bytes(&self) -> u64 {
+        self.dead_bytes
+    }
+
+    /// Возвращает, сколько байтов в конце журнала было отброшено при
+    /// открытии как обрезанная запись.
+    pub fn discarded_bytes(&self) -> u64 {
+        self.discarded_bytes
+    }
+
+    /// Записывает значение ключа.
+    pub fn put<K: AsRef<[u8]>, V: AsRef<[u8]>>(&mut self, key: K, value: V) -> Result<(), BitcaskError> {
+        let (key, value): (&[u8], &[u8]) = (key.as_ref(), value.as_ref());
+        let offset: u64 = self.length;
+        let record: Vec<u8> = encode_record(key, Some(value))?;
+        self.file.seek(SeekFrom::Start(offset))?;
+        self.file.write_all(&record)?;
+        self.length += record.len() as u64;
+        let location: Location = Location {
+            offset: offset + (HEADER_SIZE + key.len()) as u64,
+            length: value.len() as u32,
+            record_size: record.len() as u64,
+        };
+        if let Some(old) = self.key_dir.insert(key.to_vec(), location) {
+            self.dead_bytes += old.record_size;
+        }
+        Ok(())
+    }
+
+    /// Читает значение ключа с диска.
+    ///
+    /// Чтение идет по смещению, не трогая позицию файла, поэтому
+    /// достаточно общей ссылки: запись все равно каждый раз переходит в
+    /// конец журнала.
+    pub fn get<K: AsRef<[u8]>>(&self, key: K) -> Result<Option<Vec<u8>>, BitcaskError> {
+        let location: Location = match self.key_dir.get(key.as_ref()) {
+            None => return Ok(None),
+            Some(location) => *location,
+        };
+        let mut value: Vec<u8> = vec![0; location.length as usize];
+        read_exact_at(&self.file, &mut value, location.offset)?;
+        Ok(Some(value))
+    }
+
+    /// Удаляет ключ, дописывая в журнал надгробие.
+    ///
+    /// Возвращает `true`, если ключ был.
+    pub fn delete<K: AsRef<[u8]>>(&mut self, key: K) -> Result<bool, BitcaskError> {
+        let key: &[u8] = key.as_ref();
+        let old: Location = match self.key_dir.get(key) {
+            None => return Ok(false),
+            Some(location) => *location,
+        };
+        let record: Vec<u8> = encode_record(key, None)?;
+        self.file.seek(SeekFrom::Start(self.length))?;
+        self.file.write_all(&record)?;
+        self.length += record.len() as u64;
+        self.key_dir.remove(key);
+        self.dead_bytes += old.record_size + record.len() as u64;
+        Ok(true)
+    }
+
+    /// Сбрасывает журнал на диск.
+    pub fn sync(&mut self) -> Result<(), BitcaskError> {
+        self.file.sync_all()?;
+        Ok(())
+    }
+
+    /// Переписывает журнал, оставляя только последние значения живых
+    /// ключей.
+    ///
+    /// Новый журнал пишется рядом, сбрасывается на диск и атомарно
+    /// заменяет старый переименованием, после чего на диск сбрасывается и
+    /// каталог с журналом, так что при сбое на любом шаге остается либо
+    /// старый, либо новый журнал целиком. Вне Unix каталог сбросить
+    /// нельзя, и после сбоя сразу за `compact` может вернуться старый
+    /// журнал.
+    pub fn compact(&mut self) -> Result<(), BitcaskError> {
+        let temporary_path: PathBuf = compaction_path(&self.path);
+        let file: File = OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&temporary_path)?;
+        let mut key_dir: HashMap<Vec<u8>, Location> = HashMap::with_capacity(self.key_dir.len());
+        let mut length: u64 = 0;
+        {
+            let mut writer: BufWriter<&File> = BufWriter::new(&file);
+            for key in self.key_dir.keys() {
+                let value: Vec<u8> = self.get(key)?.unwrap();
+                let record: Vec<u8> = encode_record(key, Some(&value))?;
+                writer.write_all(&record)?;
+                let location: Location = Location {
+                    offset: length + (HEADER_SIZE + key.len()) as u64,
+                    length: value.len() as u32,
+                    record_size: record.len() as u64,
+                };
+                length += record.len() as u64;
+                key_dir.insert(key.clone(), location);
+            }
+            writer.flush()?;
+        }
+        file.sync_all()?;
+        fs::rename(&temporary_path, &self.path)?;
+        sync_directory(&self.path)?;
+        self.file = file;
+        self.key_dir = key_dir;
+        self.length = length;
+        self.dead_bytes = 0;
+        Ok(())
+    }
+
+    /// Читает журнал с начала и строит каталог ключей. Журнал обрезается
+    /// перед последней записью, если она не прочиталась целиком; на целой
+    /// записи, которая не сошлась с CRC, возвращается ошибка.
+    fn replay(&mut self) -> Result<(), BitcaskError> {
+        let file_length: u64 = self.file.metadata()?.len();
+        self.file.seek(SeekFrom::Start(0))?;
+        let mut reader: BufReader<&File> = BufReader::new(&self.file);
+        let mut offset: u64 = 0;
+        let mut header: [u8; HEADER_SIZE] = [0; HEADER_SIZE];
+        while offset + HEADER_SIZE as u64 <= file_length {
+            reader.read_exact(&mut header)?;
+            let checksum: u32 = u32::from_le_bytes(header[0..4].try_into().unwrap());
+            let key_length: u64 = u32::from_le_bytes(header[4..8].try_into().unwrap()) as u64;
+            let value_length: u32 = u32::from_le_bytes(header[8..12].try_into().unwrap());
+            let body_length: u64 = key_length + if value_length == TOMBSTONE { 0 } else { value_length as u64 };
+            let record_size: u64 = HEADER_SIZE as u64 + body_length;
+            if offset + record_size > file_length {
+                break;
+            }
+            let mut body: Vec<u8> = vec![0; body_length as usize];
+            reader.read_exact(&mut body)?;
+            let mut checked: Vec<u8> = header[4..].to_vec();
+            checked.extend_from_slice(&body);
+            if crc32(&checked) != checksum {
+                return Err(BitcaskError::Corrupt(offset));
+            }
+            body.truncate(key_length as usize);
+            let old: Option<Location> = match value_length {
+                TOMBSTONE => {
+                    self.dead_bytes += record_size;
+                    self.key_dir.remove(&body)
+                }
+                _ => {
+                    let location: Location = Location {
+                        offset: offset + HEADER_SIZE as u64 + key_length,
+                        length: value_length,
+                        record_size,
+                    };
+                    self.key_dir.insert(body, location)
+                }
+            };
+            if let Some(old) = old {
+                self.dead_bytes += old.record_size;
+            }
+            offset += record_size;
+        }
+        self.length = offset;
+        self.discarded_bytes = file_length - offset;
+        if self.discarded_bytes > 0 {
+            self.file.set_len(offset)?;
+        }
+        Ok(())
+    }
+}
+
+/// Кодирует запись; `None` вместо значения дает надгробие.
+fn encode_record(key: &[u8], value: Option<&[u8]>) -> Result<Vec<u8>, BitcaskError> {
+    let key_length: u32 = u32::try_from(key.len()).map_err(|_| BitcaskError::TooLarge(key.len()))?;
+    let value_length: u32 = match value {
+        None => TOMBSTONE,
+        Some(value) => match u32::try_from(value.len()) {
+            Ok(length) if length != TOMBSTONE => length,
+            _ => return Err(BitcaskError::TooLarge(value.len())),
+        },
+    };
+    let mut record: Vec<u8> = Vec::with_capacity(HEADER_SIZE + key.len() + value.map_or(0, |value| value.len()));
+    record.extend_from_slice(&[0; 4]);
+    record.extend_from_slice(&key_length.to_le_bytes());
+    record.extend_from_slice(&value_length.to_le_bytes());
+    record.extend_from_slice(key);
+    record.extend_from_slice(value.unwrap_or(&[]));
+    let checksum: u32 = crc32(&record[4..]);
+    record[..4].copy_from_slice(&checksum.to_le_bytes());
+    Ok(record)
+}
+
+/// Читает `buffer.len()` байтов с позиции `offset`, не сдвигая позицию
+/// файла.
+#[cfg(unix)]
+fn read_exact_at(file: &File, buffer: &mut [u8], offset: u64) -> io::Result<()> {
+    std::os::unix::fs::FileExt::read_exact_at(file, buffer, offset)
+}
+
+/// Читает `buffer.len()` байтов с позиции `offset`. Каждое чтение само
+/// задает позицию, так что одновременные вызовы не мешают друг другу.
+#[cfg(windows)]
+fn read_exact_at(file: &File, mut buffer: &mut [u8], mut offset: u64) -> io::Result<()> {
+    use std::os::windows::fs::FileExt;
+    while !buffer.is_empty() {
+        match file.seek_read(buffer, offset) {
+            Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+            Ok(read) => {
+                buffer = &mut buffer[read..];
+                offset += read as u64;
+            }
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    Ok(())
+}
+
+/// Читает `buffer.len()` байтов с позиции `offset` через общую позицию
+/// файла. Без позиционного чтения одновременные вызовы `get` из разных
+/// потоков могут прочитать не те байты.
+#[cfg(not(any(unix, windows)))]
+fn read_exact_at(mut file: &File, buffer: &mut [u8], offset: u64) -> io::Result<()> {
+    file.seek(SeekFrom::Start(offset))?;
+    file.read_exact(buffer)
+}
+
+/// Сбрасывает на диск каталог, в котором лежит `path`, чтобы
+/// переименование в нем пережило сбой.
+#[cfg(unix)]
+fn sync_directory(path: &Path) -> io::Result<()> {
+    let directory: &Path = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+    File::open(directory)?.sync_all()
+}
+
+/// Каталог как файл открывается только в Unix; здесь сбрасывать нечего.
+#[cfg(not(unix))]
+fn sync_directory(_: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Путь, по которому `compact` пишет новый журнал.
+fn compaction_path(path: &Path) -> PathBuf {
+    let mut name: OsString = path.as_os_str().to_owned();
+    name.push(".compact");
+    PathBuf::from(name)
+}
@@ -0,0 +1,413 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+
+/// Метка начала сериализованного `BloomFilter`.
+const BLOOM_MAGIC: &[u8; 4] = b"BLM1";
+
+/// Метка начала сериализованного `CountingBloomFilter`.
+const COUNTING_MAGIC: &[u8; 4] = b"CBF1";
+
+/// Ошибки вероятностных фильтров.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterError {
+    /// Фильтры разного размера или с разным количеством индексов нельзя
+    /// объединить.
+    ParametersMismatch,
+    /// Сериализованные байты обрезаны или повреждены.
+    InvalidData,
+    /// В кукушкином фильтре не нашлось места для еще одного отпечатка.
+    Full,
+}
+
+impl fmt::Display for FilterError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FilterError::ParametersMismatch => write!(f, "filters have different parameters"),
+            FilterError::InvalidData => write!(f, "malformed filter data"),
+            FilterError::Full => write!(f, "filter is full"),
+        }
+    }
+}
+
+impl std::error::Error for FilterError {}
+
+/// Структура `BloomFilter` — фильтр Блума: множество, которое может
+/// ошибочно ответить «есть» на отсутствующий элемент, но никогда не
+/// ответит «нет» на добавленный.
+///
+/// Элемент хэшируется один раз хэшером из `S`, как в `HashMap`, а `k`
+/// индексов битов получаются из этого хэша двойным хэшированием
+/// (Кирш — Митценмахер).
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::BloomFilter;
+/// let mut filter = BloomFilter::new(1000, 0.01);
+/// filter.insert("ключ");
+/// assert!(filter.contains("ключ"));
+/// ```
+#[derive(Clone)]
+pub struct BloomFilter<S = BuildHasherDefault<DefaultHasher>> {
+    bits: Vec<u64>, // Битовый массив, по 64 бита в слове
+    bit_count: usize, // Количество используемых битов
+    hash_count: u32, // Количество индексов на элемент
+    hash_builder: S, // Строитель хэшеров для элементов
+}
+
+impl BloomFilter {
+    /// Создает фильтр на `expected_items` элементов с долей ложных
+    /// срабатываний не больше `false_positive_rate`.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `expected_items` равно нулю или `false_positive_rate`
+    /// не лежит строго между 0 и 1.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        Self::with_hasher(expected_items, false_positive_rate, BuildHasherDefault::default())
+    }
+}
+
+impl<S: BuildHasher> BloomFilter<S> {
+    /// Создает фильтр, хэширующий элементы хэшерами из `hash_builder`.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `expected_items` равно нулю или `false_positive_rate`
+    /// не лежит строго между 0 и 1.
+    pub fn with_hasher(expected_items: usize, false_positive_rate: f64, hash_builder: S) -> Self {
+        let (bit_count, hash_count) = optimal_parameters(expected_items, false_positive_rate);
+        BloomFilter {
+            bits: vec![0; bit_count.div_ceil(64)],
+            bit_count,
+            hash_count,
+            hash_builder,
+        }
+    }
+
+    /// Возвращает количество битов.
+    pub fn bit_count(&self) -> usize {
+        self.bit_count
+    }
+
+    /// Возвращает количество индексов, которые ставит каждый элемент.
+    pub fn hash_count(&self) -> u32 {
+        self.hash_count
+    }
+
+    /// Добавляет элемент.
+    pub fn insert<T: ?Sized + Hash>(&mut self, item: &T) {
+        let hash: u64 = self.hash_builder.hash_one(item);
+        for index in indices(hash, self.hash_count, self.bit_count) {
+            self.bits[index / 64] |= 1 << (index % 64);
+        }
+    }
+
+    /// Проверяет, мог ли элемент быть добавлен. `false` означает, что
+    /// элемента точно нет.
+    pub fn contains<T: ?Sized + Hash>(&self, item: &T) -> bool {
+        let hash: u64 = self.hash_builder.hash_one(item);
+        indices(hash, self.hash_count, self.bit_count).all(|index| self.bits[index / 64] & (1 << (index % 64)) != 0)
+    }
+
+    /// Оценивает количество различных добавленных элементов по доле
+    /// установленных битов.
+    pub fn estimated_len(&self) -> usize {
+        let set: usize = self.bits.iter().map(|word| word.count_ones() as usize).sum();
+        estimate_items(set, self.bit_count, self.hash_count)
+    }
+
+    /// Оценивает долю ложных срабатываний при текущем заполнении.
+    pub fn false_positive_rate(&self) -> f64 {
+        let set: usize = self.bits.iter().map(|word| word.count_ones() as usize).sum();
+        (set as f64 / self.bit_count as f64).powi(self.hash_count as i32)
+    }
+
+    /// Удаляет все элементы.
+    pub fn clear(&mut self) {
+        self.bits.iter_mut().for_each(|word| *word = 0);
+    }
+
+    /// Добавляет все элементы другого фильтра с теми же размером и
+    /// количеством индексов.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::BloomFilter;
+    /// let mut first = BloomFilter::new(100, 0.01);
+    /// let mut second = BloomFilter::new(100, 0.01);
+    /// first.insert(&1);
+    /// second.insert(&2);
+    /// first.merge(&second).unwrap();
+    /// assert!(first.contains(&1) && first.contains(&2));
+    /// ```
+    pub fn merge(&mut self, other: &BloomFilter<S>) -> Result<(), FilterError> {
+        if self.bit_count != other.bit_count || self.hash_count != other.hash_count {
+            return Err(FilterError::ParametersMismatch);
+        }
+        for (word, other) in self.bits.iter_mut().zip(other.bits.iter()) {
+            *word |= other;
+        }
+        Ok(())
+    }
+
+    /// Сериализует фильтр. Хэшер не сохраняется: читать байты нужно
+    /// фильтром с тем же `S`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(16 + self.bits.len() * 8);
+        bytes.extend_from_slice(BLOOM_MAGIC);
+        bytes.extend_from_slice(&(self.bit_count as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.hash_count.to_le_bytes());
+        for word in self.bits.iter() {
+            bytes.extend_from_slice(&word.to_le_bytes());
+        }
+        bytes
+    }
+}
+
+impl<S: BuildHasher + Default> BloomFilter<S> {
+    /// Восстанавливает фильтр из байтов, полученных `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FilterError> {
+        let mut reader: ByteReader = ByteReader::new(bytes, BLOOM_MAGIC)?;
+        let bit_count: usize = reader.u64()? as usize;
+        let hash_count: u32 = reader.u32()?;
+        if bit_count == 0 || hash_count == 0 {
+            return Err(FilterError::InvalidData);
+        }
+        let words: usize = bit_count.div_ceil(64);
+        let bits: Vec<u64> = (0..words).map(|_| reader.u64()).collect::<Result<_, _>>()?;
+        reader.finish()?;
+        Ok(BloomFilter {
+            bits,
+            bit_count,
+            hash_count,
+            hash_builder: S::default(),
+        })
+    }
+}
+
+/// Структура `CountingBloomFilter` — фильтр Блума со счетчиками вместо
+/// битов, поэтому элементы можно удалять.
+///
+/// Счетчики занимают по байту и насыщаются на 255: насыщенный счетчик
+/// больше не уменьшается, чтобы удаление не привело к ложному «нет».
+/// Удалять можно только добавленные элементы — удаление ложно найденного
+/// элемента портит счетчики чужих элементов.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::CountingBloomFilter;
+/// let mut filter = CountingBloomFilter::new(1000, 0.01);
+/// filter.insert("ключ");
+/// assert!(filter.remove("ключ"));
+/// assert!(!filter.contains("ключ"));
+/// ```
+#[derive(Clone)]
+pub struct CountingBloomFilter<S = BuildHasherDefault<DefaultHasher>> {
+    counters: Vec<u8>, // Счетчик для каждого индекса
+    hash_count: u32, // Количество индексов на элемент
+    hash_builder: S, // Строитель хэшеров для элементов
+}
+
+impl CountingBloomFilter {
+    /// Создает фильтр на `expected_items` элементов с долей ложных
+    /// срабатываний не больше `false_positive_rate`.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `expected_items` равно нулю или `false_positive_rate`
+    /// не лежит строго между 0 и 1.
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        Self::with_hasher(expected_items, false_positive_rate, BuildHasherDefault::default())
+    }
+}
+
+impl<S: BuildHasher> CountingBloomFilter<S> {
+    /// Создает фильтр, хэширующий элементы хэшерами из `hash_builder`.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `expected_items` равно нулю или `false_positive_rate`
+    /// не лежит строго между 0 и 1.
+    pub fn with_hasher(expected_items: usize, false_positive_rate: f64, hash_builder: S) -> Self {
+        let (counter_count, hash_count) = optimal_parameters(expected_items, false_positive_rate);
+        CountingBloomFilter {
+            counters: vec![0; counter_count],
+            hash_count,
+            hash_builder,
+        }
+    }
+
+    /// Возвращает количество счетчиков.
+    pub fn counter_count(&self) -> usize {
+        self.counters.len()
+    }
+
+    /// Возвращает количество индексов, которые меняет каждый элемент.
+    pub fn hash_count(&self) -> u32 {
+        self.hash_count
+    }
+
+    /// Добавляет элемент.
+    pub fn insert<T: ?Sized + Hash>(&mut self, item: &T) {
+        let hash: u64 = self.hash_builder.hash_one(item);
+        for index in indices(hash, self.hash_count, self.counters.len()) {
+            self.counters[index] = self.counters[index].saturating_add(1);
+        }
+    }
+
+    /// Проверяет, мог ли элемент быть добавлен. `false` означает, что
+    /// элемента точно нет.
+    pub fn contains<T: ?Sized + Hash>(&self, item: &T) -> bool {
+        let hash: u64 = self.hash_builder.hash_one(item);
+        indices(hash, self.hash_count, self.counters.len()).all(|index| self.counters[index] > 0)
+    }
+
+    /// Удаляет одно добавление элемента.
+    ///
+    /// Возвращает `false` и ничего не меняет, если элемента точно нет.
+    pub fn remove<T: ?Sized + Hash>(&mut self, item: &T) -> bool {
+        if !self.contains(item) {
+            return false;
+        }
+        let hash: u64 = self.hash_builder.hash_one(item);
+        for index in indices(hash, self.hash_count, self.counters.len()) {
+            if self.counters[index] != u8::MAX {
+                self.counters[index] -= 1;
+            }
+        }
+        true
+    }
+
+    /// Оценивает количество различных элементов по доле ненулевых
+    /// счетчиков.
+    pub fn estimated_len(&self) -> usize {
+        let set: usize = self.counters.iter().filter(|&&counter| counter > 0).count();
+        estimate_items(set, self.counters.len(), self.hash_count)
+    }
+
+    /// Удаляет все элементы.
+    pub fn clear(&mut self) {
+        self.counters.iter_mut().for_each(|counter| *counter = 0);
+    }
+
+    /// Добавляет все элементы другого фильтра с теми же размером и
+    /// количеством индексов, складывая счетчики.
+    pub fn merge(&mut self, other: &CountingBloomFilter<S>) -> Result<(), FilterError> {
+        if self.counters.len() != other.counters.len() || self.hash_count != other.hash_count {
+            return Err(FilterError::ParametersMismatch);
+        }
+        for (counter, other) in self.counters.iter_mut().zip(other.counters.iter()) {
+            *counter = counter.saturating_add(*other);
+        }
+        Ok(())
+    }
+
+    /// Сериализует фильтр. Хэшер не сохраняется: читать байты нужно
+    /// фильтром с тем же `S`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(16 + self.counters.len());
+        bytes.extend_from_slice(COUNTING_MAGIC);
+        bytes.extend_from_slice(&(self.counters.len() as u64).to_le_bytes());
+        bytes.extend_from_slice(&self.hash_count.to_le_bytes());
+        bytes.extend_from_slice(&self.counters);
+        bytes
+    }
+}
+
+impl<S: BuildHasher + Default> CountingBloomFilter<S> {
+    /// Восстанавливает фильтр из байтов, полученных `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FilterError> {
+        let mut reader: ByteReader = ByteReader::new(bytes, COUNTING_MAGIC)?;
+        let counter_count: usize = reader.u64()? as usize;
+        let hash_count: u32 = reader.u32()?;
+        if counter_count == 0 || hash_count == 0 {
+            return Err(FilterError::InvalidData);
+        }
+        let counters: Vec<u8> = reader.take(counter_count)?.to_vec();
+        reader.finish()?;
+        Ok(CountingBloomFilter {
+            counters,
+            hash_count,
+            hash_builder: S::default(),
+        })
+    }
+}
+
+/// Вычисляет размер и количество индексов фильтра Блума:
+/// `m = -n ln p / ln² 2`, `k = m / n · ln 2`.
+fn optimal_parameters(expected_items: usize, false_positive_rate: f64) -> (usize, u32) {
+    assert!(expected_items > 0, "The expected number of items must be positive.");
+    assert!(
+        false_positive_rate > 0.0 && false_positive_rate < 1.0,
+        "The false positive rate must be between 0 and 1."
+    );
+    let ln2: f64 = std::f64::consts::LN_2;
+    let size: f64 = (-(expected_items as f64) * false_positive_rate.ln() / (ln2 * ln2)).ceil().max(64.0);
+    let hash_count: f64 = (size / expected_items as f64 * ln2).round().max(1.0);
+    (size as usize, hash_count as u32)
+}
+
+/// Оценивает количество элементов по `set` занятым индексам из `size`:
+/// `n = -m / k · ln(1 - X / m)`.
+fn estimate_items(set: usize, size: usize, hash_count: u32) -> usize {
+    match set == size {
+        true => usize::MAX,
+        false => (-(size as f64) / hash_count as f64 * (1.0 - set as f64 / size as f64).ln()).round() as usize,
+    }
+}
+
+/// Индексы элемента с хэшем `hash`: `h1 + i·h2 mod size` для `i` от 0 до
+/// `hash_count`.
+pub(super) fn indices(hash: u64, hash_count: u32, size: usize) -> impl Iterator<Item = usize> {
+    let step: u64 = hash.rotate_left(32) | 1;
+    (0..hash_count as u64).map(move |i| (hash.wrapping_add(i.wrapping_mul(step)) % size as u64) as usize)
+}
+
+/// Последовательное чтение сериализованного фильтра.
+pub(super) struct ByteReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> ByteReader<'a> {
+    /// Начинает чтение, проверяя метку `magic`.
+    pub(super) fn new(bytes: &'a [u8], magic: &[u8; 4]) -> Result<Self, FilterError> {
+        let mut reader: ByteReader = ByteReader { bytes };
+        match reader.take(4)? == magic {
+            true => Ok(reader),
+            false => Err(FilterError::InvalidData),
+        }
+    }
+
+    pub(super) fn take(&mut self, count: usize) -> Result<&'a [u8], FilterError> {
+        if self.bytes.len() < count {
+            return Err(FilterError::InvalidData);
+        }
+        let (head, tail) = self.bytes.split_at(count);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    pub(super) fn u16(&mut self) -> Result<u16, FilterError> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    pub(super) fn u32(&mut self) -> Result<u32, FilterError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    pub(super) fn u64(&mut self) -> Result<u64, FilterError> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+
+    /// Проверяет, что все байты прочитаны.
+    pub(super) fn finish(self) -> Result<(), FilterError> {
+        match self.bytes.is_empty() {
+            true => Ok(()),
+            false => Err(FilterError::InvalidData),
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::vec;
+
+use super::hash_map::HashMap;
+
+type Shard<K, V, S> = RwLock<HashMap<K, V, S>>;
+
+/// Структура `ConcurrentHashMap` — хэш-таблица для общего доступа из
+/// нескольких потоков.
+///
+/// Ключи распределены по сегментам — отдельным `HashMap`, каждый под своим
+/// `RwLock`. Операции с разными сегментами не мешают друг другу, а чтения
+/// одного сегмента идут параллельно. Все методы принимают `&self`, так что
+/// таблицу достаточно обернуть в `Arc` или передать ссылкой в
+/// `std::thread::scope`.
+///
+/// Паника в замыкании, переданном в `compute` или `compute_with`, не делает
+/// таблицу непригодной: замыкание вызывается до изменения сегмента, поэтому
+/// отравленная блокировка просто снимается. Паника в `Hash` или `Eq` ключа
+/// посреди вставки или рехеширования сегмента может оставить в нем не все
+/// элементы; такой сегмент тоже остается доступен, но его содержимое не
+/// гарантируется.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::ConcurrentHashMap;
+/// let map: ConcurrentHashMap<u32, u32> = ConcurrentHashMap::new();
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             for i in 0..100 {
+///                 map.compute(i % 10, |_, count| Some(count.unwrap_or(0) + 1));
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(map.get(&0), Some(40));
+/// ```
+pub struct ConcurrentHashMap<K, V, S = BuildHasherDefault<DefaultHasher>> {
+    shards: Vec<Shard<K, V, S>>, // Сегменты таблицы
+    hash_builder: S, // Строитель хэшеров для выбора сегмента
+}
+
+impl<K: Eq + Hash, V> ConcurrentHashMap<K, V> {
+    /// Создает таблицу с числом сегментов, вчетверо большим числа
+    /// доступных ядер.
+    pub fn new() -> Self {
+        let shards: usize = std::thread::available_parallelism().map(|cores| cores.get() * 4).unwrap_or(16);
+        Self::with_shards(shards)
+    }
+
+    /// Создает таблицу с `shards` сегментами.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `shards` равно нулю.
+    pub fn with_shards(shards: usize) -> Self {
+        Self::with_shards_and_hasher(shards, BuildHasherDefault::default())
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Clone> ConcurrentHashMap<K, V, S> {
+    /// Создает таблицу с `shards` сегментами, хэширующую ключи хэшерами из
+    /// `hash_builder`.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `shards` равно нулю.
+    pub fn with_shards_and_hasher(shards: usize, hash_builder: S) -> Self {
+        assert!(shards > 0, "The map needs at least one shard.");
+        ConcurrentHashMap {
+            shards: (0..shards).map(|_| RwLock::new(HashMap::with_hasher(hash_builder.clone()))).collect(),
+            hash_builder,
+        }
+    }
+
+    /// Возвращает количество сегментов.
+    pub fn shard_count(&self) -> usize {
+        self.shards.len()
+    }
+
+    /// Возвращает количество элементов. Сегменты пересчитываются по очереди,
+    /// поэтому при одновременных вставках результат приблизителен.
+    pub fn len(&self) -> usize {
+        (0..self.shards.len()).map(|index| self.read_shard(index).len()).sum()
+    }
+
+    /// Проверяет, пуста ли таблица.
+    pub fn is_empty(&self) -> bool {
+        (0..self.shards.len()).all(|index| self.read_shard(index).is_empty())
+    }
+
+    /// Вставляет ключ и значение. Если ключ уже есть, его значение
+    /// заменяется.
+    ///
+    /// Возвращает прежнее значение ключа или `None`, если ключа не было.
+    pub fn insert(&self, key: K, value: V) -> Option<V> {
+        let index: usize = self.shard_index(&key);
+        self.write_shard(index).insert(key, value)
+    }
+
+    /// Удаляет ключ.
+    ///
+    /// Возвращает удаленное значение или `None`, если ключа не было.
+    pub fn remove<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.write_shard(self.shard_index(key)).remove(key)
+    }
+
+    /// Проверяет, есть ли в таблице указанный ключ.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.read_shard(self.shard_index(key)).contains_key(key)
+    }
+
+    /// Вызывает `f` для значения ключа, пока сегмент заблокирован на
+    /// чтение, и возвращает ее результат. Значение при этом не копируется.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::ConcurrentHashMap;
+    /// let map: ConcurrentHashMap<u32, Vec<u32>> = ConcurrentHashMap::new();
+    /// map.insert(1, vec![1, 2, 3]);
+    /// assert_eq!(map.get_with(&1, |values| values.len()), Some(3));
+    /// ```
+    pub fn get_with<Q, R, F>(&self, key: &Q, f: F) -> Option<R>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+        F: FnOnce(&V) -> R,
+    {
+        self.read_shard(self.shard_index(key)).get(key).map(f)
+    }
+
+    /// Атомарно пересчитывает значение ключа. `f` получает ключ и ссылку на
+    /// текущее значение (`None`, если ключа нет); если она возвращает `None`,
+    /// ключ удаляется. Весь вызов выполняется под блокировкой сегмента на
+    /// запись.
+    ///
+    /// Значение заменяется на месте только после возврата из `f`, поэтому
+    /// паника в `f` оставляет элемент нетронутым.
+    pub fn compute_with<F: FnOnce(&K, Option<&V>) -> Option<V>>(&self, key: K, f: F) {
+        let mut shard: RwLockWriteGuard<HashMap<K, V, S>> = self.write_shard(self.shard_index(&key));
+        match shard.get_mut(&key) {
+            Some(slot) => match f(&key, Some(slot)) {
+                Some(value) => *slot = value,
+                None => {
+                    shard.remove(&key);
+                }
+            },
+            None => {
+                if let Some(value) = f(&key, None) {
+                    shard.insert(key, value);
+                }
+            }
+        }
+    }
+
+    /// Удаляет все элементы.
+    pub fn clear(&self) {
+        for index in 0..self.shards.len() {
+            self.write_shard(index).clear();
+        }
+    }
+
+    /// Вычисляет индекс сегмента ключа. Берутся старшие биты хэша, чтобы
+    /// выбор сегмента не зависел от выбора корзины внутри него.
+    fn shard_index<Q: ?Sized + Hash>(&self, key: &Q) -> usize {
+        (self.hash_builder.hash_one(key).rotate_left(32) as usize) % self.shards.len()
+    }
+
+    fn read_shard(&self, index: usize) -> RwLockReadGuard<'_, HashMap<K, V, S>> {
+        self.shards[index].read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write_shard(&self, index: usize) -> RwLockWriteGuard<'_, HashMap<K, V, S>> {
+        self.shards[index].write().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl<K: Eq + Hash, V: Clone, S: BuildHasher + Clone> ConcurrentHashMap<K, V, S> {
+    /// Возвращает копию значения, связанного с указанным ключом.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::ConcurrentHashMap;
+    /// let map = ConcurrentHashMap::new();
+    /// map.insert("ключ", 1);
+    /// assert_eq!(map.get("ключ"), Some(1));
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get_with(key, V::clone)
+    }
+
+    /// Атомарно пересчитывает значение ключа, как `compute_with`.
+    ///
+    /// Возвращает копию нового значения или `None`, если ключ удален.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::ConcurrentHashMap;
+    /// let map: ConcurrentHashMap<&str, u32> = ConcurrentHashMap::new();
+    /// assert_eq!(map.compute("а", |_, count| Some(count.unwrap_or(0) + 1)), Some(1));
+    /// assert_eq!(map.compute("а", |_, count| Some(count.unwrap_or(0) + 1)), Some(2));
+    /// assert_eq!(map.compute("а", |_, _| None), None);
+    /// ```
+    pub fn compute<F: FnOnce(&K, Option<V>) -> Option<V>>(&self, key: K, f: F) -> Option<V> {
+        let mut new: Option<V> = None;
+        self.compute_with(key, |key, old| {
+            let value: Option<V> = f(key, old.cloned());
+            new = value.clone();
+            value
+        });
+        new
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone, S: BuildHasher + Clone> ConcurrentHashMap<K, V, S> {
+    /// Копирует все элементы. На время копирования все сегменты блокируются
+    /// на чтение, так что снимок согласован: в нем нет половины чужой
+    /// операции.
+    pub fn snapshot(&self) -> Vec<(K, V)> {
+        let shards: Vec<RwLockReadGuard<HashMap<K, V, S>>> = (0..self.shards.len()).map(|index| self.read_shard(index)).collect();
+        let mut items: Vec<(K, V)> = Vec::with_capacity(shards.iter().map(|shard| shard.len()).sum());
+        for shard in shards.iter() {
+            items.extend(shard.iter().map(|(key, value)| (key.clone(), value.clone())));
+        }
+        items
+    }
+
+    /// Возвращает итератор по снимку таблицы, см. `snapshot`. Изменения,
+    /// сделанные после вызова, итератор не видит.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::ConcurrentHashMap;
+    /// let map = ConcurrentHashMap::new();
+    /// map.insert(1, "один");
+    /// for (key, value) in map.iter() {
+    ///     map.remove(&key);
+    ///     println!("{}: {}", key, value);
+    /// }
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn iter(&self) -> vec::IntoIter<(K, V)> {
+        self.snapshot().into_iter()
+    }
+}
+
+impl<K: Eq + Hash, V> Default for ConcurrentHashMap<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
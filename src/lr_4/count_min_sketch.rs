@@ -0,0 +1,258 @@
+use std::borrow::Borrow;
+use std::cmp::Reverse;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+
+use super::bloom_filter::indices;
+use super::hash_map::HashMap;
+use super::hyper_log_log::SketchError;
+use crate::lr_1::PriorityQueue;
+
+/// Структура `CountMinSketch` — приблизительные частоты элементов потока
+/// в таблице счетчиков фиксированного размера.
+///
+/// Каждый элемент увеличивает по одному счетчику в каждой из `depth`
+/// строк, а оценка — минимум этих счетчиков. Оценка никогда не меньше
+/// настоящей частоты и с вероятностью `1 - delta` превышает ее не больше
+/// чем на `epsilon · total()`. Индексы в строках получаются из одного
+/// хэша двойным хэшированием, как в `BloomFilter`.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::CountMinSketch;
+/// let mut sketch = CountMinSketch::new(0.001, 0.01);
+/// sketch.add("страница", 3);
+/// sketch.add("страница", 2);
+/// assert_eq!(sketch.estimate("страница"), 5);
+/// ```
+#[derive(Clone)]
+pub struct CountMinSketch<S = BuildHasherDefault<DefaultHasher>> {
+    counters: Vec<u64>, // Строки счетчиков одна за другой
+    width: usize, // Количество счетчиков в строке
+    depth: u32, // Количество строк
+    total: u64, // Сумма всех добавленных количеств
+    hash_builder: S, // Строитель хэшеров для элементов
+}
+
+impl CountMinSketch {
+    /// Создает таблицу, которая с вероятностью `1 - delta` ошибается не
+    /// больше чем на `epsilon` от суммы всех количеств.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `epsilon` не положительно или `delta` не лежит строго
+    /// между 0 и 1.
+    pub fn new(epsilon: f64, delta: f64) -> Self {
+        Self::with_hasher(epsilon, delta, BuildHasherDefault::default())
+    }
+}
+
+impl<S: BuildHasher> CountMinSketch<S> {
+    /// Создает таблицу, хэширующую элементы хэшерами из `hash_builder`.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `epsilon` не положительно или `delta` не лежит строго
+    /// между 0 и 1.
+    pub fn with_hasher(epsilon: f64, delta: f64, hash_builder: S) -> Self {
+        assert!(epsilon > 0.0, "The error bound must be positive.");
+        assert!(delta > 0.0 && delta < 1.0, "The failure probability must be between 0 and 1.");
+        let width: usize = (std::f64::consts::E / epsilon).ceil() as usize;
+        let depth: u32 = (1.0 / delta).ln().ceil().max(1.0) as u32;
+        Self::with_dimensions(width, depth, hash_builder)
+    }
+
+    /// Создает таблицу из `depth` строк по `width` счетчиков.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `width` или `depth` равно нулю.
+    pub fn with_dimensions(width: usize, depth: u32, hash_builder: S) -> Self {
+        assert!(width > 0 && depth > 0, "The dimensions must be positive.");
+        CountMinSketch {
+            counters: vec![0; width * depth as usize],
+            width,
+            depth,
+            total: 0,
+            hash_builder,
+        }
+    }
+
+    /// Возвращает количество счетчиков в строке.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Возвращает количество строк.
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Возвращает сумму всех добавленных количеств.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Добавляет `count` появлений элемента.
+    pub fn add<T: ?Sized + Hash>(&mut self, item: &T, count: u64) {
+        let hash: u64 = self.hash_builder.hash_one(item);
+        for (row, column) in indices(hash, self.depth, self.width).enumerate() {
+            let counter: &mut u64 = &mut self.counters[row * self.width + column];
+            *counter = counter.saturating_add(count);
+        }
+        self.total = self.total.saturating_add(count);
+    }
+
+    /// Оценивает частоту элемента сверху.
+    pub fn estimate<T: ?Sized + Hash>(&self, item: &T) -> u64 {
+        let hash: u64 = self.hash_builder.hash_one(item);
+        indices(hash, self.depth, self.width)
+            .enumerate()
+            .map(|(row, column)| self.counters[row * self.width + column])
+            .min()
+            .unwrap()
+    }
+
+    /// Складывает счетчики с другой таблицей тех же размеров.
+    pub fn merge(&mut self, other: &CountMinSketch<S>) -> Result<(), SketchError> {
+        if self.width != other.width || self.depth != other.depth {
+            return Err(SketchError::ParametersMismatch);
+        }
+        for (counter, &other) in self.counters.iter_mut().zip(other.counters.iter()) {
+            *counter = counter.saturating_add(other);
+        }
+        self.total = self.total.saturating_add(other.total);
+        Ok(())
+    }
+
+    /// Обнуляет все счетчики.
+    pub fn clear(&mut self) {
+        self.counters.iter_mut().for_each(|counter| *counter = 0);
+        self.total = 0;
+    }
+}
+
+/// Структура `HeavyHitters` — `CountMinSketch`, который помнит `k`
+/// элементов с наибольшими оценками частоты.
+///
+/// Кандидаты хранятся в `HashMap` вместе с оценками и в
+/// `lr_1::PriorityQueue` с обратным порядком, вершина которой — кандидат
+/// с наименьшей оценкой. Новый элемент вытесняет его, если оценка нового
+/// больше. При обновлении оценки кандидата в очередь кладется новая пара,
+/// а устаревшие пары отбрасываются, когда оказываются на вершине.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::HeavyHitters;
+/// let mut hitters = HeavyHitters::new(2, 0.001, 0.01);
+/// for word in "а б а в а б г а".split(' ') {
+///     hitters.add(word);
+/// }
+/// assert_eq!(hitters.top(), vec![("а", 4), ("б", 2)]);
+/// ```
+pub struct HeavyHitters<K: Ord, S = BuildHasherDefault<DefaultHasher>> {
+    sketch: CountMinSketch<S>,
+    candidates: HashMap<K, u64>, // Текущие кандидаты и их оценки
+    queue: PriorityQueue<(Reverse<u64>, K)>, // Кандидаты по возрастанию оценки, с устаревшими парами
+    k: usize, // Сколько кандидатов помнить
+}
+
+impl<K: Eq + Hash + Ord + Clone> HeavyHitters<K> {
+    /// Создает отслеживание `k` самых частых элементов поверх
+    /// `CountMinSketch::new(epsilon, delta)`.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `k` равно нулю или параметры таблицы недопустимы.
+    pub fn new(k: usize, epsilon: f64, delta: f64) -> Self {
+        Self::with_sketch(k, CountMinSketch::new(epsilon, delta))
+    }
+}
+
+impl<K: Eq + Hash + Ord + Clone, S: BuildHasher> HeavyHitters<K, S> {
+    /// Создает отслеживание `k` самых частых элементов поверх `sketch`.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `k` равно нулю.
+    pub fn with_sketch(k: usize, sketch: CountMinSketch<S>) -> Self {
+        assert!(k > 0, "The number of heavy hitters must be positive.");
+        HeavyHitters {
+            sketch,
+            candidates: HashMap::with_capacity(k),
+            queue: PriorityQueue::new(),
+            k,
+        }
+    }
+
+    /// Возвращает таблицу частот.
+    pub fn sketch(&self) -> &CountMinSketch<S> {
+        &self.sketch
+    }
+
+    /// Добавляет одно появление элемента.
+    pub fn add(&mut self, item: K) {
+        self.add_count(item, 1);
+    }
+
+    /// Добавляет `count` появлений элемента.
+    pub fn add_count(&mut self, item: K, count: u64) {
+        self.sketch.add(&item, count);
+        let estimate: u64 = self.sketch.estimate(&item);
+        if let Some(candidate) = self.candidates.get_mut(&item) {
+            *candidate = estimate;
+        } else if self.candidates.len() == self.k {
+            self.discard_stale();
+            match self.queue.peek() {
+                Some((Reverse(smallest), _)) if *smallest < estimate => {
+                    let (_, evicted) = self.queue.pop().unwrap();
+                    self.candidates.remove(&evicted);
+                }
+                _ => return,
+            }
+            self.candidates.insert(item.clone(), estimate);
+        } else {
+            self.candidates.insert(item.clone(), estimate);
+        }
+        self.queue.push((Reverse(estimate), item));
+        if self.queue.size() > 4 * self.k + 16 {
+            self.rebuild_queue();
+        }
+    }
+
+    /// Оценивает частоту элемента сверху.
+    pub fn estimate<Q: ?Sized + Hash>(&self, item: &Q) -> u64
+    where
+        K: Borrow<Q>,
+    {
+        self.sketch.estimate(item)
+    }
+
+    /// Возвращает кандидатов с оценками по убыванию оценки.
+    pub fn top(&self) -> Vec<(K, u64)> {
+        let mut top: Vec<(K, u64)> = self.candidates.iter().map(|(key, &count)| (key.clone(), count)).collect();
+        top.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        top
+    }
+
+    /// Снимает с вершины очереди пары, оценка которых уже не совпадает с
+    /// оценкой кандидата.
+    fn discard_stale(&mut self) {
+        while let Some((Reverse(count), key)) = self.queue.peek() {
+            if self.candidates.get(key) == Some(count) {
+                return;
+            }
+            self.queue.pop();
+        }
+    }
+
+    /// Собирает очередь заново только из актуальных пар.
+    fn rebuild_queue(&mut self) {
+        self.queue = PriorityQueue::new();
+        for (key, &count) in self.candidates.iter() {
+            self.queue.push((Reverse(count), key.clone()));
+        }
+    }
+}
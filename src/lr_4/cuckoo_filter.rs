@@ -0,0 +1,254 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+
+use super::bloom_filter::{ByteReader, FilterError};
+
+/// Метка начала сериализованного `CuckooFilter`.
+const MAGIC: &[u8; 4] = b"CKF1";
+
+/// Количество отпечатков в одной корзине.
+const BUCKET_SIZE: usize = 4;
+
+/// Доля заполнения, на которую рассчитан размер фильтра.
+const TARGET_LOAD: f64 = 0.95;
+
+/// Сколько раз при вставке отпечаток может вытеснить соседа, прежде чем
+/// фильтр будет признан полным.
+const MAX_KICKS: usize = 500;
+
+/// Пустая ячейка корзины; настоящие отпечатки никогда не равны нулю.
+const EMPTY: u16 = 0;
+
+type Bucket = [u16; BUCKET_SIZE];
+
+/// Структура `CuckooFilter` — кукушкин фильтр: вероятностное множество,
+/// которое, в отличие от фильтра Блума, умеет удалять элементы.
+///
+/// Фильтр хранит 16-битные отпечатки элементов в корзинах по
+/// `BUCKET_SIZE` штук. У каждого отпечатка две возможные корзины, и
+/// вторая вычисляется по первой и самому отпечатку, поэтому при вставке
+/// отпечатки можно вытеснять, не зная исходных элементов. Поиск проверяет
+/// не больше двух корзин. Вероятность ложного срабатывания около
+/// `2 · BUCKET_SIZE / 2^16`.
+///
+/// Одинаковые элементы хранятся отдельными отпечатками, так что один и тот
+/// же элемент нельзя добавить больше `2 · BUCKET_SIZE` раз. Удалять можно
+/// только добавленные элементы.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::CuckooFilter;
+/// let mut filter = CuckooFilter::new(1000);
+/// filter.insert("ключ").unwrap();
+/// assert!(filter.contains("ключ"));
+/// assert!(filter.remove("ключ"));
+/// assert!(!filter.contains("ключ"));
+/// ```
+#[derive(Clone)]
+pub struct CuckooFilter<S = BuildHasherDefault<DefaultHasher>> {
+    buckets: Vec<Bucket>, // Количество корзин — степень двойки
+    len: usize, // Количество отпечатков
+    random: u64, // Состояние генератора для выбора вытесняемого отпечатка
+    hash_builder: S, // Строитель хэшеров для элементов
+}
+
+impl CuckooFilter {
+    /// Создает фильтр примерно на `capacity` элементов.
+    pub fn new(capacity: usize) -> Self {
+        Self::with_hasher(capacity, BuildHasherDefault::default())
+    }
+}
+
+impl<S: BuildHasher> CuckooFilter<S> {
+    /// Создает фильтр, хэширующий элементы хэшерами из `hash_builder`.
+    pub fn with_hasher(capacity: usize, hash_builder: S) -> Self {
+        let buckets: usize = ((capacity as f64 / (BUCKET_SIZE as f64 * TARGET_LOAD)).ceil() as usize).next_power_of_two();
+        Self::with_buckets(buckets, hash_builder)
+    }
+
+    fn with_buckets(bucket_count: usize, hash_builder: S) -> Self {
+        CuckooFilter {
+            buckets: vec![[EMPTY; BUCKET_SIZE]; bucket_count],
+            len: 0,
+            random: 0x2545_F491_4F6C_DD1D,
+            hash_builder,
+        }
+    }
+
+    /// Возвращает количество добавленных элементов.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Проверяет, пуст ли фильтр.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Возвращает количество ячеек для отпечатков.
+    pub fn capacity(&self) -> usize {
+        self.buckets.len() * BUCKET_SIZE
+    }
+
+    /// Возвращает долю занятых ячеек.
+    pub fn load_factor(&self) -> f64 {
+        self.len as f64 / self.capacity() as f64
+    }
+
+    /// Добавляет элемент.
+    ///
+    /// Возвращает `FilterError::Full`, если отпечатку не нашлось места;
+    /// фильтр при этом не меняется.
+    pub fn insert<T: ?Sized + Hash>(&mut self, item: &T) -> Result<(), FilterError> {
+        let (index, fingerprint) = self.locate(item);
+        match self.insert_fingerprint(index, fingerprint) {
+            true => Ok(()),
+            false => Err(FilterError::Full),
+        }
+    }
+
+    /// Проверяет, мог ли элемент быть добавлен. `false` означает, что
+    /// элемента точно нет.
+    pub fn contains<T: ?Sized + Hash>(&self, item: &T) -> bool {
+        let (index, fingerprint) = self.locate(item);
+        let alternate: usize = self.alternate_index(index, fingerprint);
+        self.buckets[index].contains(&fingerprint) || self.buckets[alternate].contains(&fingerprint)
+    }
+
+    /// Удаляет одно добавление элемента.
+    ///
+    /// Возвращает `false`, если отпечатка элемента нет.
+    pub fn remove<T: ?Sized + Hash>(&mut self, item: &T) -> bool {
+        let (index, fingerprint) = self.locate(item);
+        let alternate: usize = self.alternate_index(index, fingerprint);
+        for bucket in [index, alternate] {
+            if let Some(slot) = self.buckets[bucket].iter_mut().find(|slot| **slot == fingerprint) {
+                *slot = EMPTY;
+                self.len -= 1;
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Удаляет все элементы.
+    pub fn clear(&mut self) {
+        self.buckets.iter_mut().for_each(|bucket| *bucket = [EMPTY; BUCKET_SIZE]);
+        self.len = 0;
+    }
+
+    /// Добавляет все отпечатки другого фильтра с тем же количеством корзин.
+    ///
+    /// Если места не хватило, возвращает `FilterError::Full` и оставляет
+    /// фильтр прежним.
+    pub fn merge(&mut self, other: &CuckooFilter<S>) -> Result<(), FilterError> {
+        if self.buckets.len() != other.buckets.len() {
+            return Err(FilterError::ParametersMismatch);
+        }
+        let backup: (Vec<Bucket>, usize) = (self.buckets.clone(), self.len);
+        for (index, bucket) in other.buckets.iter().enumerate() {
+            for &fingerprint in bucket.iter().filter(|&&fingerprint| fingerprint != EMPTY) {
+                if !self.insert_fingerprint(index, fingerprint) {
+                    (self.buckets, self.len) = backup;
+                    return Err(FilterError::Full);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Сериализует фильтр. Хэшер не сохраняется: читать байты нужно
+    /// фильтром с тем же `S`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes: Vec<u8> = Vec::with_capacity(12 + self.capacity() * 2);
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&(self.buckets.len() as u64).to_le_bytes());
+        for fingerprint in self.buckets.iter().flatten() {
+            bytes.extend_from_slice(&fingerprint.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Вычисляет первую корзину и отпечаток элемента.
+    fn locate<T: ?Sized + Hash>(&self, item: &T) -> (usize, u16) {
+        let hash: u64 = self.hash_builder.hash_one(item);
+        let fingerprint: u16 = match (hash >> 48) as u16 {
+            EMPTY => 1,
+            fingerprint => fingerprint,
+        };
+        ((hash as usize) & (self.buckets.len() - 1), fingerprint)
+    }
+
+    /// Вычисляет другую корзину отпечатка. Функция обратима: другая
+    /// корзина другой корзины — исходная.
+    fn alternate_index(&self, index: usize, fingerprint: u16) -> usize {
+        (index ^ self.hash_builder.hash_one(fingerprint) as usize) & (self.buckets.len() - 1)
+    }
+
+    /// Кладет отпечаток в одну из двух его корзин, вытесняя при
+    /// необходимости другие отпечатки в их запасные корзины.
+    ///
+    /// Возвращает `false`, если места не нашлось; все вытеснения при этом
+    /// откатываются.
+    fn insert_fingerprint(&mut self, index: usize, fingerprint: u16) -> bool {
+        let alternate: usize = self.alternate_index(index, fingerprint);
+        for bucket in [index, alternate] {
+            if let Some(slot) = self.buckets[bucket].iter_mut().find(|slot| **slot == EMPTY) {
+                *slot = fingerprint;
+                self.len += 1;
+                return true;
+            }
+        }
+        let mut held: u16 = fingerprint;
+        let mut bucket: usize = match self.next_random() % 2 {
+            0 => index,
+            _ => alternate,
+        };
+        let mut swaps: Vec<(usize, usize)> = Vec::new();
+        for _ in 0..MAX_KICKS {
+            let slot: usize = (self.next_random() as usize) % BUCKET_SIZE;
+            std::mem::swap(&mut held, &mut self.buckets[bucket][slot]);
+            swaps.push((bucket, slot));
+            bucket = self.alternate_index(bucket, held);
+            if let Some(free) = self.buckets[bucket].iter_mut().find(|slot| **slot == EMPTY) {
+                *free = held;
+                self.len += 1;
+                return true;
+            }
+        }
+        // Каждый обмен обратим, так что обратный проход возвращает
+        // отпечатки на прежние места.
+        for (bucket, slot) in swaps.into_iter().rev() {
+            std::mem::swap(&mut held, &mut self.buckets[bucket][slot]);
+        }
+        false
+    }
+
+    fn next_random(&mut self) -> u64 {
+        self.random ^= self.random << 13;
+        self.random ^= self.random >> 7;
+        self.random ^= self.random << 17;
+        self.random
+    }
+}
+
+impl<S: BuildHasher + Default> CuckooFilter<S> {
+    /// Восстанавливает фильтр из байтов, полученных `to_bytes`.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, FilterError> {
+        let mut reader: ByteReader = ByteReader::new(bytes, MAGIC)?;
+        let bucket_count: usize = reader.u64()? as usize;
+        if !bucket_count.is_power_of_two() || bucket_count > bytes.len() {
+            return Err(FilterError::InvalidData);
+        }
+        let mut filter: CuckooFilter<S> = CuckooFilter::with_buckets(bucket_count, S::default());
+        for bucket in filter.buckets.iter_mut() {
+            for slot in bucket.iter_mut() {
+                *slot = reader.u16()?;
+            }
+        }
+        reader.finish()?;
+        filter.len = filter.buckets.iter().flatten().filter(|&&fingerprint| fingerprint != EMPTY).count();
+        Ok(filter)
+    }
+}
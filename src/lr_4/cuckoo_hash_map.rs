@@ -0,0 +1,331 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+use std::iter::{Chain, Flatten, FromIterator};
+use std::slice;
+
+/// Начальное количество ячеек в каждой из двух таблиц.
+const INITIAL_TABLE_SIZE: usize = 8;
+
+/// Наибольшая доля занятых ячеек, после которой таблицы растут. Выше 0.5
+/// кукушкино хэширование с двумя функциями почти наверняка зацикливается.
+const MAX_LOAD_FACTOR: f64 = 0.45;
+
+/// Сколько раз при вставке элемент может вытеснить соседа, прежде чем
+/// таблицы будут перестроены.
+const MAX_KICKS: usize = 64;
+
+/// Сколько раз подряд таблицы перестраиваются с новыми хэш-функциями того
+/// же размера, прежде чем вырасти вдвое. Ниже `MAX_LOAD_FACTOR` неудача
+/// при случайных хэш-функциях маловероятна, так что рост здесь — защита от
+/// хэшера, плохо различающего ключи.
+const MAX_REHASHES: usize = 8;
+
+/// Прибавка к затравкам хэш-функций при перестройке.
+const SEED_STEP: u64 = 0x9E37_79B9_7F4A_7C15;
+
+type Table<K, V> = Vec<Option<(K, V)>>;
+
+/// Структура `CuckooHashMap` — хэш-таблица с кукушкиным хэшированием.
+///
+/// Ключ может лежать только в одной из двух ячеек: в первой таблице по
+/// первой хэш-функции или во второй по второй. Поэтому поиск и удаление
+/// проверяют не больше двух ячеек в худшем случае. Вставка кладет ключ в
+/// первую ячейку, вытесняя ее владельца в его другую ячейку, и так далее;
+/// если цепочка вытеснений слишком длинна, таблицы перестраиваются с новыми
+/// хэш-функциями того же размера. Удваиваются они, только когда превышен
+/// коэффициент загрузки.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::CuckooHashMap;
+/// let mut map = CuckooHashMap::new();
+/// map.insert("один", 1);
+/// assert_eq!(map.get(&"один"), Some(&1));
+/// assert_eq!(CuckooHashMap::<&str, i32>::MAX_PROBES, 2);
+/// ```
+#[derive(Clone)]
+pub struct CuckooHashMap<K, V, S = BuildHasherDefault<DefaultHasher>> {
+    tables: [Table<K, V>; 2], // Две таблицы одинакового размера
+    seeds: [u64; 2], // Затравки двух хэш-функций
+    items_count: usize, // Количество элементов
+    hash_builder: S, // Строитель хэшеров для ключей
+}
+
+impl<K: Eq + Hash, V> CuckooHashMap<K, V> {
+    /// Создает новую хэш-таблицу.
+    pub fn new() -> Self {
+        Self::with_hasher(BuildHasherDefault::default())
+    }
+
+    /// Создает хэш-таблицу, в которую поместится `capacity` элементов без
+    /// перестройки.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, BuildHasherDefault::default())
+    }
+}
+
+impl<K, V, S> CuckooHashMap<K, V, S> {
+    /// Наибольшее количество ячеек, которые проверяет поиск.
+    pub const MAX_PROBES: usize = 2;
+
+    /// Возвращает итератор по парам ключ-значение в произвольном порядке.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        let [first, second] = &self.tables;
+        Iter {
+            inner: first.iter().chain(second.iter()).flatten(),
+            remaining: self.items_count,
+        }
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> CuckooHashMap<K, V, S> {
+    /// Создает новую хэш-таблицу, хэширующую ключи хэшерами из `hash_builder`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Создает хэш-таблицу с хэшерами из `hash_builder`, в которую
+    /// поместится `capacity` элементов без перестройки.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        let table_size: usize = table_size_for(capacity);
+        CuckooHashMap {
+            tables: [empty_table(table_size), empty_table(table_size)],
+            seeds: [SEED_STEP, SEED_STEP.wrapping_mul(2)],
+            items_count: 0,
+            hash_builder,
+        }
+    }
+
+    /// Возвращает количество элементов.
+    pub fn len(&self) -> usize {
+        self.items_count
+    }
+
+    /// Проверяет, пуста ли хэш-таблица.
+    pub fn is_empty(&self) -> bool {
+        self.items_count == 0
+    }
+
+    /// Возвращает, сколько элементов поместится в таблицу без перестройки.
+    pub fn capacity(&self) -> usize {
+        (self.tables[0].len() as f64 * 2.0 * MAX_LOAD_FACTOR) as usize
+    }
+
+    /// Вставляет ключ и значение. Если ключ уже есть, его значение
+    /// заменяется.
+    ///
+    /// Возвращает прежнее значение ключа или `None`, если ключа не было.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::CuckooHashMap;
+    /// let mut map = CuckooHashMap::new();
+    /// assert_eq!(map.insert(1, "один"), None);
+    /// assert_eq!(map.insert(1, "uno"), Some("один"));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(old) = self.get_mut(&key) {
+            return Some(std::mem::replace(old, value));
+        }
+        if self.items_count + 1 > self.capacity() {
+            self.rebuild(self.tables[0].len() * 2, None);
+        }
+        if let Err(homeless) = self.place((key, value)) {
+            self.rebuild(self.tables[0].len(), Some(homeless));
+        }
+        None
+    }
+
+    /// Возвращает ссылку на значение, связанное с указанным ключом.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (table, index) = self.find(key)?;
+        self.tables[table][index].as_ref().map(|(_, value)| value)
+    }
+
+    /// Возвращает изменяемую ссылку на значение, связанное с указанным ключом.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (table, index) = self.find(key)?;
+        self.tables[table][index].as_mut().map(|(_, value)| value)
+    }
+
+    /// Проверяет, есть ли в хэш-таблице указанный ключ.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.find(key).is_some()
+    }
+
+    /// Удаляет значение по указанному ключу.
+    ///
+    /// Возвращает удаленное значение или `None`, если ключа не было.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (table, index) = self.find(key)?;
+        self.items_count -= 1;
+        self.tables[table][index].take().map(|(_, value)| value)
+    }
+
+    /// Удаляет все элементы, сохраняя размер таблиц.
+    pub fn clear(&mut self) {
+        for slot in self.tables.iter_mut().flatten() {
+            *slot = None;
+        }
+        self.items_count = 0;
+    }
+
+    /// Ищет ячейку ключа: проверяет не больше двух ячеек.
+    ///
+    /// Возвращает номер таблицы и индекс ячейки.
+    fn find<Q>(&self, key: &Q) -> Option<(usize, usize)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        (0..2).map(|table| (table, self.slot_index(table, key))).find(|(table, index)| {
+            matches!(&self.tables[*table][*index], Some((k, _)) if k.borrow() == key)
+        })
+    }
+
+    /// Вычисляет индекс ячейки ключа в таблице `table`.
+    fn slot_index<Q: ?Sized + Hash>(&self, table: usize, key: &Q) -> usize {
+        (self.hash_builder.hash_one((self.seeds[table], key)) as usize) % self.tables[table].len()
+    }
+
+    /// Размещает элемент, которого точно нет в таблице, вытесняя соседей.
+    ///
+    /// Если цепочка вытеснений, скорее всего, зациклилась, возвращает
+    /// элемент, оставшийся без места.
+    fn place(&mut self, mut entry: (K, V)) -> Result<(), (K, V)> {
+        for _ in 0..MAX_KICKS {
+            for table in 0..2 {
+                let index: usize = self.slot_index(table, &entry.0);
+                match self.tables[table][index].replace(entry) {
+                    None => {
+                        self.items_count += 1;
+                        return Ok(());
+                    }
+                    Some(evicted) => entry = evicted,
+                }
+            }
+        }
+        Err(entry)
+    }
+
+    /// Перестраивает таблицы с размером `table_size` и новыми хэш-функциями
+    /// и размещает в них все элементы вместе с `extra`.
+    ///
+    /// Если вытеснения снова зацикливаются, пробует следующие хэш-функции
+    /// того же размера и удваивает таблицы только после `MAX_REHASHES`
+    /// неудач подряд.
+    fn rebuild(&mut self, mut table_size: usize, extra: Option<(K, V)>) {
+        let mut entries: Vec<(K, V)> = self.take_entries();
+        entries.extend(extra);
+        let mut failures: usize = 0;
+        loop {
+            self.tables = [empty_table(table_size), empty_table(table_size)];
+            self.seeds = [self.seeds[0].wrapping_add(SEED_STEP), self.seeds[1].wrapping_add(SEED_STEP)];
+            self.items_count = 0;
+            let mut pending: std::vec::IntoIter<(K, V)> = entries.into_iter();
+            let homeless: (K, V) = match pending.by_ref().find_map(|entry| self.place(entry).err()) {
+                None => return,
+                Some(homeless) => homeless,
+            };
+            entries = self.take_entries();
+            entries.push(homeless);
+            entries.extend(pending);
+            failures += 1;
+            if failures == MAX_REHASHES {
+                failures = 0;
+                table_size *= 2;
+            }
+        }
+    }
+
+    /// Забирает все элементы из таблиц, оставляя их пустыми.
+    fn take_entries(&mut self) -> Vec<(K, V)> {
+        let old: [Table<K, V>; 2] = std::mem::take(&mut self.tables);
+        self.items_count = 0;
+        old.into_iter().flatten().flatten().collect()
+    }
+}
+
+fn table_size_for(capacity: usize) -> usize {
+    ((capacity as f64 / (2.0 * MAX_LOAD_FACTOR)).ceil() as usize).max(INITIAL_TABLE_SIZE)
+}
+
+fn empty_table<K, V>(size: usize) -> Table<K, V> {
+    let mut table: Table<K, V> = Vec::with_capacity(size);
+    table.resize_with(size, || None);
+    table
+}
+
+type Slots<'a, K, V> = slice::Iter<'a, Option<(K, V)>>;
+
+/// Итератор по парам ключ-значение, см. `CuckooHashMap::iter`.
+pub struct Iter<'a, K, V> {
+    inner: Flatten<Chain<Slots<'a, K, V>, Slots<'a, K, V>>>,
+    remaining: usize,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.inner.next()?;
+        self.remaining -= 1;
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V, S> IntoIterator for &'a CuckooHashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for CuckooHashMap<K, V, S> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map: CuckooHashMap<K, V, S> = CuckooHashMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Extend<(K, V)> for CuckooHashMap<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for CuckooHashMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
@@ -1,12 +1,63 @@
+use std::borrow::Borrow;
 use std::collections::LinkedList;
-use std::hash::{Hash, Hasher};
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
 use std::collections::hash_map::DefaultHasher;
+use std::fmt::{self, Debug};
+use std::iter::{Chain, FlatMap, Flatten, FromIterator};
+use std::ops::Index;
+use std::{slice, vec};
 
 /// Структура `HashMap`, реализующая хэш-таблицу.
-pub struct HashMap<K, V> {
+///
+/// Хэш-функцию задает параметр `S`, как в `std::collections::HashMap`. По
+/// умолчанию используется `DefaultHasher` с нулевым ключом, поэтому хэши
+/// одинаковы от запуска к запуску. Для целых ключей быстрее `FxBuildHasher`
+/// или `FnvBuildHasher`, а для ключей из недоверенного источника нужен
+/// `SipBuildHasher::random()`.
+///
+/// Обычно рехеширование переносит все элементы сразу, и одна вставка может
+/// задержаться на время перестройки всей таблицы. В режиме постепенного
+/// рехеширования (`set_incremental_rehash`) старая таблица сохраняется, а
+/// ее корзины переносятся в новую по нескольку за операцию, как в словарях
+/// Redis. Пока перенос не закончен, поиск смотрит в обе таблицы.
+#[derive(Clone)]
+pub struct HashMap<K, V, S = BuildHasherDefault<DefaultHasher>> {
     buckets: Vec<LinkedList<(K, V)>>, // Вектор связных списков для разрешения коллизий
     items_count: usize, // Количество элементов в хэш-таблице
     load_factor: f64, // Коэффициент загрузки для определения момента рехеширования
+    hash_builder: S, // Строитель хэшеров для ключей
+    min_buckets: usize, // Меньше этого числа корзин таблица сама не сжимается
+    rehash_count: usize, // Сколько раз таблица перестраивалась
+    incremental: bool, // Переносить ли элементы при рехешировании постепенно
+    old_buckets: Vec<LinkedList<(K, V)>>, // Корзины прежней таблицы, пока идет перенос
+    migrated: usize, // Сколько корзин прежней таблицы уже перенесено
+}
+
+/// Таблица сжимается, когда ее заполненность падает ниже этой доли от
+/// коэффициента загрузки.
+const SHRINK_THRESHOLD: f64 = 0.25;
+
+/// Сколько корзин прежней таблицы переносится за одну операцию при
+/// постепенном рехешировании.
+const REHASH_STEP: usize = 4;
+
+/// Сведения о распределении элементов по корзинам, см. `HashMap::stats`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HashMapStats {
+    pub items: usize,
+    /// Корзины таблицы вместе с еще не перенесенными корзинами прежней.
+    pub buckets: usize,
+    /// Наибольший допустимый коэффициент загрузки.
+    pub load_factor: f64,
+    /// Самая длинная цепочка коллизий.
+    pub longest_chain: usize,
+    /// `chain_histogram[n]` — количество корзин с цепочкой длины `n`.
+    pub chain_histogram: Vec<usize>,
+    /// Сколько раз таблица перестраивалась с момента создания.
+    pub rehashes: usize,
+    /// Корзины прежней таблицы, которые еще не перенесены при постепенном
+    /// рехешировании. Они входят в `buckets` и учтены в гистограмме.
+    pub pending_buckets: usize,
 }
 
 impl<K: Eq + Hash, V> HashMap<K, V> {
@@ -19,14 +70,206 @@ impl<K: Eq + Hash, V> HashMap<K, V> {
     /// let mut map: HashMap<i32, String> = HashMap::new();
     /// ```
     pub fn new() -> Self {
+        Self::with_hasher(BuildHasherDefault::default())
+    }
+
+    /// Создает хэш-таблицу, в которую поместится `capacity` элементов без
+    /// рехеширования. Удаления не сжимают ее меньше этого размера.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::HashMap;
+    /// let mut map: HashMap<i32, i32> = HashMap::with_capacity(1000);
+    /// assert!(map.capacity() >= 1000);
+    /// for i in 0..1000 {
+    ///     map.insert(i, i);
+    /// }
+    /// assert_eq!(map.stats().rehashes, 0);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, BuildHasherDefault::default())
+    }
+
+    /// Создает хэш-таблицу с указанным коэффициентом загрузки: средней
+    /// длиной цепочки, после которой таблица растет.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `load_factor` не положителен.
+    pub fn with_load_factor(load_factor: f64) -> Self {
+        let mut map: Self = Self::new();
+        map.set_load_factor(load_factor);
+        map
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> HashMap<K, V, S> {
+    /// Создает новую хэш-таблицу, хэширующую ключи хэшерами из `hash_builder`.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::{FxBuildHasher, HashMap};
+    /// let mut map: HashMap<u64, &str, FxBuildHasher> = HashMap::with_hasher(FxBuildHasher::default());
+    /// map.insert(1, "один");
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self {
         let initial_capacity: usize = 1;
         HashMap {
             buckets: Vec::with_capacity(initial_capacity),
             items_count: 0,
             load_factor: 2.0,
+            hash_builder,
+            min_buckets: initial_capacity,
+            rehash_count: 0,
+            incremental: false,
+            old_buckets: Vec::new(),
+            migrated: 0,
         }.initialize_buckets(initial_capacity)
     }
 
+    /// Создает хэш-таблицу с хэшерами из `hash_builder`, в которую
+    /// поместится `capacity` элементов без рехеширования.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        let mut map: Self = Self::with_hasher(hash_builder);
+        let buckets: usize = map.buckets_for(capacity);
+        map.min_buckets = buckets;
+        map.rehash(buckets);
+        map.rehash_count = 0;
+        map
+    }
+
+    /// Возвращает, сколько элементов поместится в таблицу без рехеширования.
+    pub fn capacity(&self) -> usize {
+        (self.buckets.len() as f64 * self.load_factor) as usize
+    }
+
+    pub fn load_factor(&self) -> f64 {
+        self.load_factor
+    }
+
+    /// Задает коэффициент загрузки и сразу перестраивает таблицу, если она
+    /// заполнена сильнее.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `load_factor` не положителен.
+    pub fn set_load_factor(&mut self, load_factor: f64) {
+        assert!(load_factor > 0.0, "The load factor must be positive.");
+        self.load_factor = load_factor;
+        if self.current_load_factor() > load_factor {
+            self.rehash(self.buckets_for(self.items_count));
+        }
+    }
+
+    /// Включает или выключает постепенное рехеширование. При выключении
+    /// незаконченный перенос доводится до конца сразу.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::HashMap;
+    /// let mut map: HashMap<i32, i32> = HashMap::new();
+    /// map.set_incremental_rehash(true);
+    /// for i in 0..100 {
+    ///     map.insert(i, i);
+    /// }
+    /// assert!(map.is_rehashing());
+    /// assert_eq!(map.get(&0), Some(&0));
+    /// ```
+    pub fn set_incremental_rehash(&mut self, enabled: bool) {
+        self.incremental = enabled;
+        if !enabled {
+            self.finish_rehash();
+        }
+    }
+
+    /// Проверяет, идет ли сейчас постепенный перенос элементов.
+    pub fn is_rehashing(&self) -> bool {
+        !self.old_buckets.is_empty()
+    }
+
+    /// Готовит таблицу к вставке еще `additional` элементов, чтобы при
+    /// этом не понадобилось рехеширование.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::HashMap;
+    /// let mut map: HashMap<i32, i32> = HashMap::new();
+    /// map.reserve(100);
+    /// assert!(map.capacity() >= 100);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let needed: usize = self.buckets_for(self.items_count + additional);
+        if needed > self.buckets.len() {
+            self.rehash(needed);
+        }
+    }
+
+    /// Сжимает таблицу до наименьшего числа корзин, в котором ее элементы
+    /// помещаются без превышения коэффициента загрузки.
+    pub fn shrink_to_fit(&mut self) {
+        self.min_buckets = 1;
+        self.rehash(self.buckets_for(self.items_count));
+    }
+
+    /// Возвращает распределение элементов по корзинам.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::HashMap;
+    /// let mut map: HashMap<i32, i32> = HashMap::new();
+    /// map.insert(1, 1);
+    /// let stats = map.stats();
+    /// assert_eq!(stats.items, 1);
+    /// assert_eq!(stats.chain_histogram.iter().sum::<usize>(), stats.buckets);
+    /// ```
+    pub fn stats(&self) -> HashMapStats {
+        let pending: &[LinkedList<(K, V)>] = self.old_buckets.get(self.migrated..).unwrap_or(&[]);
+        let chains = || self.buckets.iter().chain(pending).map(|bucket| bucket.len());
+        let longest_chain: usize = chains().max().unwrap_or(0);
+        let mut chain_histogram: Vec<usize> = vec![0; longest_chain + 1];
+        for length in chains() {
+            chain_histogram[length] += 1;
+        }
+        HashMapStats {
+            items: self.items_count,
+            buckets: self.buckets.len() + pending.len(),
+            load_factor: self.load_factor,
+            longest_chain,
+            chain_histogram,
+            rehashes: self.rehash_count,
+            pending_buckets: pending.len(),
+        }
+    }
+
+    /// Возвращает количество корзин, в которых `items` элементов помещаются
+    /// без превышения коэффициента загрузки.
+    fn buckets_for(&self, items: usize) -> usize {
+        ((items as f64 / self.load_factor).ceil() as usize).max(1)
+    }
+
+    /// Сжимает таблицу, если после удалений она заполнена меньше чем на
+    /// `SHRINK_THRESHOLD` от коэффициента загрузки. После сжатия остается
+    /// запас вдвое, чтобы чередование вставок и удалений не вызывало
+    /// рехеширование каждый раз.
+    fn shrink_if_sparse(&mut self) {
+        if self.buckets.len() > self.min_buckets
+            && self.current_load_factor() < self.load_factor * SHRINK_THRESHOLD
+        {
+            let new_size: usize = self.buckets_for(self.items_count * 2).max(self.min_buckets);
+            self.rehash(new_size);
+        }
+    }
+
+    /// Возвращает строитель хэшеров таблицы.
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
     /// Инициализирует внутренние корзины хэш-таблицы.
     fn initialize_buckets(mut self, capacity: usize) -> Self {
         for _ in 0..capacity {
@@ -35,55 +278,171 @@ impl<K: Eq + Hash, V> HashMap<K, V> {
         self
     }
 
-    /// Вставляет ключ и значение в хэш-таблицу.
+    /// Возвращает количество элементов в хэш-таблице.
+    pub fn len(&self) -> usize {
+        self.items_count
+    }
+
+    /// Проверяет, пуста ли хэш-таблица.
+    pub fn is_empty(&self) -> bool {
+        self.items_count == 0
+    }
+
+    /// Вставляет ключ и значение в хэш-таблицу. Если ключ уже есть, его
+    /// значение заменяется.
+    ///
+    /// Возвращает прежнее значение ключа или `None`, если ключа не было.
     ///
     /// # Примеры
     ///
     /// ```
     /// use your_crate::HashMap;
     /// let mut map = HashMap::new();
-    /// map.insert(1, "один");
+    /// assert_eq!(map.insert(1, "один"), None);
+    /// assert_eq!(map.insert(1, "uno"), Some("один"));
+    /// assert_eq!(map.get(&1), Some(&"uno"));
     /// ```
-    pub fn insert(&mut self, key: K, value: V) {
-        let index: usize = self.get_index(&key);
-        if self.buckets.is_empty() || index >= self.buckets.len() {
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.rehash_step();
+        match self.find_mut(&key) {
+            Some(old) => Some(std::mem::replace(old, value)),
+            None => {
+                self.insert_new(key, value);
+                None
+            }
+        }
+    }
+
+    /// Вставляет ключ, которого точно нет в таблице, расширяя ее заранее,
+    /// если коэффициент загрузки превысит порог.
+    ///
+    /// Возвращает ссылку на вставленное значение.
+    fn insert_new(&mut self, key: K, value: V) -> &mut V {
+        if self.buckets.is_empty() {
             self.resize();
         }
-        if !self.buckets[index].iter().any(|(k, _)| *k == key) {
-            self.buckets[index].push_back((key, value));
-            self.items_count += 1;
-            if self.current_load_factor() > self.load_factor {
-                self.rehash(self.buckets.len() * 2 + 1);
-            }
+        // Таблица, уже заполненная до порога, растет до вставки, чтобы
+        // ссылка на новое значение не потерялась при рехешировании.
+        if self.current_load_factor() >= self.load_factor {
+            self.rehash(self.buckets.len() * 2 + 1);
         }
+        let index: usize = self.get_index(&key);
+        self.items_count += 1;
+        let bucket: &mut LinkedList<(K, V)> = &mut self.buckets[index];
+        bucket.push_back((key, value));
+        &mut bucket.back_mut().unwrap().1
     }
 
     /// Удаляет значение по указанному ключу из хэш-таблицы.
     ///
+    /// Возвращает удаленное значение или `None`, если ключа не было.
+    ///
     /// # Примеры
     ///
     /// ```
     /// use your_crate::HashMap;
     /// let mut map = HashMap::new();
     /// map.insert(1, "один");
-    /// map.remove(&1);
+    /// assert_eq!(map.remove(&1), Some("один"));
+    /// assert_eq!(map.remove(&1), None);
     /// ```
-    pub fn remove(&mut self, key: &K) {
-        let index: usize = self.get_index(key);
-        let bucket: &mut LinkedList<(K, V)> = &mut self.buckets[index];
-        let mut new_bucket: LinkedList<(K, V)> = LinkedList::new();
-        while let Some((node_key, node_value)) = bucket.pop_front() {
-            if &node_key != key {
-                new_bucket.push_back((node_key, node_value));
-            } else {
-                self.items_count -= 1;
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.rehash_step();
+        let (index, old_index) = self.bucket_indices(key);
+        let value: V = match remove_from(&mut self.buckets[index], key) {
+            Some(value) => value,
+            None => remove_from(&mut self.old_buckets[old_index?], key)?,
+        };
+        self.items_count -= 1;
+        self.shrink_if_sparse();
+        Some(value)
+    }
+
+    /// Проверяет, есть ли в хэш-таблице указанный ключ.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Удаляет все элементы, сохраняя выделенные корзины. Незаконченный
+    /// перенос прекращается: переносить больше нечего.
+    pub fn clear(&mut self) {
+        for bucket in self.buckets.iter_mut() {
+            bucket.clear();
+        }
+        self.old_buckets = Vec::new();
+        self.migrated = 0;
+        self.items_count = 0;
+    }
+
+    /// Оставляет только элементы, для которых `keep` возвращает `true`.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::HashMap;
+    /// let mut map: HashMap<i32, i32> = (0..10).map(|i| (i, i * i)).collect();
+    /// map.retain(|key, _| key % 2 == 0);
+    /// assert_eq!(map.len(), 5);
+    /// ```
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut keep: F) {
+        for bucket in self.buckets.iter_mut().chain(self.old_buckets.iter_mut()) {
+            let mut kept: LinkedList<(K, V)> = LinkedList::new();
+            while let Some((key, mut value)) = bucket.pop_front() {
+                match keep(&key, &mut value) {
+                    true => kept.push_back((key, value)),
+                    false => self.items_count -= 1,
+                }
             }
+            *bucket = kept;
         }
-        *bucket = new_bucket;
+        self.shrink_if_sparse();
+    }
+
+    /// Возвращает запись для ключа, через которую можно прочитать, изменить
+    /// или вставить значение за один поиск.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::HashMap;
+    /// let mut counts: HashMap<&str, u32> = HashMap::new();
+    /// for word in ["а", "б", "а"] {
+    ///     *counts.entry(word).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(counts.get(&"а"), Some(&2));
+    ///
+    /// counts.entry("б").and_modify(|count| *count *= 10).or_insert(1);
+    /// assert_eq!(counts.get(&"б"), Some(&10));
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        self.rehash_step();
+        let (index, old_index) = self.bucket_indices(&key);
+        let bucket: &mut LinkedList<(K, V)> = match self.buckets[index].iter().any(|(k, _)| *k == key) {
+            true => &mut self.buckets[index],
+            false => match old_index {
+                Some(old_index) if self.old_buckets[old_index].iter().any(|(k, _)| *k == key) => {
+                    &mut self.old_buckets[old_index]
+                }
+                _ => return Entry::Vacant(VacantEntry { map: self, key }),
+            },
+        };
+        let entry: &mut (K, V) = bucket.iter_mut().find(|(k, _)| *k == key).unwrap();
+        Entry::Occupied(OccupiedEntry { entry })
     }
 
     /// Возвращает ссылку на значение, связанное с указанным ключом.
     ///
+    /// Ключ можно передать в заимствованной форме: таблицу с ключами
+    /// `String` можно спрашивать по `&str` без выделения памяти.
+    ///
     /// # Примеры
     ///
     /// ```
@@ -91,10 +450,56 @@ impl<K: Eq + Hash, V> HashMap<K, V> {
     /// let mut map = HashMap::new();
     /// map.insert(1, "один");
     /// assert_eq!(map.get(&1), Some(&"один"));
+    ///
+    /// let mut names: HashMap<String, u32> = HashMap::new();
+    /// names.insert("один".to_string(), 1);
+    /// assert_eq!(names.get("один"), Some(&1));
     /// ```
-    pub fn get(&self, key: &K) -> Option<&V> {
-        let index: usize = self.get_index(key);
-        self.buckets[index].iter().find_map(|(k, v)| if k == key { Some(v) } else { None })
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (index, old_index) = self.bucket_indices(key);
+        let pending: Option<&LinkedList<(K, V)>> = old_index.map(|old_index| &self.old_buckets[old_index]);
+        self.buckets[index]
+            .iter()
+            .chain(pending.into_iter().flatten())
+            .find_map(|(k, v)| if k.borrow() == key { Some(v) } else { None })
+    }
+
+    /// Возвращает изменяемую ссылку на значение, связанное с указанным ключом.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::HashMap;
+    /// let mut map = HashMap::new();
+    /// map.insert(1, 10);
+    /// *map.get_mut(&1).unwrap() += 1;
+    /// assert_eq!(map.get(&1), Some(&11));
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.rehash_step();
+        self.find_mut(key)
+    }
+
+    /// Ищет значение ключа в обеих таблицах, не продвигая перенос.
+    fn find_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (index, old_index) = self.bucket_indices(key);
+        let pending: Option<&mut LinkedList<(K, V)>> = old_index.map(|old_index| &mut self.old_buckets[old_index]);
+        self.buckets[index]
+            .iter_mut()
+            .chain(pending.into_iter().flatten())
+            .find_map(|(k, v)| if (*k).borrow() == key { Some(v) } else { None })
     }
 
     /// Вычисляет текущий коэффициент загрузки хэш-таблицы.
@@ -106,9 +511,22 @@ impl<K: Eq + Hash, V> HashMap<K, V> {
     }
 
     /// Выполняет рехеширование хэш-таблицы, изменяя размер внутреннего вектора корзин.
-    /// Этот метод вызывается, когда коэффициент загрузки превышает установленный порог.
+    /// Этот метод вызывается, когда коэффициент загрузки превышает установленный порог,
+    /// а также при резервировании и сжатии.
     fn rehash(&mut self, new_size: usize) {
-        if new_size <= self.buckets.len() {
+        if new_size == self.buckets.len() || new_size == 0 {
+            return;
+        }
+        // Новый перенос начинается только после окончания прежнего.
+        self.finish_rehash();
+        self.rehash_count += 1;
+
+        if self.incremental {
+            let mut buckets: Vec<LinkedList<(K, V)>> = Vec::with_capacity(new_size);
+            buckets.resize_with(new_size, LinkedList::new);
+            self.old_buckets = std::mem::replace(&mut self.buckets, buckets);
+            self.migrated = 0;
+            self.rehash_step();
             return;
         }
 
@@ -131,6 +549,49 @@ impl<K: Eq + Hash, V> HashMap<K, V> {
         }
     }
 
+    /// Переносит в новую таблицу очередные `REHASH_STEP` корзин прежней,
+    /// если идет постепенное рехеширование.
+    fn rehash_step(&mut self) {
+        self.migrate(REHASH_STEP);
+    }
+
+    /// Доводит постепенное рехеширование до конца.
+    fn finish_rehash(&mut self) {
+        self.migrate(usize::MAX);
+    }
+
+    /// Переносит не более `count` корзин прежней таблицы. Когда перенесены
+    /// все, прежняя таблица освобождается.
+    fn migrate(&mut self, count: usize) {
+        if self.old_buckets.is_empty() {
+            return;
+        }
+        let end: usize = self.old_buckets.len().min(self.migrated.saturating_add(count));
+        while self.migrated < end {
+            let bucket: LinkedList<(K, V)> = std::mem::take(&mut self.old_buckets[self.migrated]);
+            for (key, value) in bucket {
+                let index: usize = self.get_index(&key);
+                self.buckets[index].push_back((key, value));
+            }
+            self.migrated += 1;
+        }
+        if self.migrated == self.old_buckets.len() {
+            self.old_buckets = Vec::new();
+            self.migrated = 0;
+        }
+    }
+
+    /// Вычисляет индекс корзины ключа в текущей таблице и, если ключ может
+    /// быть еще не перенесен, в прежней. Хэш считается один раз.
+    fn bucket_indices<Q: ?Sized + Hash>(&self, key: &Q) -> (usize, Option<usize>) {
+        let hash: usize = self.hash_builder.hash_one(key) as usize;
+        let old_index: Option<usize> = match self.old_buckets.len() {
+            0 => None,
+            size => Some(hash % size).filter(|index| *index >= self.migrated),
+        };
+        (hash % self.buckets.len(), old_index)
+    }
+
     /// Увеличивает размер хэш-таблицы, удваивая количество корзин.
     /// Этот метод вызывается, когда требуется расширить хэш-таблицу для улучшения производительности или вместимости.
     fn resize(&mut self) {
@@ -145,7 +606,7 @@ impl<K: Eq + Hash, V> HashMap<K, V> {
     /// Использует хэш-функцию ключа и размер хэш-таблицы для определения индекса.
     ///
     /// Возвращает индекс вектора корзин, в который должен быть помещен элемент.
-    fn get_index(&self, key: &K) -> usize {
+    fn get_index<Q: ?Sized + Hash>(&self, key: &Q) -> usize {
         self.get_index_for_key(key, self.buckets.len())
     }
 
@@ -153,9 +614,438 @@ impl<K: Eq + Hash, V> HashMap<K, V> {
     /// Этот метод используется внутренне при рехешировании для определения новых индексов элементов.
     ///
     /// Возвращает индекс вектора корзин, в который должен быть помещен элемент.
-    fn get_index_for_key(&self, key: &K, size: usize) -> usize {
-        let mut hasher: DefaultHasher = DefaultHasher::new();
-        key.hash(&mut hasher);
-        (hasher.finish() as usize) % size
+    fn get_index_for_key<Q: ?Sized + Hash>(&self, key: &Q, size: usize) -> usize {
+        (self.hash_builder.hash_one(key) as usize) % size
+    }
+}
+
+/// Удаляет ключ из корзины.
+///
+/// Возвращает значение ключа или `None`, если его в корзине не было.
+fn remove_from<K: Borrow<Q>, V, Q: ?Sized + Eq>(bucket: &mut LinkedList<(K, V)>, key: &Q) -> Option<V> {
+    let position: usize = bucket.iter().position(|(k, _)| k.borrow() == key)?;
+    // Список разрезается перед найденным узлом, а хвост без него
+    // приклеивается обратно.
+    let mut tail: LinkedList<(K, V)> = bucket.split_off(position);
+    let (_, value) = tail.pop_front().unwrap();
+    bucket.append(&mut tail);
+    Some(value)
+}
+
+/// Запись хэш-таблицы для одного ключа, см. `HashMap::entry`.
+pub enum Entry<'a, K, V, S> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+/// Запись для ключа, который есть в таблице.
+pub struct OccupiedEntry<'a, K, V> {
+    entry: &'a mut (K, V),
+}
+
+/// Запись для ключа, которого в таблице нет.
+pub struct VacantEntry<'a, K, V, S> {
+    map: &'a mut HashMap<K, V, S>,
+    key: K,
+}
+
+impl<'a, K: Eq + Hash, V, S: BuildHasher> Entry<'a, K, V, S> {
+    /// Возвращает ключ записи.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Вставляет `default`, если ключа нет.
+    ///
+    /// Возвращает изменяемую ссылку на значение ключа.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Вставляет результат `default()`, если ключа нет. Функция не
+    /// вызывается, если ключ есть.
+    ///
+    /// Возвращает изменяемую ссылку на значение ключа.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Изменяет значение функцией `f`, если ключ есть.
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K: Eq + Hash, V: Default, S: BuildHasher> Entry<'a, K, V, S> {
+    /// Вставляет `V::default()`, если ключа нет.
+    ///
+    /// Возвращает изменяемую ссылку на значение ключа.
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.entry.0
+    }
+
+    pub fn get(&self) -> &V {
+        &self.entry.1
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.entry.1
+    }
+
+    /// Превращает запись в изменяемую ссылку на значение, живущую столько
+    /// же, сколько заимствование таблицы.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.entry.1
+    }
+
+    /// Заменяет значение и возвращает прежнее.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(&mut self.entry.1, value)
+    }
+}
+
+impl<'a, K: Eq + Hash, V, S: BuildHasher> VacantEntry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Вставляет значение под ключом записи.
+    ///
+    /// Возвращает изменяемую ссылку на вставленное значение.
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.map.insert_new(self.key, value)
+    }
+}
+
+impl<K, V, S> HashMap<K, V, S> {
+    /// Возвращает итератор по парам ключ-значение в произвольном порядке.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::HashMap;
+    /// let mut map = HashMap::new();
+    /// map.insert(1, "один");
+    /// map.insert(2, "два");
+    /// for (key, value) in map.iter() {
+    ///     println!("{}: {}", key, value);
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.buckets.iter().chain(self.old_buckets.iter()).flatten(),
+            remaining: self.items_count,
+        }
+    }
+
+    /// Возвращает итератор по парам ключ-значение с изменяемыми значениями.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            inner: self.buckets.iter_mut().chain(self.old_buckets.iter_mut()).flatten(),
+            remaining: self.items_count,
+        }
+    }
+
+    /// Возвращает итератор по ключам.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Возвращает итератор по значениям.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    /// Возвращает итератор по изменяемым значениям.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut { inner: self.iter_mut() }
+    }
+
+    /// Забирает все элементы из таблицы, сохраняя выделенные корзины.
+    /// Элементы, не пройденные итератором, удаляются вместе с ним.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::HashMap;
+    /// let mut map = HashMap::new();
+    /// map.insert(1, "один");
+    /// let drained: Vec<(i32, &str)> = map.drain().collect();
+    /// assert_eq!(drained, vec![(1, "один")]);
+    /// assert!(map.is_empty());
+    /// ```
+    pub fn drain(&mut self) -> Drain<'_, K, V> {
+        let remaining: usize = std::mem::replace(&mut self.items_count, 0);
+        let take: TakeBucket<K, V> = std::mem::take;
+        Drain {
+            inner: self.buckets.iter_mut().chain(self.old_buckets.iter_mut()).flat_map(take),
+            remaining,
+        }
+    }
+}
+
+type Bucket<K, V> = LinkedList<(K, V)>;
+
+/// Забирает содержимое корзины, оставляя на ее месте пустой список.
+type TakeBucket<K, V> = fn(&mut Bucket<K, V>) -> Bucket<K, V>;
+
+/// Корзины обеих таблиц: текущей и прежней, если идет перенос.
+type BothTables<I> = Chain<I, I>;
+
+type DrainBuckets<'a, K, V> = FlatMap<BothTables<slice::IterMut<'a, Bucket<K, V>>>, Bucket<K, V>, TakeBucket<K, V>>;
+
+/// Итератор по парам ключ-значение, см. `HashMap::iter`.
+pub struct Iter<'a, K, V> {
+    inner: Flatten<BothTables<slice::Iter<'a, Bucket<K, V>>>>,
+    remaining: usize, // Количество еще не выданных элементов
+}
+
+/// Итератор по парам ключ-значение с изменяемыми значениями, см. `HashMap::iter_mut`.
+pub struct IterMut<'a, K, V> {
+    inner: Flatten<BothTables<slice::IterMut<'a, Bucket<K, V>>>>,
+    remaining: usize,
+}
+
+/// Итератор, забирающий элементы таблицы, см. `HashMap::into_iter`.
+pub struct IntoIter<K, V> {
+    inner: Flatten<BothTables<vec::IntoIter<Bucket<K, V>>>>,
+    remaining: usize,
+}
+
+/// Итератор, забирающий элементы и оставляющий таблицу пустой, см. `HashMap::drain`.
+pub struct Drain<'a, K, V> {
+    inner: DrainBuckets<'a, K, V>,
+    remaining: usize,
+}
+
+/// Итератор по ключам, см. `HashMap::keys`.
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+/// Итератор по значениям, см. `HashMap::values`.
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+/// Итератор по изменяемым значениям, см. `HashMap::values_mut`.
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.inner.next()?;
+        self.remaining -= 1;
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.inner.next()?;
+        self.remaining -= 1;
+        Some((&*key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry: (K, V) = self.inner.next()?;
+        self.remaining -= 1;
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> Iterator for Drain<'a, K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry: (K, V) = self.inner.next()?;
+        self.remaining -= 1;
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> Drop for Drain<'a, K, V> {
+    /// Удаляет элементы, до которых итератор не дошел, чтобы таблица
+    /// осталась пустой.
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {}
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+impl<'a, K, V> ExactSizeIterator for Drain<'a, K, V> {}
+impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {}
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
+impl<'a, K, V> ExactSizeIterator for ValuesMut<'a, K, V> {}
+
+impl<K, V, S> IntoIterator for HashMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    /// Превращает таблицу в итератор, забирающий ее элементы.
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter {
+            inner: self.buckets.into_iter().chain(self.old_buckets).flatten(),
+            remaining: self.items_count,
+        }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a HashMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut HashMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for HashMap<K, V, S> {
+    /// Собирает таблицу из пар; при повторе ключа остается последнее значение.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map: HashMap<K, V, S> = HashMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Extend<(K, V)> for HashMap<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K, Q, V, S> Index<&Q> for HashMap<K, V, S>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: ?Sized + Hash + Eq,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    /// Возвращает ссылку на значение ключа.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если ключа нет в таблице.
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("The key is not in the map.")
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for HashMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Таблицы равны, если в них одни и те же ключи с равными значениями,
+/// независимо от порядка вставки и числа корзин.
+impl<K: Eq + Hash, V: PartialEq, S: BuildHasher> PartialEq for HashMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for HashMap<K, V, S> {}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for HashMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
     }
 }
@@ -0,0 +1,307 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{self, Debug};
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+use std::iter::{Chain, FromIterator};
+
+use super::hash_map::{self, HashMap};
+
+/// Структура `HashSet` — множество на основе `HashMap` с пустыми значениями.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::HashSet;
+/// let a: HashSet<i32> = (1..=4).collect();
+/// let b: HashSet<i32> = (3..=6).collect();
+/// let mut common: Vec<i32> = a.intersection(&b).copied().collect();
+/// common.sort();
+/// assert_eq!(common, vec![3, 4]);
+/// assert_eq!(a.union(&b).count(), 6);
+/// ```
+#[derive(Clone)]
+pub struct HashSet<T, S = BuildHasherDefault<DefaultHasher>> {
+    map: HashMap<T, (), S>, // Элементы множества хранятся ключами таблицы
+}
+
+impl<T: Eq + Hash> HashSet<T> {
+    /// Создает новое пустое множество.
+    pub fn new() -> Self {
+        HashSet { map: HashMap::new() }
+    }
+
+    /// Создает множество, в которое поместится `capacity` элементов без
+    /// рехеширования.
+    pub fn with_capacity(capacity: usize) -> Self {
+        HashSet { map: HashMap::with_capacity(capacity) }
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> HashSet<T, S> {
+    /// Создает новое множество, хэширующее элементы хэшерами из `hash_builder`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        HashSet { map: HashMap::with_hasher(hash_builder) }
+    }
+
+    /// Возвращает количество элементов.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Проверяет, пусто ли множество.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Добавляет элемент.
+    ///
+    /// Возвращает `true`, если элемента в множестве не было.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::HashSet;
+    /// let mut set = HashSet::new();
+    /// assert!(set.insert(1));
+    /// assert!(!set.insert(1));
+    /// ```
+    pub fn insert(&mut self, value: T) -> bool {
+        self.map.insert(value, ()).is_none()
+    }
+
+    /// Удаляет элемент.
+    ///
+    /// Возвращает `true`, если элемент был в множестве.
+    pub fn remove<Q>(&mut self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.remove(value).is_some()
+    }
+
+    /// Проверяет, есть ли элемент в множестве.
+    pub fn contains<Q>(&self, value: &Q) -> bool
+    where
+        T: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.contains_key(value)
+    }
+
+    /// Удаляет все элементы.
+    pub fn clear(&mut self) {
+        self.map.clear();
+    }
+
+    /// Оставляет только элементы, для которых `keep` возвращает `true`.
+    pub fn retain<F: FnMut(&T) -> bool>(&mut self, mut keep: F) {
+        self.map.retain(|value, _| keep(value));
+    }
+
+    /// Возвращает итератор по элементам в произвольном порядке.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter { inner: self.map.keys() }
+    }
+
+    /// Возвращает итератор по элементам, которые есть в `self` или в `other`.
+    /// Общие элементы выдаются один раз.
+    pub fn union<'a>(&'a self, other: &'a HashSet<T, S>) -> Union<'a, T, S> {
+        Union { inner: self.iter().chain(other.difference(self)) }
+    }
+
+    /// Возвращает итератор по элементам, которые есть и в `self`, и в `other`.
+    /// Проходится меньшее из множеств.
+    pub fn intersection<'a>(&'a self, other: &'a HashSet<T, S>) -> Intersection<'a, T, S> {
+        let (smaller, larger): (&HashSet<T, S>, &HashSet<T, S>) = match self.len() <= other.len() {
+            true => (self, other),
+            false => (other, self),
+        };
+        Intersection { iter: smaller.iter(), other: larger }
+    }
+
+    /// Возвращает итератор по элементам `self`, которых нет в `other`.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::HashSet;
+    /// let a: HashSet<i32> = (1..=4).collect();
+    /// let b: HashSet<i32> = (3..=6).collect();
+    /// let mut only_a: Vec<i32> = a.difference(&b).copied().collect();
+    /// only_a.sort();
+    /// assert_eq!(only_a, vec![1, 2]);
+    /// ```
+    pub fn difference<'a>(&'a self, other: &'a HashSet<T, S>) -> Difference<'a, T, S> {
+        Difference { iter: self.iter(), other }
+    }
+
+    /// Возвращает итератор по элементам, которые есть ровно в одном из
+    /// множеств.
+    pub fn symmetric_difference<'a>(&'a self, other: &'a HashSet<T, S>) -> SymmetricDifference<'a, T, S> {
+        SymmetricDifference { inner: self.difference(other).chain(other.difference(self)) }
+    }
+
+    /// Проверяет, все ли элементы `self` есть в `other`.
+    pub fn is_subset(&self, other: &HashSet<T, S>) -> bool {
+        self.len() <= other.len() && self.iter().all(|value| other.contains(value))
+    }
+
+    /// Проверяет, все ли элементы `other` есть в `self`.
+    pub fn is_superset(&self, other: &HashSet<T, S>) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Проверяет, что у множеств нет общих элементов.
+    pub fn is_disjoint(&self, other: &HashSet<T, S>) -> bool {
+        self.intersection(other).next().is_none()
+    }
+}
+
+/// Итератор по элементам множества, см. `HashSet::iter`.
+pub struct Iter<'a, T> {
+    inner: hash_map::Keys<'a, T, ()>,
+}
+
+/// Итератор, забирающий элементы множества, см. `HashSet::into_iter`.
+pub struct IntoIter<T> {
+    inner: hash_map::IntoIter<T, ()>,
+}
+
+/// Итератор по объединению множеств, см. `HashSet::union`.
+pub struct Union<'a, T, S> {
+    inner: Chain<Iter<'a, T>, Difference<'a, T, S>>,
+}
+
+/// Итератор по пересечению множеств, см. `HashSet::intersection`.
+pub struct Intersection<'a, T, S> {
+    iter: Iter<'a, T>, // Элементы меньшего множества
+    other: &'a HashSet<T, S>,
+}
+
+/// Итератор по разности множеств, см. `HashSet::difference`.
+pub struct Difference<'a, T, S> {
+    iter: Iter<'a, T>,
+    other: &'a HashSet<T, S>,
+}
+
+/// Итератор по симметрической разности множеств, см. `HashSet::symmetric_difference`.
+pub struct SymmetricDifference<'a, T, S> {
+    inner: Chain<Difference<'a, T, S>, Difference<'a, T, S>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> Iterator for IntoIter<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.inner.next().map(|(value, _)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+impl<T> ExactSizeIterator for IntoIter<T> {}
+
+impl<'a, T: Eq + Hash, S: BuildHasher> Iterator for Union<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+}
+
+impl<'a, T: Eq + Hash, S: BuildHasher> Iterator for Intersection<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let other: &HashSet<T, S> = self.other;
+        self.iter.find(|value| other.contains(*value))
+    }
+}
+
+impl<'a, T: Eq + Hash, S: BuildHasher> Iterator for Difference<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        let other: &HashSet<T, S> = self.other;
+        self.iter.find(|value| !other.contains(*value))
+    }
+}
+
+impl<'a, T: Eq + Hash, S: BuildHasher> Iterator for SymmetricDifference<'a, T, S> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        self.inner.next()
+    }
+}
+
+impl<T, S> IntoIterator for HashSet<T, S> {
+    type Item = T;
+    type IntoIter = IntoIter<T>;
+
+    fn into_iter(self) -> IntoIter<T> {
+        IntoIter { inner: self.map.into_iter() }
+    }
+}
+
+impl<'a, T: Eq + Hash, S: BuildHasher> IntoIterator for &'a HashSet<T, S> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T>;
+
+    fn into_iter(self) -> Iter<'a, T> {
+        self.iter()
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> FromIterator<T> for HashSet<T, S> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        let mut set: HashSet<T, S> = HashSet::default();
+        set.extend(iter);
+        set
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> Extend<T> for HashSet<T, S> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        for value in iter {
+            self.insert(value);
+        }
+    }
+}
+
+impl<T: Debug, S> Debug for HashSet<T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.map.keys()).finish()
+    }
+}
+
+/// Множества равны, если в них одни и те же элементы.
+impl<T: Eq + Hash, S: BuildHasher> PartialEq for HashSet<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.map == other.map
+    }
+}
+
+impl<T: Eq + Hash, S: BuildHasher> Eq for HashSet<T, S> {}
+
+impl<T: Eq + Hash, S: BuildHasher + Default> Default for HashSet<T, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
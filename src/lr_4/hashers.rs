@@ -0,0 +1,229 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
+
+/// Хэш-функция FxHash из компилятора Rust.
+///
+/// Очень быстрая на целых числах и коротких ключах, но не защищена от
+/// подбора коллизий: ключи из недоверенного источника лучше хэшировать
+/// `SipBuildHasher`.
+#[derive(Clone, Copy, Default)]
+pub struct FxHasher {
+    hash: u64,
+}
+
+const FX_SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+impl FxHasher {
+    fn add_to_hash(&mut self, word: u64) {
+        self.hash = (self.hash.rotate_left(5) ^ word).wrapping_mul(FX_SEED);
+    }
+}
+
+impl Hasher for FxHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.add_to_hash(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        let mut rest: &[u8] = chunks.remainder();
+        if rest.len() >= 4 {
+            self.add_to_hash(u32::from_le_bytes(rest[..4].try_into().unwrap()) as u64);
+            rest = &rest[4..];
+        }
+        for byte in rest {
+            self.add_to_hash(*byte as u64);
+        }
+    }
+
+    fn write_u8(&mut self, i: u8) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_u16(&mut self, i: u16) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_u32(&mut self, i: u32) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn write_u64(&mut self, i: u64) {
+        self.add_to_hash(i);
+    }
+
+    fn write_usize(&mut self, i: usize) {
+        self.add_to_hash(i as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// 64-битная хэш-функция FNV-1a.
+///
+/// Проста и хорошо перемешивает короткие строки, но, как и FxHash, не
+/// защищена от подбора коллизий.
+#[derive(Clone, Copy)]
+pub struct FnvHasher {
+    hash: u64,
+}
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        FnvHasher { hash: FNV_OFFSET_BASIS }
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.hash ^= *byte as u64;
+            self.hash = self.hash.wrapping_mul(FNV_PRIME);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.hash
+    }
+}
+
+/// Хэш-функция SipHash-2-4 со 128-битным ключом.
+///
+/// Пока ключ неизвестен атакующему, он не может заранее подобрать ключи
+/// таблицы с одинаковыми хэшами (HashDoS).
+#[derive(Clone, Copy)]
+pub struct SipHasher {
+    v0: u64,
+    v1: u64,
+    v2: u64,
+    v3: u64,
+    tail: u64, // Байты, еще не составившие целое 64-битное слово
+    tail_length: usize, // Количество таких байтов
+    length: usize, // Общее количество обработанных байтов
+}
+
+impl SipHasher {
+    /// Создает хэшер с ключом `(k0, k1)`.
+    pub fn new_with_keys(k0: u64, k1: u64) -> Self {
+        SipHasher {
+            v0: k0 ^ 0x736f_6d65_7073_6575,
+            v1: k1 ^ 0x646f_7261_6e64_6f6d,
+            v2: k0 ^ 0x6c79_6765_6e65_7261,
+            v3: k1 ^ 0x7465_6462_7974_6573,
+            tail: 0,
+            tail_length: 0,
+            length: 0,
+        }
+    }
+
+    fn round(&mut self) {
+        self.v0 = self.v0.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(13) ^ self.v0;
+        self.v0 = self.v0.rotate_left(32);
+        self.v2 = self.v2.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(16) ^ self.v2;
+        self.v0 = self.v0.wrapping_add(self.v3);
+        self.v3 = self.v3.rotate_left(21) ^ self.v0;
+        self.v2 = self.v2.wrapping_add(self.v1);
+        self.v1 = self.v1.rotate_left(17) ^ self.v2;
+        self.v2 = self.v2.rotate_left(32);
+    }
+
+    fn compress(&mut self, word: u64) {
+        self.v3 ^= word;
+        self.round();
+        self.round();
+        self.v0 ^= word;
+    }
+}
+
+impl Hasher for SipHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        self.length += bytes.len();
+        let mut bytes: &[u8] = bytes;
+        // Сначала дополняется слово, начатое прошлой записью.
+        while self.tail_length > 0 && !bytes.is_empty() {
+            self.tail |= (bytes[0] as u64) << (8 * self.tail_length);
+            self.tail_length += 1;
+            bytes = &bytes[1..];
+            if self.tail_length == 8 {
+                let word: u64 = self.tail;
+                self.compress(word);
+                self.tail = 0;
+                self.tail_length = 0;
+            }
+        }
+        let mut chunks = bytes.chunks_exact(8);
+        for chunk in &mut chunks {
+            self.compress(u64::from_le_bytes(chunk.try_into().unwrap()));
+        }
+        for byte in chunks.remainder() {
+            self.tail |= (*byte as u64) << (8 * self.tail_length);
+            self.tail_length += 1;
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        let mut state: SipHasher = *self;
+        let last: u64 = ((self.length as u64 & 0xff) << 56) | self.tail;
+        state.compress(last);
+        state.v2 ^= 0xff;
+        for _ in 0..4 {
+            state.round();
+        }
+        state.v0 ^ state.v1 ^ state.v2 ^ state.v3
+    }
+}
+
+/// Строит хэшеры `FxHasher`.
+pub type FxBuildHasher = BuildHasherDefault<FxHasher>;
+
+/// Строит хэшеры `FnvHasher`.
+pub type FnvBuildHasher = BuildHasherDefault<FnvHasher>;
+
+/// Строит хэшеры `SipHasher` с одним и тем же ключом.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::{HashMap, SipBuildHasher};
+/// let mut map = HashMap::with_hasher(SipBuildHasher::random());
+/// map.insert("ключ", 1);
+/// assert_eq!(map.get(&"ключ"), Some(&1));
+/// ```
+#[derive(Clone, Copy)]
+pub struct SipBuildHasher {
+    k0: u64,
+    k1: u64,
+}
+
+impl SipBuildHasher {
+    /// Создает строитель с заданным ключом. Одинаковый ключ дает одинаковые
+    /// хэши от запуска к запуску.
+    pub fn with_keys(k0: u64, k1: u64) -> Self {
+        SipBuildHasher { k0, k1 }
+    }
+
+    /// Создает строитель со случайным ключом, взятым из `RandomState`
+    /// стандартной библиотеки.
+    pub fn random() -> Self {
+        let state: RandomState = RandomState::new();
+        let mut first = state.build_hasher();
+        first.write_u8(0);
+        let mut second = state.build_hasher();
+        second.write_u8(1);
+        SipBuildHasher::with_keys(first.finish(), second.finish())
+    }
+}
+
+impl BuildHasher for SipBuildHasher {
+    type Hasher = SipHasher;
+
+    fn build_hasher(&self) -> SipHasher {
+        SipHasher::new_with_keys(self.k0, self.k1)
+    }
+}
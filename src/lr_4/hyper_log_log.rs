@@ -0,0 +1,146 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+
+/// Ошибки потоковых оценок `HyperLogLog` и `CountMinSketch`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SketchError {
+    /// Оценки разного размера нельзя объединить.
+    ParametersMismatch,
+}
+
+impl fmt::Display for SketchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SketchError::ParametersMismatch => write!(f, "sketches have different parameters"),
+        }
+    }
+}
+
+impl std::error::Error for SketchError {}
+
+/// Структура `HyperLogLog` — оценка количества различных элементов в
+/// потоке за `2^precision` байт памяти.
+///
+/// Первые `precision` битов хэша выбирают регистр, а регистр запоминает
+/// наибольшую позицию первой единицы в остальных битах. Относительная
+/// ошибка оценки около `1.04 / sqrt(2^precision)`; на малых количествах
+/// используется линейный подсчет по пустым регистрам. Регистры двух
+/// оценок с одинаковой точностью объединяются взятием максимума, и
+/// результат равен оценке объединения потоков.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::HyperLogLog;
+/// let mut users = HyperLogLog::new(12);
+/// for i in 0..10_000 {
+///     users.insert(&(i % 1_000));
+/// }
+/// assert!((users.estimate() as i64 - 1_000).abs() < 50);
+/// ```
+#[derive(Clone)]
+pub struct HyperLogLog<S = BuildHasherDefault<DefaultHasher>> {
+    registers: Vec<u8>, // По регистру на каждое значение первых битов хэша
+    precision: u32, // Количество битов хэша, выбирающих регистр
+    hash_builder: S, // Строитель хэшеров для элементов
+}
+
+impl HyperLogLog {
+    /// Создает оценку с `2^precision` регистрами.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `precision` не лежит между 4 и 16.
+    pub fn new(precision: u32) -> Self {
+        Self::with_hasher(precision, BuildHasherDefault::default())
+    }
+}
+
+impl<S: BuildHasher> HyperLogLog<S> {
+    /// Создает оценку, хэширующую элементы хэшерами из `hash_builder`.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `precision` не лежит между 4 и 16.
+    pub fn with_hasher(precision: u32, hash_builder: S) -> Self {
+        assert!((4..=16).contains(&precision), "The precision must be between 4 and 16.");
+        HyperLogLog {
+            registers: vec![0; 1 << precision],
+            precision,
+            hash_builder,
+        }
+    }
+
+    /// Возвращает количество битов хэша, выбирающих регистр.
+    pub fn precision(&self) -> u32 {
+        self.precision
+    }
+
+    /// Возвращает ожидаемую относительную ошибку оценки.
+    pub fn relative_error(&self) -> f64 {
+        1.04 / (self.registers.len() as f64).sqrt()
+    }
+
+    /// Учитывает элемент.
+    pub fn insert<T: ?Sized + Hash>(&mut self, item: &T) {
+        let hash: u64 = self.hash_builder.hash_one(item);
+        let index: usize = (hash >> (64 - self.precision)) as usize;
+        // Единица в конце ограничивает ранг, если остальные биты нулевые.
+        let rest: u64 = (hash << self.precision) | (1 << (self.precision - 1));
+        let rank: u8 = rest.leading_zeros() as u8 + 1;
+        self.registers[index] = self.registers[index].max(rank);
+    }
+
+    /// Оценивает количество различных учтенных элементов.
+    pub fn estimate(&self) -> u64 {
+        let count: f64 = self.registers.len() as f64;
+        let alpha: f64 = match self.registers.len() {
+            16 => 0.673,
+            32 => 0.697,
+            64 => 0.709,
+            _ => 0.7213 / (1.0 + 1.079 / count),
+        };
+        let sum: f64 = self.registers.iter().map(|&register| 2f64.powi(-(register as i32))).sum();
+        let raw: f64 = alpha * count * count / sum;
+        let empty: usize = self.registers.iter().filter(|&&register| register == 0).count();
+        match raw <= 2.5 * count && empty > 0 {
+            true => (count * (count / empty as f64).ln()).round() as u64,
+            false => raw.round() as u64,
+        }
+    }
+
+    /// Объединяет регистры с другой оценкой той же точности.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::HyperLogLog;
+    /// let mut monday = HyperLogLog::new(10);
+    /// let mut tuesday = HyperLogLog::new(10);
+    /// monday.insert("анна");
+    /// tuesday.insert("анна");
+    /// tuesday.insert("борис");
+    /// monday.merge(&tuesday).unwrap();
+    /// assert_eq!(monday.estimate(), 2);
+    /// ```
+    pub fn merge(&mut self, other: &HyperLogLog<S>) -> Result<(), SketchError> {
+        if self.precision != other.precision {
+            return Err(SketchError::ParametersMismatch);
+        }
+        for (register, &other) in self.registers.iter_mut().zip(other.registers.iter()) {
+            *register = (*register).max(other);
+        }
+        Ok(())
+    }
+
+    /// Возвращает регистры.
+    pub fn registers(&self) -> &[u8] {
+        &self.registers
+    }
+
+    /// Забывает все элементы.
+    pub fn clear(&mut self) {
+        self.registers.iter_mut().for_each(|register| *register = 0);
+    }
+}
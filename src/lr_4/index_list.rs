@@ -0,0 +1,226 @@
+/// Индекс, обозначающий отсутствие соседа.
+const NIL: usize = usize::MAX;
+
+/// Узел списка.
+#[derive(Clone)]
+struct Node<T> {
+    value: Option<T>, // `None` у освобожденных узлов
+    prev: usize,
+    next: usize,
+}
+
+/// Структура `IndexList` — двусвязный список, узлы которого лежат в `Vec` и
+/// связаны индексами, а не указателями.
+///
+/// Индекс узла не меняется, пока узел в списке, поэтому его можно хранить
+/// в хэш-таблице и за O(1) переставлять или удалять узел. Освобожденные
+/// узлы переиспользуются.
+#[derive(Clone)]
+pub struct IndexList<T> {
+    nodes: Vec<Node<T>>,
+    head: usize, // Первый узел или `NIL`
+    tail: usize, // Последний узел или `NIL`
+    free: Vec<usize>, // Освобожденные узлы
+    len: usize,
+}
+
+impl<T> IndexList<T> {
+    pub fn new() -> Self {
+        IndexList {
+            nodes: Vec::new(),
+            head: NIL,
+            tail: NIL,
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Возвращает индекс первого узла.
+    pub fn front(&self) -> Option<usize> {
+        match self.head {
+            NIL => None,
+            index => Some(index),
+        }
+    }
+
+    /// Возвращает индекс последнего узла.
+    pub fn back(&self) -> Option<usize> {
+        match self.tail {
+            NIL => None,
+            index => Some(index),
+        }
+    }
+
+    /// Возвращает значение узла.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если узла с таким индексом в списке нет.
+    pub fn get(&self, index: usize) -> &T {
+        self.nodes[index].value.as_ref().expect("The node is not in the list.")
+    }
+
+    /// Возвращает изменяемую ссылку на значение узла.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если узла с таким индексом в списке нет.
+    pub fn get_mut(&mut self, index: usize) -> &mut T {
+        self.nodes[index].value.as_mut().expect("The node is not in the list.")
+    }
+
+    /// Вставляет значение в начало списка.
+    ///
+    /// Возвращает индекс нового узла.
+    pub fn push_front(&mut self, value: T) -> usize {
+        let index: usize = self.allocate(value);
+        self.link_front(index);
+        index
+    }
+
+    /// Вставляет значение сразу после узла `after`.
+    ///
+    /// Возвращает индекс нового узла.
+    pub fn insert_after(&mut self, after: usize, value: T) -> usize {
+        let index: usize = self.allocate(value);
+        let next: usize = self.nodes[after].next;
+        self.nodes[index].prev = after;
+        self.nodes[index].next = next;
+        self.nodes[after].next = index;
+        match next {
+            NIL => self.tail = index,
+            next => self.nodes[next].prev = index,
+        }
+        index
+    }
+
+    /// Возвращает индекс узла, следующего за узлом `index`.
+    pub fn next(&self, index: usize) -> Option<usize> {
+        match self.nodes[index].next {
+            NIL => None,
+            next => Some(next),
+        }
+    }
+
+    /// Удаляет узел.
+    ///
+    /// Возвращает значение узла.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если узла с таким индексом в списке нет.
+    pub fn remove(&mut self, index: usize) -> T {
+        let value: T = self.nodes[index].value.take().expect("The node is not in the list.");
+        self.unlink(index);
+        self.free.push(index);
+        self.len -= 1;
+        value
+    }
+
+    /// Удаляет последний узел.
+    pub fn pop_back(&mut self) -> Option<T> {
+        let index: usize = self.back()?;
+        Some(self.remove(index))
+    }
+
+    /// Переставляет узел в начало списка.
+    pub fn move_to_front(&mut self, index: usize) {
+        if self.head != index {
+            self.unlink(index);
+            self.link_front(index);
+        }
+    }
+
+    /// Удаляет все узлы.
+    pub fn clear(&mut self) {
+        *self = IndexList::new();
+    }
+
+    /// Возвращает итератор по значениям от начала списка к концу.
+    pub fn iter(&self) -> Iter<'_, T> {
+        Iter {
+            list: self,
+            index: self.head,
+            remaining: self.len,
+        }
+    }
+
+    /// Размещает значение в свободном узле, еще не связанном с соседями.
+    fn allocate(&mut self, value: T) -> usize {
+        let node: Node<T> = Node { value: Some(value), prev: NIL, next: NIL };
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                self.nodes[index] = node;
+                index
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    fn link_front(&mut self, index: usize) {
+        self.nodes[index].prev = NIL;
+        self.nodes[index].next = self.head;
+        match self.head {
+            NIL => self.tail = index,
+            head => self.nodes[head].prev = index,
+        }
+        self.head = index;
+    }
+
+    fn unlink(&mut self, index: usize) {
+        let (prev, next): (usize, usize) = (self.nodes[index].prev, self.nodes[index].next);
+        match prev {
+            NIL => self.head = next,
+            prev => self.nodes[prev].next = next,
+        }
+        match next {
+            NIL => self.tail = prev,
+            next => self.nodes[next].prev = prev,
+        }
+    }
+}
+
+/// Итератор по значениям списка, см. `IndexList::iter`.
+pub struct Iter<'a, T> {
+    list: &'a IndexList<T>,
+    index: usize, // Следующий узел или `NIL`
+    remaining: usize,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<&'a T> {
+        if self.index == NIL {
+            return None;
+        }
+        let node: &'a Node<T> = &self.list.nodes[self.index];
+        self.index = node.next;
+        self.remaining -= 1;
+        node.value.as_ref()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {}
+
+impl<T> Default for IndexList<T> {
+    fn default() -> Self {
+        IndexList::new()
+    }
+}
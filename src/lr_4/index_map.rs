@@ -0,0 +1,524 @@
+use std::borrow::Borrow;
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{self, Debug};
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+use std::iter::FromIterator;
+use std::ops::Index;
+use std::{slice, vec};
+
+/// Начальное количество корзин индекса.
+const INITIAL_BUCKETS: usize = 8;
+
+/// Элемент вместе с хэшем ключа, чтобы при перестройке индекса не
+/// хэшировать ключи заново.
+#[derive(Clone)]
+struct Item<K, V> {
+    hash: u64,
+    key: K,
+    value: V,
+}
+
+/// Структура `IndexMap` — хэш-таблица, которая помнит порядок вставки.
+///
+/// Элементы лежат в векторе в порядке вставки, а хэш-индекс с цепочками,
+/// как у `HashMap`, хранит для каждого ключа его позицию в векторе.
+/// Поэтому итерация идет в порядке вставки и не зависит от хэшера, а к
+/// элементу можно обратиться и по ключу, и по позиции за O(1). Повторная
+/// вставка ключа меняет значение, но не позицию.
+///
+/// Удалить элемент можно двумя способами: `swap_remove` за O(1) ставит на
+/// его место последний элемент, а `shift_remove` за O(n) сдвигает все
+/// последующие элементы и сохраняет порядок остальных.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::IndexMap;
+/// let mut map = IndexMap::new();
+/// map.insert("в", 3);
+/// map.insert("а", 1);
+/// map.insert("б", 2);
+/// assert_eq!(map.keys().collect::<Vec<_>>(), vec![&"в", &"а", &"б"]);
+/// assert_eq!(map.get_index(1), Some((&"а", &1)));
+/// map.sort_keys();
+/// assert_eq!(map.first(), Some((&"а", &1)));
+/// ```
+#[derive(Clone)]
+pub struct IndexMap<K, V, S = BuildHasherDefault<DefaultHasher>> {
+    items: Vec<Item<K, V>>, // Элементы в порядке вставки
+    buckets: Vec<Vec<usize>>, // Позиции элементов, разложенные по хэшам ключей
+    hash_builder: S, // Строитель хэшеров для ключей
+}
+
+impl<K: Eq + Hash, V> IndexMap<K, V> {
+    /// Создает новую пустую таблицу.
+    pub fn new() -> Self {
+        Self::with_hasher(BuildHasherDefault::default())
+    }
+
+    /// Создает таблицу, в которую поместится `capacity` элементов без
+    /// перестройки индекса.
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, BuildHasherDefault::default())
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> IndexMap<K, V, S> {
+    /// Создает таблицу, хэширующую ключи хэшерами из `hash_builder`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        Self::with_capacity_and_hasher(0, hash_builder)
+    }
+
+    /// Создает таблицу с хэшерами из `hash_builder`, в которую поместится
+    /// `capacity` элементов без перестройки индекса.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        IndexMap {
+            items: Vec::with_capacity(capacity),
+            buckets: vec![Vec::new(); capacity.max(INITIAL_BUCKETS)],
+            hash_builder,
+        }
+    }
+
+    /// Вставляет ключ и значение. Новый ключ становится последним, а у
+    /// существующего меняется только значение.
+    ///
+    /// Возвращает прежнее значение ключа или `None`, если ключа не было.
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        self.insert_full(key, value).1
+    }
+
+    /// То же, что `insert`, но возвращает еще и позицию ключа.
+    pub fn insert_full(&mut self, key: K, value: V) -> (usize, Option<V>) {
+        let hash: u64 = self.hash_builder.hash_one(&key);
+        if let Some(index) = self.find(hash, &key) {
+            return (index, Some(std::mem::replace(&mut self.items[index].value, value)));
+        }
+        let index: usize = self.items.len();
+        self.items.push(Item { hash, key, value });
+        if self.items.len() > self.buckets.len() {
+            self.rebuild_index(self.buckets.len() * 2);
+        } else {
+            let bucket: usize = self.bucket_of(hash);
+            self.buckets[bucket].push(index);
+        }
+        (index, None)
+    }
+
+    /// Возвращает ссылку на значение, связанное с указанным ключом.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index: usize = self.get_index_of(key)?;
+        Some(&self.items[index].value)
+    }
+
+    /// Возвращает изменяемую ссылку на значение, связанное с указанным ключом.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index: usize = self.get_index_of(key)?;
+        Some(&mut self.items[index].value)
+    }
+
+    /// Возвращает позицию ключа или `None`, если ключа нет.
+    pub fn get_index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.find(self.hash_builder.hash_one(key), key)
+    }
+
+    /// Проверяет, есть ли в таблице указанный ключ.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get_index_of(key).is_some()
+    }
+
+    /// Удаляет ключ за O(1), ставя на его место последний элемент.
+    ///
+    /// Возвращает удаленное значение или `None`, если ключа не было.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::IndexMap;
+    /// let mut map: IndexMap<i32, ()> = (1..=4).map(|key| (key, ())).collect();
+    /// map.swap_remove(&2);
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), vec![&1, &4, &3]);
+    /// ```
+    pub fn swap_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index: usize = self.get_index_of(key)?;
+        self.swap_remove_index(index).map(|(_, value)| value)
+    }
+
+    /// Удаляет ключ за O(n), сдвигая все последующие элементы.
+    ///
+    /// Возвращает удаленное значение или `None`, если ключа не было.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::IndexMap;
+    /// let mut map: IndexMap<i32, ()> = (1..=4).map(|key| (key, ())).collect();
+    /// map.shift_remove(&2);
+    /// assert_eq!(map.keys().collect::<Vec<_>>(), vec![&1, &3, &4]);
+    /// ```
+    pub fn shift_remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index: usize = self.get_index_of(key)?;
+        self.shift_remove_index(index).map(|(_, value)| value)
+    }
+
+    /// Удаляет элемент на позиции `index`, ставя на его место последний.
+    pub fn swap_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        if index >= self.items.len() {
+            return None;
+        }
+        self.unlink(index);
+        let last: usize = self.items.len() - 1;
+        if index != last {
+            self.relink(last, index);
+        }
+        let item: Item<K, V> = self.items.swap_remove(index);
+        Some((item.key, item.value))
+    }
+
+    /// Удаляет элемент на позиции `index`, сдвигая все последующие.
+    pub fn shift_remove_index(&mut self, index: usize) -> Option<(K, V)> {
+        if index >= self.items.len() {
+            return None;
+        }
+        self.unlink(index);
+        for moved in index + 1..self.items.len() {
+            self.relink(moved, moved - 1);
+        }
+        let item: Item<K, V> = self.items.remove(index);
+        Some((item.key, item.value))
+    }
+
+    /// Удаляет последний элемент.
+    pub fn pop(&mut self) -> Option<(K, V)> {
+        self.swap_remove_index(self.items.len().checked_sub(1)?)
+    }
+
+    /// Оставляет только элементы, для которых `keep` возвращает `true`,
+    /// сохраняя их порядок.
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut keep: F) {
+        self.items.retain_mut(|item| keep(&item.key, &mut item.value));
+        self.rebuild_index(self.buckets.len());
+    }
+
+    /// Сортирует элементы по ключам.
+    pub fn sort_keys(&mut self)
+    where
+        K: Ord,
+    {
+        self.sort_by(|key1, _, key2, _| key1.cmp(key2));
+    }
+
+    /// Сортирует элементы функцией сравнения, которая получает ключи и
+    /// значения двух элементов. Сортировка устойчивая.
+    pub fn sort_by<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&K, &V, &K, &V) -> Ordering,
+    {
+        self.items.sort_by(|a, b| compare(&a.key, &a.value, &b.key, &b.value));
+        self.rebuild_index(self.buckets.len());
+    }
+
+    /// Ищет позицию ключа с хэшем `hash`.
+    fn find<Q>(&self, hash: u64, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq,
+    {
+        self.buckets[self.bucket_of(hash)].iter().copied().find(|&index| {
+            let item: &Item<K, V> = &self.items[index];
+            item.hash == hash && item.key.borrow() == key
+        })
+    }
+
+    fn bucket_of(&self, hash: u64) -> usize {
+        (hash % self.buckets.len() as u64) as usize
+    }
+
+    /// Убирает позицию `index` из индекса.
+    fn unlink(&mut self, index: usize) {
+        let bucket: usize = self.bucket_of(self.items[index].hash);
+        self.buckets[bucket].retain(|&other| other != index);
+    }
+
+    /// Меняет в индексе позицию элемента `from` на `to`.
+    fn relink(&mut self, from: usize, to: usize) {
+        let bucket: usize = self.bucket_of(self.items[from].hash);
+        for index in self.buckets[bucket].iter_mut() {
+            if *index == from {
+                *index = to;
+            }
+        }
+    }
+
+    /// Строит индекс заново с `size` корзинами.
+    fn rebuild_index(&mut self, size: usize) {
+        self.buckets = vec![Vec::new(); size];
+        for (index, item) in self.items.iter().enumerate() {
+            let bucket: usize = (item.hash % size as u64) as usize;
+            self.buckets[bucket].push(index);
+        }
+    }
+}
+
+impl<K, V, S> IndexMap<K, V, S> {
+    /// Возвращает количество элементов.
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    /// Проверяет, пуста ли таблица.
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    /// Возвращает элемент на позиции `index`.
+    pub fn get_index(&self, index: usize) -> Option<(&K, &V)> {
+        self.items.get(index).map(|item| (&item.key, &item.value))
+    }
+
+    /// Возвращает элемент на позиции `index` с изменяемым значением.
+    pub fn get_index_mut(&mut self, index: usize) -> Option<(&K, &mut V)> {
+        self.items.get_mut(index).map(|item| (&item.key, &mut item.value))
+    }
+
+    /// Возвращает первый элемент.
+    pub fn first(&self) -> Option<(&K, &V)> {
+        self.get_index(0)
+    }
+
+    /// Возвращает последний элемент.
+    pub fn last(&self) -> Option<(&K, &V)> {
+        self.get_index(self.items.len().checked_sub(1)?)
+    }
+
+    /// Удаляет все элементы.
+    pub fn clear(&mut self) {
+        self.items.clear();
+        self.buckets.iter_mut().for_each(Vec::clear);
+    }
+
+    /// Возвращает итератор по парам ключ-значение в порядке элементов.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { inner: self.items.iter() }
+    }
+
+    /// Возвращает итератор по парам с изменяемыми значениями.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut { inner: self.items.iter_mut() }
+    }
+
+    /// Возвращает итератор по ключам в порядке элементов.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.items.iter() }
+    }
+
+    /// Возвращает итератор по значениям в порядке элементов.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.items.iter() }
+    }
+}
+
+/// Итератор по парам ключ-значение, см. `IndexMap::iter`.
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, Item<K, V>>,
+}
+
+/// Итератор по парам с изменяемыми значениями, см. `IndexMap::iter_mut`.
+pub struct IterMut<'a, K, V> {
+    inner: slice::IterMut<'a, Item<K, V>>,
+}
+
+/// Итератор, забирающий пары из таблицы.
+pub struct IntoIter<K, V> {
+    inner: vec::IntoIter<Item<K, V>>,
+}
+
+/// Итератор по ключам, см. `IndexMap::keys`.
+pub struct Keys<'a, K, V> {
+    inner: slice::Iter<'a, Item<K, V>>,
+}
+
+/// Итератор по значениям, см. `IndexMap::values`.
+pub struct Values<'a, K, V> {
+    inner: slice::Iter<'a, Item<K, V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|item| (&item.key, &item.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|item| (&item.key, &mut item.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|item| (item.key, item.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|item| &item.key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|item| &item.value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> DoubleEndedIterator for Iter<'a, K, V> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back().map(|item| (&item.key, &item.value))
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {}
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {}
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
+
+impl<K, V, S> IntoIterator for IndexMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    /// Забирает пары из таблицы в порядке элементов.
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter { inner: self.items.into_iter() }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a IndexMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut IndexMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for IndexMap<K, V, S> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map: IndexMap<K, V, S> = IndexMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Extend<(K, V)> for IndexMap<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K, Q, V, S> Index<&Q> for IndexMap<K, V, S>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: ?Sized + Hash + Eq,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    /// Возвращает ссылку на значение ключа.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если ключа нет в таблице.
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("The key is not in the map.")
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for IndexMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Таблицы равны, если в них одни и те же ключи с равными значениями,
+/// независимо от порядка, как у `HashMap`.
+impl<K: Eq + Hash, V: PartialEq, S: BuildHasher> PartialEq for IndexMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for IndexMap<K, V, S> {}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for IndexMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
@@ -0,0 +1,259 @@
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+use super::hash_map::HashMap;
+use super::index_list::IndexList;
+use super::lru_cache::EvictionCallback;
+
+/// Элементы кэша с одинаковым числом обращений.
+struct FrequencyGroup<K, V> {
+    frequency: usize,
+    entries: IndexList<(K, V)>, // От самого свежего к самому старому
+}
+
+/// Структура `LfuCache` — кэш ограниченного размера, вытесняющий элемент с
+/// наименьшим числом обращений (least frequently used); из равных
+/// вытесняется тот, к которому дольше всего не обращались.
+///
+/// Элементы разложены по группам с одинаковым числом обращений, а группы
+/// связаны в `IndexList` по возрастанию этого числа. Обращение переносит
+/// элемент в соседнюю группу, а вытесняемый элемент лежит в конце первой
+/// группы, так что `get`, `put`, `peek` и `pop_lfu` выполняются за O(1).
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::LfuCache;
+/// let mut cache = LfuCache::new(2);
+/// cache.put("а", 1);
+/// cache.put("б", 2);
+/// cache.get(&"а");
+/// cache.get(&"б");
+/// cache.get(&"б");
+/// cache.put("в", 3);
+/// assert_eq!(cache.peek(&"а"), None);
+/// assert_eq!(cache.frequency(&"б"), Some(3));
+/// ```
+pub struct LfuCache<K, V> {
+    map: HashMap<K, (usize, usize)>, // Группа ключа и узел внутри группы
+    groups: IndexList<FrequencyGroup<K, V>>, // Группы по возрастанию числа обращений
+    len: usize,
+    capacity: usize, // Наибольшее количество элементов
+    on_evict: Option<EvictionCallback<K, V>>, // Вызывается для вытесненных элементов
+}
+
+impl<K: Eq + Hash + Clone, V> LfuCache<K, V> {
+    /// Создает кэш, вмещающий `capacity` элементов.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `capacity` равно нулю.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "The capacity must be positive.");
+        LfuCache {
+            map: HashMap::with_capacity(capacity),
+            groups: IndexList::new(),
+            len: 0,
+            capacity,
+            on_evict: None,
+        }
+    }
+
+    /// Задает функцию, которая получает элементы, вытесненные из-за
+    /// нехватки места. Элементы, удаленные явно, ей не передаются.
+    pub fn set_on_evict<F: FnMut(K, V) + 'static>(&mut self, on_evict: F) {
+        self.on_evict = Some(Box::new(on_evict));
+    }
+
+    /// Возвращает количество элементов в кэше.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Проверяет, пуст ли кэш.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Возвращает наибольшее количество элементов.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Меняет наибольшее количество элементов, вытесняя лишние.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `capacity` равно нулю.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        assert!(capacity > 0, "The capacity must be positive.");
+        self.capacity = capacity;
+        while self.len > capacity {
+            self.evict();
+        }
+    }
+
+    /// Кладет значение в кэш. Новый ключ получает одно обращение, а
+    /// замена значения считается обращением к ключу. Если кэш полон,
+    /// вытесняется элемент с наименьшим числом обращений.
+    ///
+    /// Возвращает прежнее значение ключа или `None`, если ключа не было.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some((group, index)) = self.touch(&key) {
+            return Some(std::mem::replace(&mut self.groups.get_mut(group).entries.get_mut(index).1, value));
+        }
+        if self.len == self.capacity {
+            self.evict();
+        }
+        let group: usize = match self.groups.front() {
+            Some(front) if self.groups.get(front).frequency == 1 => front,
+            _ => self.groups.push_front(FrequencyGroup { frequency: 1, entries: IndexList::new() }),
+        };
+        let index: usize = self.groups.get_mut(group).entries.push_front((key.clone(), value));
+        self.map.insert(key, (group, index));
+        self.len += 1;
+        None
+    }
+
+    /// Возвращает значение ключа и засчитывает обращение к нему.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (group, index) = self.touch(key)?;
+        Some(&self.groups.get(group).entries.get(index).1)
+    }
+
+    /// Возвращает изменяемую ссылку на значение ключа и засчитывает
+    /// обращение к нему.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (group, index) = self.touch(key)?;
+        Some(&mut self.groups.get_mut(group).entries.get_mut(index).1)
+    }
+
+    /// Возвращает значение ключа, не засчитывая обращение.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (group, index) = *self.map.get(key)?;
+        Some(&self.groups.get(group).entries.get(index).1)
+    }
+
+    /// Проверяет, есть ли ключ в кэше, не засчитывая обращение.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Возвращает число обращений к ключу.
+    pub fn frequency<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (group, _) = *self.map.get(key)?;
+        Some(self.groups.get(group).frequency)
+    }
+
+    /// Возвращает элемент, который будет вытеснен следующим, не удаляя его.
+    pub fn peek_lfu(&self) -> Option<(&K, &V)> {
+        let group: &FrequencyGroup<K, V> = self.groups.get(self.groups.front()?);
+        let (key, value) = group.entries.get(group.entries.back()?);
+        Some((key, value))
+    }
+
+    /// Удаляет ключ.
+    ///
+    /// Возвращает удаленное значение или `None`, если ключа не было.
+    pub fn pop<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (group, index) = self.map.remove(key)?;
+        let (_, value) = self.take_entry(group, index);
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// Удаляет элемент с наименьшим числом обращений.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::LfuCache;
+    /// let mut cache = LfuCache::new(3);
+    /// cache.put(1, "один");
+    /// cache.put(2, "два");
+    /// cache.get(&1);
+    /// assert_eq!(cache.pop_lfu(), Some((2, "два")));
+    /// ```
+    pub fn pop_lfu(&mut self) -> Option<(K, V)> {
+        let group: usize = self.groups.front()?;
+        let index: usize = self.groups.get(group).entries.back()?;
+        let (key, value) = self.take_entry(group, index);
+        self.map.remove(&key);
+        self.len -= 1;
+        Some((key, value))
+    }
+
+    /// Удаляет все элементы, не вызывая функцию вытеснения.
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.groups.clear();
+        self.len = 0;
+    }
+
+    /// Засчитывает обращение к ключу: переносит его в группу с числом
+    /// обращений на одно больше, создавая ее при необходимости.
+    ///
+    /// Возвращает новые группу и узел ключа или `None`, если ключа нет.
+    fn touch<Q>(&mut self, key: &Q) -> Option<(usize, usize)>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let (group, index) = *self.map.get(key)?;
+        let frequency: usize = self.groups.get(group).frequency;
+        let entry: (K, V) = self.groups.get_mut(group).entries.remove(index);
+        let target: usize = match self.groups.next(group) {
+            Some(next) if self.groups.get(next).frequency == frequency + 1 => next,
+            _ => self.groups.insert_after(group, FrequencyGroup { frequency: frequency + 1, entries: IndexList::new() }),
+        };
+        if self.groups.get(group).entries.is_empty() {
+            self.groups.remove(group);
+        }
+        let index: usize = self.groups.get_mut(target).entries.push_front(entry);
+        *self.map.get_mut(key).unwrap() = (target, index);
+        Some((target, index))
+    }
+
+    /// Вынимает элемент из группы и удаляет опустевшую группу.
+    fn take_entry(&mut self, group: usize, index: usize) -> (K, V) {
+        let entry: (K, V) = self.groups.get_mut(group).entries.remove(index);
+        if self.groups.get(group).entries.is_empty() {
+            self.groups.remove(group);
+        }
+        entry
+    }
+
+    /// Вытесняет элемент с наименьшим числом обращений и передает его
+    /// функции вытеснения.
+    fn evict(&mut self) {
+        if let Some((key, value)) = self.pop_lfu() {
+            if let Some(on_evict) = self.on_evict.as_mut() {
+                on_evict(key, value);
+            }
+        }
+    }
+}
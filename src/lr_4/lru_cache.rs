@@ -0,0 +1,236 @@
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+use super::hash_map::HashMap;
+use super::index_list::{self, IndexList};
+
+/// Функция, которую кэш вызывает для каждого элемента, вытесненного из-за
+/// нехватки места.
+pub type EvictionCallback<K, V> = Box<dyn FnMut(K, V)>;
+
+/// Структура `LruCache` — кэш ограниченного размера, вытесняющий элемент,
+/// к которому дольше всего не обращались (least recently used).
+///
+/// Хэш-таблица хранит для каждого ключа индекс узла в `IndexList`, а список
+/// упорядочен от самого свежего элемента к самому старому. Поэтому `get`,
+/// `put`, `peek` и `pop_lru` выполняются за O(1).
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::LruCache;
+/// let mut cache = LruCache::new(2);
+/// cache.put("а", 1);
+/// cache.put("б", 2);
+/// cache.get(&"а");
+/// cache.put("в", 3);
+/// assert_eq!(cache.peek(&"б"), None);
+/// assert_eq!(cache.peek(&"а"), Some(&1));
+/// ```
+pub struct LruCache<K, V> {
+    map: HashMap<K, usize>, // Индексы узлов списка по ключам
+    list: IndexList<(K, V)>, // Элементы от самого свежего к самому старому
+    capacity: usize, // Наибольшее количество элементов
+    on_evict: Option<EvictionCallback<K, V>>, // Вызывается для вытесненных элементов
+}
+
+impl<K: Eq + Hash + Clone, V> LruCache<K, V> {
+    /// Создает кэш, вмещающий `capacity` элементов.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `capacity` равно нулю.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "The capacity must be positive.");
+        LruCache {
+            map: HashMap::with_capacity(capacity),
+            list: IndexList::new(),
+            capacity,
+            on_evict: None,
+        }
+    }
+
+    /// Задает функцию, которая получает элементы, вытесненные из-за
+    /// нехватки места. Элементы, удаленные явно, ей не передаются.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use std::cell::RefCell;
+    /// use std::rc::Rc;
+    /// use your_crate::LruCache;
+    /// let evicted: Rc<RefCell<Vec<i32>>> = Rc::new(RefCell::new(Vec::new()));
+    /// let log = Rc::clone(&evicted);
+    /// let mut cache = LruCache::new(1);
+    /// cache.set_on_evict(move |key, _| log.borrow_mut().push(key));
+    /// cache.put(1, "один");
+    /// cache.put(2, "два");
+    /// assert_eq!(*evicted.borrow(), vec![1]);
+    /// ```
+    pub fn set_on_evict<F: FnMut(K, V) + 'static>(&mut self, on_evict: F) {
+        self.on_evict = Some(Box::new(on_evict));
+    }
+
+    /// Возвращает количество элементов в кэше.
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    /// Проверяет, пуст ли кэш.
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    /// Возвращает наибольшее количество элементов.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Меняет наибольшее количество элементов, вытесняя лишние.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `capacity` равно нулю.
+    pub fn set_capacity(&mut self, capacity: usize) {
+        assert!(capacity > 0, "The capacity must be positive.");
+        self.capacity = capacity;
+        while self.len() > capacity {
+            self.evict();
+        }
+    }
+
+    /// Кладет значение в кэш и делает его самым свежим. Если кэш полон,
+    /// вытесняется самый старый элемент.
+    ///
+    /// Возвращает прежнее значение ключа или `None`, если ключа не было.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        if let Some(index) = self.map.get(&key) {
+            let index: usize = *index;
+            self.list.move_to_front(index);
+            return Some(std::mem::replace(&mut self.list.get_mut(index).1, value));
+        }
+        if self.len() == self.capacity {
+            self.evict();
+        }
+        let index: usize = self.list.push_front((key.clone(), value));
+        self.map.insert(key, index);
+        None
+    }
+
+    /// Возвращает значение ключа и делает его самым свежим.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index: usize = *self.map.get(key)?;
+        self.list.move_to_front(index);
+        Some(&self.list.get(index).1)
+    }
+
+    /// Возвращает изменяемую ссылку на значение ключа и делает его самым свежим.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index: usize = *self.map.get(key)?;
+        self.list.move_to_front(index);
+        Some(&mut self.list.get_mut(index).1)
+    }
+
+    /// Возвращает значение ключа, не меняя порядок вытеснения.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index: usize = *self.map.get(key)?;
+        Some(&self.list.get(index).1)
+    }
+
+    /// Проверяет, есть ли ключ в кэше, не меняя порядок вытеснения.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Возвращает самый старый элемент, не удаляя его.
+    pub fn peek_lru(&self) -> Option<(&K, &V)> {
+        let (key, value) = self.list.get(self.list.back()?);
+        Some((key, value))
+    }
+
+    /// Удаляет ключ.
+    ///
+    /// Возвращает удаленное значение или `None`, если ключа не было.
+    pub fn pop<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index: usize = self.map.remove(key)?;
+        Some(self.list.remove(index).1)
+    }
+
+    /// Удаляет самый старый элемент.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::LruCache;
+    /// let mut cache = LruCache::new(3);
+    /// cache.put(1, "один");
+    /// cache.put(2, "два");
+    /// assert_eq!(cache.pop_lru(), Some((1, "один")));
+    /// ```
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let (key, value) = self.list.pop_back()?;
+        self.map.remove(&key);
+        Some((key, value))
+    }
+
+    /// Удаляет все элементы, не вызывая функцию вытеснения.
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.list.clear();
+    }
+
+    /// Возвращает итератор по элементам от самого свежего к самому старому.
+    /// Порядок вытеснения при этом не меняется.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { inner: self.list.iter() }
+    }
+
+    /// Вытесняет самый старый элемент и передает его функции вытеснения.
+    fn evict(&mut self) {
+        if let Some((key, value)) = self.pop_lru() {
+            if let Some(on_evict) = self.on_evict.as_mut() {
+                on_evict(key, value);
+            }
+        }
+    }
+}
+
+/// Итератор по элементам кэша, см. `LruCache::iter`.
+pub struct Iter<'a, K, V> {
+    inner: index_list::Iter<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (key, value) = self.inner.next()?;
+        Some((key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
@@ -1,4 +1,53 @@
 mod hash_map;
 mod test_hash_map;
+mod robin_hood_map;
+mod test_robin_hood_map;
+mod hashers;
+mod test_hashers;
+mod concurrent_hash_map;
+mod test_concurrent_hash_map;
+mod hash_set;
+mod test_hash_set;
+mod multi_map;
+mod test_multi_map;
+mod index_list;
+mod lru_cache;
+mod test_lru_cache;
+mod lfu_cache;
+mod test_lfu_cache;
+mod ttl_cache;
+mod test_ttl_cache;
+mod bitcask;
+mod test_bitcask;
+mod cuckoo_hash_map;
+mod test_cuckoo_hash_map;
+mod static_map;
+mod test_static_map;
+mod bloom_filter;
+mod test_bloom_filter;
+mod cuckoo_filter;
+mod test_cuckoo_filter;
+mod hyper_log_log;
+mod test_hyper_log_log;
+mod count_min_sketch;
+mod test_count_min_sketch;
+mod index_map;
+mod test_index_map;
 
-pub use hash_map::HashMap;
\ No newline at end of file
+pub use hash_map::{Entry, HashMap, HashMapStats};
+pub use robin_hood_map::RobinHoodMap;
+pub use hashers::{FnvBuildHasher, FnvHasher, FxBuildHasher, FxHasher, SipBuildHasher, SipHasher};
+pub use concurrent_hash_map::ConcurrentHashMap;
+pub use hash_set::HashSet;
+pub use multi_map::MultiMap;
+pub use lru_cache::LruCache;
+pub use lfu_cache::LfuCache;
+pub use ttl_cache::{ManualClock, TtlCache};
+pub use bitcask::{Bitcask, BitcaskError};
+pub use cuckoo_hash_map::CuckooHashMap;
+pub use static_map::{StaticMap, StaticMapError};
+pub use bloom_filter::{BloomFilter, CountingBloomFilter, FilterError};
+pub use cuckoo_filter::CuckooFilter;
+pub use hyper_log_log::{HyperLogLog, SketchError};
+pub use count_min_sketch::{CountMinSketch, HeavyHitters};
+pub use index_map::IndexMap;
\ No newline at end of file
@@ -0,0 +1,291 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{self, Debug};
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+use std::iter::FromIterator;
+use std::slice;
+
+use super::hash_map::{self, HashMap};
+
+/// Структура `MultiMap` — хэш-таблица, в которой одному ключу соответствует
+/// несколько значений.
+///
+/// Значения ключа хранятся в `Vec` в порядке вставки. Ключ без значений в
+/// таблице не остается.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::MultiMap;
+/// let mut tags = MultiMap::new();
+/// tags.insert("rust", "язык");
+/// tags.insert("rust", "ржавчина");
+/// assert_eq!(tags.get_all("rust"), &["язык", "ржавчина"]);
+/// assert_eq!(tags.len(), 2);
+/// assert_eq!(tags.keys_len(), 1);
+/// ```
+#[derive(Clone)]
+pub struct MultiMap<K, V, S = BuildHasherDefault<DefaultHasher>> {
+    map: HashMap<K, Vec<V>, S>, // Непустые списки значений по ключам
+    values_count: usize, // Количество значений по всем ключам
+}
+
+impl<K: Eq + Hash, V> MultiMap<K, V> {
+    /// Создает новую пустую таблицу.
+    pub fn new() -> Self {
+        Self::with_hasher(BuildHasherDefault::default())
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> MultiMap<K, V, S> {
+    /// Создает новую таблицу, хэширующую ключи хэшерами из `hash_builder`.
+    pub fn with_hasher(hash_builder: S) -> Self {
+        MultiMap {
+            map: HashMap::with_hasher(hash_builder),
+            values_count: 0,
+        }
+    }
+
+    /// Возвращает количество значений по всем ключам.
+    pub fn len(&self) -> usize {
+        self.values_count
+    }
+
+    /// Возвращает количество различных ключей.
+    pub fn keys_len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Проверяет, пуста ли таблица.
+    pub fn is_empty(&self) -> bool {
+        self.values_count == 0
+    }
+
+    /// Добавляет значение к остальным значениям ключа.
+    pub fn insert(&mut self, key: K, value: V) {
+        self.map.entry(key).or_default().push(value);
+        self.values_count += 1;
+    }
+
+    /// Возвращает первое значение ключа.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.get(key).and_then(|values| values.first())
+    }
+
+    /// Возвращает все значения ключа в порядке вставки; для отсутствующего
+    /// ключа — пустой срез.
+    pub fn get_all<Q>(&self, key: &Q) -> &[V]
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.get(key).map(|values| values.as_slice()).unwrap_or(&[])
+    }
+
+    /// Возвращает изменяемые ссылки на все значения ключа.
+    pub fn get_all_mut<Q>(&mut self, key: &Q) -> &mut [V]
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.get_mut(key).map(|values| values.as_mut_slice()).unwrap_or(&mut [])
+    }
+
+    /// Возвращает количество значений ключа.
+    pub fn count<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get_all(key).len()
+    }
+
+    /// Проверяет, есть ли у ключа хотя бы одно значение.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.map.contains_key(key)
+    }
+
+    /// Удаляет ключ вместе со всеми его значениями.
+    ///
+    /// Возвращает удаленные значения или `None`, если ключа не было.
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<Vec<V>>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let values: Vec<V> = self.map.remove(key)?;
+        self.values_count -= values.len();
+        Some(values)
+    }
+
+    /// Оставляет у каждого ключа только значения, для которых `keep`
+    /// возвращает `true`. Ключи, оставшиеся без значений, удаляются.
+    pub fn retain<F: FnMut(&K, &V) -> bool>(&mut self, mut keep: F) {
+        let mut values_count: usize = 0;
+        self.map.retain(|key, values| {
+            values.retain(|value| keep(key, value));
+            values_count += values.len();
+            !values.is_empty()
+        });
+        self.values_count = values_count;
+    }
+
+    /// Удаляет все ключи и значения.
+    pub fn clear(&mut self) {
+        self.map.clear();
+        self.values_count = 0;
+    }
+
+    /// Возвращает итератор по ключам; каждый ключ выдается один раз.
+    pub fn keys(&self) -> hash_map::Keys<'_, K, Vec<V>> {
+        self.map.keys()
+    }
+
+    /// Возвращает итератор по парам ключ-значение. Ключ повторяется для
+    /// каждого своего значения, значения одного ключа идут подряд.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            groups: self.map.iter(),
+            current: None,
+            remaining: self.values_count,
+        }
+    }
+
+    /// Возвращает итератор по ключам вместе со всеми их значениями.
+    pub fn iter_all(&self) -> IterAll<'_, K, V> {
+        IterAll { inner: self.map.iter() }
+    }
+}
+
+impl<K: Eq + Hash, V: PartialEq, S: BuildHasher> MultiMap<K, V, S> {
+    /// Удаляет первое значение ключа, равное `value`.
+    ///
+    /// Возвращает `true`, если такое значение было.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::MultiMap;
+    /// let mut map = MultiMap::new();
+    /// map.insert(1, "а");
+    /// map.insert(1, "б");
+    /// assert!(map.remove_value(&1, &"а"));
+    /// assert_eq!(map.get_all(&1), &["б"]);
+    /// assert!(map.remove_value(&1, &"б"));
+    /// assert!(!map.contains_key(&1));
+    /// ```
+    pub fn remove_value<Q>(&mut self, key: &Q, value: &V) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let values: &mut Vec<V> = match self.map.get_mut(key) {
+            None => return false,
+            Some(values) => values,
+        };
+        let position: usize = match values.iter().position(|v| v == value) {
+            None => return false,
+            Some(position) => position,
+        };
+        values.remove(position);
+        if values.is_empty() {
+            self.map.remove(key);
+        }
+        self.values_count -= 1;
+        true
+    }
+}
+
+/// Итератор по парам ключ-значение, см. `MultiMap::iter`.
+pub struct Iter<'a, K, V> {
+    groups: hash_map::Iter<'a, K, Vec<V>>,
+    current: Option<(&'a K, slice::Iter<'a, V>)>, // Ключ и его еще не выданные значения
+    remaining: usize,
+}
+
+/// Итератор по ключам со всеми их значениями, см. `MultiMap::iter_all`.
+pub struct IterAll<'a, K, V> {
+    inner: hash_map::Iter<'a, K, Vec<V>>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some((key, values)) = self.current.as_mut() {
+                if let Some(value) = values.next() {
+                    self.remaining -= 1;
+                    return Some((*key, value));
+                }
+            }
+            let (key, values) = self.groups.next()?;
+            self.current = Some((key, values.iter()));
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> Iterator for IterAll<'a, K, V> {
+    type Item = (&'a K, &'a [V]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, values)| (key, values.as_slice()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+impl<'a, K, V> ExactSizeIterator for IterAll<'a, K, V> {}
+
+impl<'a, K: Eq + Hash, V, S: BuildHasher> IntoIterator for &'a MultiMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for MultiMap<K, V, S> {
+    /// Собирает таблицу из пар; все значения повторяющихся ключей сохраняются.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map: MultiMap<K, V, S> = MultiMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Extend<(K, V)> for MultiMap<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for MultiMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Debug::fmt(&self.map, f)
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for MultiMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
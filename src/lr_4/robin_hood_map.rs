@@ -0,0 +1,781 @@
+use std::borrow::Borrow;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{self, Debug};
+use std::iter::{FlatMap, Flatten, FromIterator};
+use std::ops::Index;
+use std::{slice, vec};
+
+/// Занятая ячейка таблицы.
+#[derive(Clone)]
+struct Slot<K, V> {
+    hash: u64, // Полный хэш ключа, чтобы не пересчитывать его при сдвигах и рехешировании
+    distance: usize, // Расстояние от желаемой ячейки ключа до фактической
+    key: K,
+    value: V,
+}
+
+/// Структура `RobinHoodMap` — хэш-таблица с открытой адресацией и тем же
+/// набором методов, что и `HashMap`.
+///
+/// Коллизии разрешаются линейным пробированием по методу Робин Гуда: при
+/// вставке элемент, ушедший от своей желаемой ячейки дальше, чем текущий
+/// владелец ячейки, занимает его место, а вытесненный элемент идет дальше.
+/// Поэтому цепочки пробирования короткие и почти одинаковые, а поиск
+/// отсутствующего ключа останавливается, как только встречается элемент,
+/// который ближе к своей ячейке, чем искомый был бы здесь.
+///
+/// Удаление выполняется обратным сдвигом: следующие за удаленным элементы
+/// сдвигаются на одну ячейку назад, так что надгробия не нужны.
+///
+/// Хэш-функцию задает параметр `S`, как в `HashMap`; по умолчанию
+/// используется `DefaultHasher` с нулевым ключом.
+#[derive(Clone)]
+pub struct RobinHoodMap<K, V, S = BuildHasherDefault<DefaultHasher>> {
+    slots: Vec<Option<Slot<K, V>>>, // Ячейки таблицы; их количество — степень двойки
+    items_count: usize, // Количество элементов в хэш-таблице
+    load_factor: f64, // Наибольшая доля занятых ячеек, после которой таблица растет
+    hash_builder: S, // Строитель хэшеров для ключей
+}
+
+impl<K: Eq + Hash, V> RobinHoodMap<K, V> {
+    /// Создает новую хэш-таблицу.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::RobinHoodMap;
+    /// let mut map: RobinHoodMap<i32, String> = RobinHoodMap::new();
+    /// ```
+    pub fn new() -> Self {
+        Self::with_hasher(BuildHasherDefault::default())
+    }
+
+    /// Создает хэш-таблицу, в которую поместится `capacity` элементов без
+    /// рехеширования.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::RobinHoodMap;
+    /// let map: RobinHoodMap<i32, i32> = RobinHoodMap::with_capacity(1000);
+    /// assert!(map.capacity() >= 1000);
+    /// ```
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::with_capacity_and_hasher(capacity, BuildHasherDefault::default())
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> RobinHoodMap<K, V, S> {
+    /// Создает новую хэш-таблицу, хэширующую ключи хэшерами из `hash_builder`.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::{FxBuildHasher, RobinHoodMap};
+    /// let mut map: RobinHoodMap<u64, &str, FxBuildHasher> = RobinHoodMap::with_hasher(FxBuildHasher::default());
+    /// map.insert(1, "один");
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self {
+        RobinHoodMap {
+            slots: Vec::new(),
+            items_count: 0,
+            load_factor: 0.9,
+            hash_builder,
+        }
+    }
+
+    /// Создает хэш-таблицу с хэшерами из `hash_builder`, в которую
+    /// поместится `capacity` элементов без рехеширования.
+    pub fn with_capacity_and_hasher(capacity: usize, hash_builder: S) -> Self {
+        let mut map: Self = Self::with_hasher(hash_builder);
+        if capacity > 0 {
+            let needed: usize = (capacity as f64 / map.load_factor).ceil() as usize;
+            map.resize(needed.next_power_of_two().max(8));
+        }
+        map
+    }
+
+    /// Возвращает, сколько элементов поместится в таблицу без рехеширования.
+    pub fn capacity(&self) -> usize {
+        (self.slots.len() as f64 * self.load_factor) as usize
+    }
+
+    /// Возвращает строитель хэшеров таблицы.
+    pub fn hasher(&self) -> &S {
+        &self.hash_builder
+    }
+
+    /// Возвращает количество элементов в хэш-таблице.
+    pub fn len(&self) -> usize {
+        self.items_count
+    }
+
+    /// Проверяет, пуста ли хэш-таблица.
+    pub fn is_empty(&self) -> bool {
+        self.items_count == 0
+    }
+
+    /// Вставляет ключ и значение в хэш-таблицу. Если ключ уже есть, его
+    /// значение заменяется.
+    ///
+    /// Возвращает прежнее значение ключа или `None`, если ключа не было.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::RobinHoodMap;
+    /// let mut map = RobinHoodMap::new();
+    /// assert_eq!(map.insert(1, "один"), None);
+    /// assert_eq!(map.insert(1, "uno"), Some("один"));
+    /// assert_eq!(map.get(&1), Some(&"uno"));
+    /// ```
+    pub fn insert(&mut self, key: K, value: V) -> Option<V> {
+        let hash: u64 = self.hash_builder.hash_one(&key);
+        if let Some(index) = self.find_index(hash, &key) {
+            let slot: &mut Slot<K, V> = self.slots[index].as_mut().unwrap();
+            return Some(std::mem::replace(&mut slot.value, value));
+        }
+        self.insert_new(hash, key, value);
+        None
+    }
+
+    /// Удаляет значение по указанному ключу из хэш-таблицы.
+    ///
+    /// Возвращает удаленное значение или `None`, если ключа не было.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::RobinHoodMap;
+    /// let mut map = RobinHoodMap::new();
+    /// map.insert(1, "один");
+    /// assert_eq!(map.remove(&1), Some("один"));
+    /// assert_eq!(map.remove(&1), None);
+    /// ```
+    pub fn remove<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let mut index: usize = self.find_index(self.hash_builder.hash_one(key), key)?;
+        let removed: Slot<K, V> = self.slots[index].take().unwrap();
+        self.items_count -= 1;
+        // Обратный сдвиг: элементы, стоящие не на своем месте, подтягиваются
+        // на освободившуюся ячейку.
+        let mask: usize = self.slots.len() - 1;
+        loop {
+            let next: usize = (index + 1) & mask;
+            match self.slots[next].as_ref() {
+                Some(slot) if slot.distance > 0 => {}
+                _ => return Some(removed.value),
+            }
+            let mut slot: Slot<K, V> = self.slots[next].take().unwrap();
+            slot.distance -= 1;
+            self.slots[index] = Some(slot);
+            index = next;
+        }
+    }
+
+    /// Проверяет, есть ли в хэш-таблице указанный ключ.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get(key).is_some()
+    }
+
+    /// Удаляет все элементы, сохраняя выделенные ячейки.
+    pub fn clear(&mut self) {
+        for slot in self.slots.iter_mut() {
+            *slot = None;
+        }
+        self.items_count = 0;
+    }
+
+    /// Оставляет только элементы, для которых `keep` возвращает `true`.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::RobinHoodMap;
+    /// let mut map: RobinHoodMap<i32, i32> = (0..10).map(|i| (i, i * i)).collect();
+    /// map.retain(|key, _| key % 2 == 0);
+    /// assert_eq!(map.len(), 5);
+    /// ```
+    pub fn retain<F: FnMut(&K, &mut V) -> bool>(&mut self, mut keep: F) {
+        // Удаленные элементы рвут цепочки пробирования, поэтому оставшиеся
+        // проще разместить заново, чем сдвигать по одному.
+        let old_slots: Vec<Option<Slot<K, V>>> = std::mem::take(&mut self.slots);
+        self.slots.resize_with(old_slots.len(), || None);
+        self.items_count = 0;
+        for mut slot in old_slots.into_iter().flatten() {
+            if keep(&slot.key, &mut slot.value) {
+                self.place(slot);
+                self.items_count += 1;
+            }
+        }
+    }
+
+    /// Возвращает запись для ключа, через которую можно прочитать, изменить
+    /// или вставить значение за один поиск.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::RobinHoodMap;
+    /// let mut counts: RobinHoodMap<&str, u32> = RobinHoodMap::new();
+    /// for word in ["а", "б", "а"] {
+    ///     *counts.entry(word).or_insert(0) += 1;
+    /// }
+    /// assert_eq!(counts.get(&"а"), Some(&2));
+    /// ```
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V, S> {
+        let hash: u64 = self.hash_builder.hash_one(&key);
+        match self.find_index(hash, &key) {
+            Some(index) => Entry::Occupied(OccupiedEntry { slot: self.slots[index].as_mut().unwrap() }),
+            None => Entry::Vacant(VacantEntry { map: self, hash, key }),
+        }
+    }
+
+    /// Возвращает ссылку на значение, связанное с указанным ключом.
+    ///
+    /// Ключ можно передать в заимствованной форме, как в `HashMap::get`.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::RobinHoodMap;
+    /// let mut map = RobinHoodMap::new();
+    /// map.insert(1, "один");
+    /// assert_eq!(map.get(&1), Some(&"один"));
+    ///
+    /// let mut names: RobinHoodMap<String, u32> = RobinHoodMap::new();
+    /// names.insert("один".to_string(), 1);
+    /// assert_eq!(names.get("один"), Some(&1));
+    /// ```
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index: usize = self.find_index(self.hash_builder.hash_one(key), key)?;
+        self.slots[index].as_ref().map(|slot| &slot.value)
+    }
+
+    /// Возвращает изменяемую ссылку на значение, связанное с указанным ключом.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::RobinHoodMap;
+    /// let mut map = RobinHoodMap::new();
+    /// map.insert(1, 10);
+    /// *map.get_mut(&1).unwrap() += 1;
+    /// assert_eq!(map.get(&1), Some(&11));
+    /// ```
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let index: usize = self.find_index(self.hash_builder.hash_one(key), key)?;
+        self.slots[index].as_mut().map(|slot| &mut slot.value)
+    }
+
+    /// Возвращает наибольшее расстояние элемента от его желаемой ячейки,
+    /// то есть длину самого длинного пробирования при поиске.
+    pub fn max_probe_distance(&self) -> usize {
+        self.slots.iter().flatten().map(|slot| slot.distance).max().unwrap_or(0)
+    }
+
+    /// Ищет ячейку с указанным ключом.
+    ///
+    /// Возвращает индекс ячейки или `None`, если ключа нет.
+    fn find_index<Q>(&self, hash: u64, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Eq,
+    {
+        if self.slots.is_empty() {
+            return None;
+        }
+        let mask: usize = self.slots.len() - 1;
+        let mut index: usize = hash as usize & mask;
+        let mut distance: usize = 0;
+        while let Some(slot) = self.slots[index].as_ref() {
+            // Если бы ключ был в таблице, он вытеснил бы этот элемент.
+            if slot.distance < distance {
+                return None;
+            }
+            if slot.hash == hash && slot.key.borrow() == key {
+                return Some(index);
+            }
+            index = (index + 1) & mask;
+            distance += 1;
+        }
+        None
+    }
+
+    /// Вставляет ключ, которого точно нет в таблице, при необходимости
+    /// увеличивая ее.
+    ///
+    /// Возвращает изменяемую ссылку на вставленное значение.
+    fn insert_new(&mut self, hash: u64, key: K, value: V) -> &mut V {
+        if (self.items_count + 1) as f64 > self.slots.len() as f64 * self.load_factor {
+            self.grow();
+        }
+        let index: usize = self.place(Slot { hash, distance: 0, key, value });
+        self.items_count += 1;
+        &mut self.slots[index].as_mut().unwrap().value
+    }
+
+    /// Размещает элемент, которого точно нет в таблице, вытесняя более
+    /// близкие к своим ячейкам элементы.
+    ///
+    /// Возвращает индекс ячейки, в которую попал сам элемент.
+    fn place(&mut self, mut slot: Slot<K, V>) -> usize {
+        let mask: usize = self.slots.len() - 1;
+        let mut index: usize = slot.hash as usize & mask;
+        let mut placed: Option<usize> = None; // Ячейка элемента, если его уже вытеснили дальше
+        slot.distance = 0;
+        loop {
+            match self.slots[index].as_mut() {
+                None => {
+                    self.slots[index] = Some(slot);
+                    return placed.unwrap_or(index);
+                }
+                Some(resident) => {
+                    if resident.distance < slot.distance {
+                        std::mem::swap(resident, &mut slot);
+                        placed.get_or_insert(index);
+                    }
+                }
+            }
+            index = (index + 1) & mask;
+            slot.distance += 1;
+        }
+    }
+
+    /// Удваивает количество ячеек и заново размещает все элементы.
+    fn grow(&mut self) {
+        let new_size: usize = match self.slots.len() {
+            0 => 8,
+            n => n * 2,
+        };
+        self.resize(new_size);
+    }
+
+    /// Заново размещает все элементы в `new_size` ячейках (степень двойки).
+    fn resize(&mut self, new_size: usize) {
+        let old_slots: Vec<Option<Slot<K, V>>> = std::mem::replace(&mut self.slots, Vec::with_capacity(new_size));
+        self.slots.resize_with(new_size, || None);
+        for slot in old_slots.into_iter().flatten() {
+            self.place(slot);
+        }
+    }
+}
+
+/// Запись хэш-таблицы для одного ключа, см. `RobinHoodMap::entry`.
+pub enum Entry<'a, K, V, S> {
+    Occupied(OccupiedEntry<'a, K, V>),
+    Vacant(VacantEntry<'a, K, V, S>),
+}
+
+/// Запись для ключа, который есть в таблице.
+pub struct OccupiedEntry<'a, K, V> {
+    slot: &'a mut Slot<K, V>,
+}
+
+/// Запись для ключа, которого в таблице нет.
+pub struct VacantEntry<'a, K, V, S> {
+    map: &'a mut RobinHoodMap<K, V, S>,
+    hash: u64, // Уже вычисленный хэш ключа
+    key: K,
+}
+
+impl<'a, K: Eq + Hash, V, S: BuildHasher> Entry<'a, K, V, S> {
+    /// Возвращает ключ записи.
+    pub fn key(&self) -> &K {
+        match self {
+            Entry::Occupied(entry) => entry.key(),
+            Entry::Vacant(entry) => entry.key(),
+        }
+    }
+
+    /// Вставляет `default`, если ключа нет.
+    ///
+    /// Возвращает изменяемую ссылку на значение ключа.
+    pub fn or_insert(self, default: V) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default),
+        }
+    }
+
+    /// Вставляет результат `default()`, если ключа нет. Функция не
+    /// вызывается, если ключ есть.
+    ///
+    /// Возвращает изменяемую ссылку на значение ключа.
+    pub fn or_insert_with<F: FnOnce() -> V>(self, default: F) -> &'a mut V {
+        match self {
+            Entry::Occupied(entry) => entry.into_mut(),
+            Entry::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// Изменяет значение функцией `f`, если ключ есть.
+    pub fn and_modify<F: FnOnce(&mut V)>(self, f: F) -> Self {
+        match self {
+            Entry::Occupied(mut entry) => {
+                f(entry.get_mut());
+                Entry::Occupied(entry)
+            }
+            Entry::Vacant(entry) => Entry::Vacant(entry),
+        }
+    }
+}
+
+impl<'a, K: Eq + Hash, V: Default, S: BuildHasher> Entry<'a, K, V, S> {
+    /// Вставляет `V::default()`, если ключа нет.
+    ///
+    /// Возвращает изменяемую ссылку на значение ключа.
+    pub fn or_default(self) -> &'a mut V {
+        self.or_insert_with(V::default)
+    }
+}
+
+impl<'a, K, V> OccupiedEntry<'a, K, V> {
+    pub fn key(&self) -> &K {
+        &self.slot.key
+    }
+
+    pub fn get(&self) -> &V {
+        &self.slot.value
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.slot.value
+    }
+
+    /// Превращает запись в изменяемую ссылку на значение, живущую столько
+    /// же, сколько заимствование таблицы.
+    pub fn into_mut(self) -> &'a mut V {
+        &mut self.slot.value
+    }
+
+    /// Заменяет значение и возвращает прежнее.
+    pub fn insert(&mut self, value: V) -> V {
+        std::mem::replace(&mut self.slot.value, value)
+    }
+}
+
+impl<'a, K: Eq + Hash, V, S: BuildHasher> VacantEntry<'a, K, V, S> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    /// Вставляет значение под ключом записи.
+    ///
+    /// Возвращает изменяемую ссылку на вставленное значение.
+    pub fn insert(self, value: V) -> &'a mut V {
+        self.map.insert_new(self.hash, self.key, value)
+    }
+}
+
+impl<K, V, S> RobinHoodMap<K, V, S> {
+    /// Возвращает итератор по парам ключ-значение в порядке ячеек.
+    ///
+    /// # Примеры
+    ///
+    /// ```
+    /// use your_crate::RobinHoodMap;
+    /// let mut map = RobinHoodMap::new();
+    /// map.insert(1, "один");
+    /// map.insert(2, "два");
+    /// for (key, value) in map.iter() {
+    ///     println!("{}: {}", key, value);
+    /// }
+    /// ```
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter {
+            inner: self.slots.iter().flatten(),
+            remaining: self.items_count,
+        }
+    }
+
+    /// Возвращает итератор по парам ключ-значение с изменяемыми значениями.
+    pub fn iter_mut(&mut self) -> IterMut<'_, K, V> {
+        IterMut {
+            inner: self.slots.iter_mut().flatten(),
+            remaining: self.items_count,
+        }
+    }
+
+    /// Возвращает итератор по ключам.
+    pub fn keys(&self) -> Keys<'_, K, V> {
+        Keys { inner: self.iter() }
+    }
+
+    /// Возвращает итератор по значениям.
+    pub fn values(&self) -> Values<'_, K, V> {
+        Values { inner: self.iter() }
+    }
+
+    /// Возвращает итератор по изменяемым значениям.
+    pub fn values_mut(&mut self) -> ValuesMut<'_, K, V> {
+        ValuesMut { inner: self.iter_mut() }
+    }
+
+    /// Забирает все элементы из таблицы, сохраняя выделенные ячейки.
+    /// Элементы, не пройденные итератором, удаляются вместе с ним.
+    pub fn drain(&mut self) -> Drain<'_, K, V> {
+        let remaining: usize = std::mem::replace(&mut self.items_count, 0);
+        let take: TakeSlot<K, V> = Option::take;
+        Drain {
+            inner: self.slots.iter_mut().flat_map(take),
+            remaining,
+        }
+    }
+}
+
+/// Забирает элемент из ячейки, оставляя ее пустой.
+type TakeSlot<K, V> = fn(&mut Option<Slot<K, V>>) -> Option<Slot<K, V>>;
+
+type DrainSlots<'a, K, V> = FlatMap<slice::IterMut<'a, Option<Slot<K, V>>>, Option<Slot<K, V>>, TakeSlot<K, V>>;
+
+/// Итератор по парам ключ-значение, см. `RobinHoodMap::iter`.
+pub struct Iter<'a, K, V> {
+    inner: Flatten<slice::Iter<'a, Option<Slot<K, V>>>>,
+    remaining: usize, // Количество еще не выданных элементов
+}
+
+/// Итератор по парам ключ-значение с изменяемыми значениями, см. `RobinHoodMap::iter_mut`.
+pub struct IterMut<'a, K, V> {
+    inner: Flatten<slice::IterMut<'a, Option<Slot<K, V>>>>,
+    remaining: usize,
+}
+
+/// Итератор, забирающий элементы таблицы, см. `RobinHoodMap::into_iter`.
+pub struct IntoIter<K, V> {
+    inner: Flatten<vec::IntoIter<Option<Slot<K, V>>>>,
+    remaining: usize,
+}
+
+/// Итератор, забирающий элементы и оставляющий таблицу пустой, см. `RobinHoodMap::drain`.
+pub struct Drain<'a, K, V> {
+    inner: DrainSlots<'a, K, V>,
+    remaining: usize,
+}
+
+/// Итератор по ключам, см. `RobinHoodMap::keys`.
+pub struct Keys<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+/// Итератор по значениям, см. `RobinHoodMap::values`.
+pub struct Values<'a, K, V> {
+    inner: Iter<'a, K, V>,
+}
+
+/// Итератор по изменяемым значениям, см. `RobinHoodMap::values_mut`.
+pub struct ValuesMut<'a, K, V> {
+    inner: IterMut<'a, K, V>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot: &Slot<K, V> = self.inner.next()?;
+        self.remaining -= 1;
+        Some((&slot.key, &slot.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> Iterator for IterMut<'a, K, V> {
+    type Item = (&'a K, &'a mut V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot: &mut Slot<K, V> = self.inner.next()?;
+        self.remaining -= 1;
+        Some((&slot.key, &mut slot.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<K, V> Iterator for IntoIter<K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot: Slot<K, V> = self.inner.next()?;
+        self.remaining -= 1;
+        Some((slot.key, slot.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> Iterator for Drain<'a, K, V> {
+    type Item = (K, V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let slot: Slot<K, V> = self.inner.next()?;
+        self.remaining -= 1;
+        Some((slot.key, slot.value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, K, V> Drop for Drain<'a, K, V> {
+    /// Удаляет элементы, до которых итератор не дошел, чтобы таблица
+    /// осталась пустой.
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+impl<'a, K, V> Iterator for Keys<'a, K, V> {
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, _)| key)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> Iterator for Values<'a, K, V> {
+    type Item = &'a V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> Iterator for ValuesMut<'a, K, V> {
+    type Item = &'a mut V;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(_, value)| value)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+impl<'a, K, V> ExactSizeIterator for IterMut<'a, K, V> {}
+impl<K, V> ExactSizeIterator for IntoIter<K, V> {}
+impl<'a, K, V> ExactSizeIterator for Drain<'a, K, V> {}
+impl<'a, K, V> ExactSizeIterator for Keys<'a, K, V> {}
+impl<'a, K, V> ExactSizeIterator for Values<'a, K, V> {}
+impl<'a, K, V> ExactSizeIterator for ValuesMut<'a, K, V> {}
+
+impl<K, V, S> IntoIterator for RobinHoodMap<K, V, S> {
+    type Item = (K, V);
+    type IntoIter = IntoIter<K, V>;
+
+    /// Превращает таблицу в итератор, забирающий ее элементы.
+    fn into_iter(self) -> IntoIter<K, V> {
+        IntoIter {
+            inner: self.slots.into_iter().flatten(),
+            remaining: self.items_count,
+        }
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a RobinHoodMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+impl<'a, K, V, S> IntoIterator for &'a mut RobinHoodMap<K, V, S> {
+    type Item = (&'a K, &'a mut V);
+    type IntoIter = IterMut<'a, K, V>;
+
+    fn into_iter(self) -> IterMut<'a, K, V> {
+        self.iter_mut()
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> FromIterator<(K, V)> for RobinHoodMap<K, V, S> {
+    /// Собирает таблицу из пар; при повторе ключа остается последнее значение.
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        let mut map: RobinHoodMap<K, V, S> = RobinHoodMap::default();
+        map.extend(iter);
+        map
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> Extend<(K, V)> for RobinHoodMap<K, V, S> {
+    fn extend<I: IntoIterator<Item = (K, V)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl<K, Q, V, S> Index<&Q> for RobinHoodMap<K, V, S>
+where
+    K: Eq + Hash + Borrow<Q>,
+    Q: ?Sized + Hash + Eq,
+    S: BuildHasher,
+{
+    type Output = V;
+
+    /// Возвращает ссылку на значение ключа.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если ключа нет в таблице.
+    fn index(&self, key: &Q) -> &V {
+        self.get(key).expect("The key is not in the map.")
+    }
+}
+
+impl<K: Debug, V: Debug, S> Debug for RobinHoodMap<K, V, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// Таблицы равны, если в них одни и те же ключи с равными значениями,
+/// независимо от порядка вставки и числа ячеек.
+impl<K: Eq + Hash, V: PartialEq, S: BuildHasher> PartialEq for RobinHoodMap<K, V, S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter().all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<K: Eq + Hash, V: Eq, S: BuildHasher> Eq for RobinHoodMap<K, V, S> {}
+
+impl<K: Eq + Hash, V, S: BuildHasher + Default> Default for RobinHoodMap<K, V, S> {
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
@@ -0,0 +1,284 @@
+use std::borrow::Borrow;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+use std::slice;
+
+use super::hash_map::HashMap;
+
+/// Среднее количество ключей в одной корзине первого уровня.
+const KEYS_PER_BUCKET: usize = 4;
+
+/// Доля занятых ячеек второго уровня. Запас свободных ячеек нужен, чтобы
+/// последним корзинам смещение находилось за несколько попыток, а не за
+/// сотни тысяч.
+const LOAD_FACTOR: f64 = 0.9;
+
+/// Сколько смещений перебирается для одной корзины, прежде чем построение
+/// начнется заново с другой затравкой.
+const MAX_DISPLACEMENT: u32 = 1 << 12;
+
+/// Сколько затравок перебирается, прежде чем построение считается
+/// неудачным.
+const MAX_ATTEMPTS: u32 = 16;
+
+/// Прибавка к затравке первого уровня при новой попытке построения.
+const SEED_STEP: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// Ошибки построения `StaticMap`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaticMapError {
+    /// Ни при одной из затравок не нашлось смещений для всех корзин;
+    /// обычно это значит, что хэшер дает одинаковые хэши разным ключам.
+    NoPerfectHash,
+}
+
+impl fmt::Display for StaticMapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StaticMapError::NoPerfectHash => write!(f, "no perfect hash function found for the keys"),
+        }
+    }
+}
+
+impl std::error::Error for StaticMapError {}
+
+/// Структура `StaticMap` — неизменяемая хэш-таблица над заранее известным
+/// набором ключей, построенная на минимальной совершенной хэш-функции в
+/// стиле CHD (compress, hash, displace).
+///
+/// Первая хэш-функция раскладывает ключи по корзинам примерно по
+/// `KEYS_PER_BUCKET` штук. Для каждой корзины, начиная с самых больших,
+/// подбирается смещение, при котором вторая хэш-функция отправляет все ее
+/// ключи в свободные ячейки среди `n / LOAD_FACTOR`. Затем ячейки
+/// сжимаются: занятая ячейка получает свой ранг среди занятых, так что
+/// `n` элементов лежат ровно в `n` местах без коллизий. Поиск всегда
+/// вычисляет два хэша и сравнивает ключ ровно с одним элементом.
+///
+/// # Примеры
+///
+/// ```
+/// use your_crate::StaticMap;
+/// let map = StaticMap::build(vec![("один", 1), ("два", 2), ("три", 3)]).unwrap();
+/// assert_eq!(map.get(&"два"), Some(&2));
+/// assert_eq!(map.get(&"четыре"), None);
+/// assert!(map.index_of(&"три").unwrap() < map.len());
+/// ```
+#[derive(Clone)]
+pub struct StaticMap<K, V, S = BuildHasherDefault<DefaultHasher>> {
+    entries: Vec<(K, V)>, // Элементы, разложенные по совершенной хэш-функции
+    displacements: Vec<u32>, // Смещение для каждой корзины первого уровня
+    ranks: Vec<u32>, // Для каждой ячейки — количество занятых ячеек до нее
+    seed: u64, // Затравка первого уровня
+    hash_builder: S, // Строитель хэшеров для ключей
+}
+
+impl<K: Eq + Hash, V> StaticMap<K, V> {
+    /// Строит таблицу над парами ключ-значение. Если ключ повторяется,
+    /// остается последнее значение.
+    ///
+    /// Возвращает `StaticMapError::NoPerfectHash`, если ни при одной из
+    /// `MAX_ATTEMPTS` затравок совершенная хэш-функция не нашлась.
+    pub fn build<I: IntoIterator<Item = (K, V)>>(entries: I) -> Result<Self, StaticMapError> {
+        Self::build_with_hasher(entries, BuildHasherDefault::default())
+    }
+}
+
+impl<K, V, S> StaticMap<K, V, S> {
+    /// Наибольшее количество элементов, с которыми поиск сравнивает ключ.
+    pub const MAX_PROBES: usize = 1;
+
+    /// Возвращает количество элементов.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Проверяет, пуста ли таблица.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Возвращает итератор по парам ключ-значение в порядке индексов
+    /// совершенной хэш-функции.
+    pub fn iter(&self) -> Iter<'_, K, V> {
+        Iter { inner: self.entries.iter() }
+    }
+}
+
+impl<K: Eq + Hash, V, S: BuildHasher> StaticMap<K, V, S> {
+    /// Строит таблицу, хэширующую ключи хэшерами из `hash_builder`.
+    pub fn build_with_hasher<I: IntoIterator<Item = (K, V)>>(entries: I, hash_builder: S) -> Result<Self, StaticMapError> {
+        let unique: HashMap<K, V> = entries.into_iter().collect();
+        let entries: Vec<(K, V)> = unique.into_iter().collect();
+        let mut map: StaticMap<K, V, S> = StaticMap {
+            entries: Vec::new(),
+            displacements: vec![0; bucket_count(entries.len())],
+            ranks: vec![0; slot_count(entries.len())],
+            seed: SEED_STEP,
+            hash_builder,
+        };
+        let mut attempts: u32 = 1;
+        let slots: Vec<usize> = loop {
+            if let Some(slots) = map.place(&entries) {
+                break slots;
+            }
+            if attempts == MAX_ATTEMPTS {
+                return Err(StaticMapError::NoPerfectHash);
+            }
+            attempts += 1;
+            map.seed = map.seed.wrapping_add(SEED_STEP);
+        };
+
+        // Сжатие: ранг ячейки — количество занятых ячеек перед ней.
+        let mut taken: Vec<bool> = vec![false; map.ranks.len()];
+        for &slot in slots.iter() {
+            taken[slot] = true;
+        }
+        let mut rank: u32 = 0;
+        for (slot, &occupied) in taken.iter().enumerate() {
+            map.ranks[slot] = rank;
+            rank += occupied as u32;
+        }
+        let mut placed: Vec<Option<(K, V)>> = Vec::with_capacity(entries.len());
+        placed.resize_with(entries.len(), || None);
+        for (entry, slot) in entries.into_iter().zip(slots) {
+            placed[map.ranks[slot] as usize] = Some(entry);
+        }
+        map.entries = placed.into_iter().map(|entry| entry.unwrap()).collect();
+        Ok(map)
+    }
+
+    /// Возвращает индекс ключа в диапазоне `0..len()` или `None`, если
+    /// ключа нет. Разные ключи получают разные индексы.
+    pub fn index_of<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        if self.entries.is_empty() {
+            return None;
+        }
+        // У свободной ячейки ранг следующей занятой, так что отсутствующий
+        // ключ не совпадет с найденным элементом или выйдет за его конец.
+        let index: usize = self.ranks[self.slot(key)] as usize;
+        match self.entries.get(index) {
+            Some((k, _)) if k.borrow() == key => Some(index),
+            _ => None,
+        }
+    }
+
+    /// Возвращает ссылку на значение, связанное с указанным ключом.
+    pub fn get<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.index_of(key).map(|index| &self.entries[index].1)
+    }
+
+    /// Проверяет, есть ли в таблице указанный ключ.
+    pub fn contains_key<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.index_of(key).is_some()
+    }
+
+    /// Вычисляет ячейку ключа: корзина первого уровня дает смещение,
+    /// а смещение выбирает вторую хэш-функцию.
+    fn slot<Q: ?Sized + Hash>(&self, key: &Q) -> usize {
+        let bucket: usize = (self.hash_builder.hash_one((self.seed, key)) as usize) % self.displacements.len();
+        self.displaced_slot(key, self.displacements[bucket], self.ranks.len())
+    }
+
+    /// Вычисляет ячейку ключа среди `size` ячеек при данном смещении.
+    fn displaced_slot<Q: ?Sized + Hash>(&self, key: &Q, displacement: u32, size: usize) -> usize {
+        (self.hash_builder.hash_one((self.seed, displacement, key)) as usize) % size
+    }
+
+    /// Подбирает смещения всех корзин при текущей затравке.
+    ///
+    /// Возвращает ячейку каждого элемента или `None`, если какой-то
+    /// корзине не нашлось смещения.
+    fn place(&mut self, entries: &[(K, V)]) -> Option<Vec<usize>> {
+        let size: usize = self.ranks.len();
+        let mut buckets: Vec<Vec<usize>> = vec![Vec::new(); self.displacements.len()];
+        for (i, (key, _)) in entries.iter().enumerate() {
+            let bucket: usize = (self.hash_builder.hash_one((self.seed, key)) as usize) % buckets.len();
+            buckets[bucket].push(i);
+        }
+        let mut order: Vec<usize> = (0..buckets.len()).collect();
+        order.sort_by_key(|&bucket| std::cmp::Reverse(buckets[bucket].len()));
+
+        let mut slots: Vec<usize> = vec![0; entries.len()];
+        let mut taken: Vec<bool> = vec![false; size];
+        let mut candidate: Vec<usize> = Vec::with_capacity(KEYS_PER_BUCKET * 2);
+        for bucket in order.into_iter().take_while(|&bucket| !buckets[bucket].is_empty()) {
+            let displacement: u32 = (0..MAX_DISPLACEMENT).find(|&displacement| {
+                candidate.clear();
+                for &i in buckets[bucket].iter() {
+                    let slot: usize = self.displaced_slot(&entries[i].0, displacement, size);
+                    if taken[slot] || candidate.contains(&slot) {
+                        return false;
+                    }
+                    candidate.push(slot);
+                }
+                true
+            })?;
+            self.displacements[bucket] = displacement;
+            for (&i, &slot) in buckets[bucket].iter().zip(candidate.iter()) {
+                taken[slot] = true;
+                slots[i] = slot;
+            }
+        }
+        Some(slots)
+    }
+}
+
+fn bucket_count(items: usize) -> usize {
+    items.div_ceil(KEYS_PER_BUCKET).max(1)
+}
+
+fn slot_count(items: usize) -> usize {
+    ((items as f64 / LOAD_FACTOR).ceil() as usize).max(1)
+}
+
+/// Итератор по парам ключ-значение, см. `StaticMap::iter`.
+pub struct Iter<'a, K, V> {
+    inner: slice::Iter<'a, (K, V)>,
+}
+
+impl<'a, K, V> Iterator for Iter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, value)| (key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, K, V> ExactSizeIterator for Iter<'a, K, V> {}
+
+impl<'a, K, V, S> IntoIterator for &'a StaticMap<K, V, S> {
+    type Item = (&'a K, &'a V);
+    type IntoIter = Iter<'a, K, V>;
+
+    fn into_iter(self) -> Iter<'a, K, V> {
+        self.iter()
+    }
+}
+
+/// Строит таблицу, как `StaticMap::build`.
+///
+/// # Паника
+///
+/// Паникует, если совершенная хэш-функция не нашлась.
+impl<K: Eq + Hash, V> FromIterator<(K, V)> for StaticMap<K, V> {
+    fn from_iter<I: IntoIterator<Item = (K, V)>>(iter: I) -> Self {
+        Self::build(iter).expect("No perfect hash function was found for the keys.")
+    }
+}
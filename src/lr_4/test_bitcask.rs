@@ -0,0 +1,205 @@
+use super::{Bitcask, BitcaskError};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::temp_file;
+    use std::fs::{self, OpenOptions};
+    use std::io::{Seek, SeekFrom, Write};
+    use std::path::PathBuf;
+
+    fn value(store: &Bitcask, key: &str) -> Option<String> {
+        store.get(key).unwrap().map(|bytes| String::from_utf8(bytes).unwrap())
+    }
+
+    #[test]
+    fn test_bitcask_api() {
+        let path: PathBuf = temp_file("api.bitcask");
+        let mut store: Bitcask = Bitcask::open(&path).unwrap();
+        assert!(store.is_empty());
+        store.put("один", "1").unwrap();
+        store.put("два", "2").unwrap();
+        store.put("один", "uno").unwrap();
+        store.put("пусто", "").unwrap();
+        assert_eq!(store.len(), 3);
+        assert_eq!(value(&store, "один"), Some("uno".to_string()));
+        assert_eq!(value(&store, "пусто"), Some(String::new()));
+        assert_eq!(value(&store, "три"), None);
+        assert!(store.contains_key("два"));
+
+        assert!(store.delete("два").unwrap());
+        assert!(!store.delete("два").unwrap());
+        assert_eq!(value(&store, "два"), None);
+        let mut keys: Vec<&[u8]> = store.keys().collect();
+        keys.sort();
+        assert_eq!(keys, vec!["один".as_bytes(), "пусто".as_bytes()]);
+        assert!(store.dead_bytes() > 0);
+        assert!(store.dead_bytes() < store.file_size());
+        store.sync().unwrap();
+        assert_eq!(fs::metadata(&path).unwrap().len(), store.file_size());
+    }
+
+    #[test]
+    fn test_bitcask_recovery() {
+        let path: PathBuf = temp_file("recovery.bitcask");
+        {
+            let mut store: Bitcask = Bitcask::open(&path).unwrap();
+            for i in 0..100 {
+                store.put(format!("key{}", i), format!("value{}", i)).unwrap();
+            }
+            for i in (0..100).step_by(3) {
+                store.delete(format!("key{}", i)).unwrap();
+            }
+            store.put("key1", "changed").unwrap();
+            store.sync().unwrap();
+        }
+        let mut store: Bitcask = Bitcask::open(&path).unwrap();
+        assert_eq!(store.discarded_bytes(), 0);
+        assert_eq!(store.len(), 66);
+        assert_eq!(value(&store, "key0"), None);
+        assert_eq!(value(&store, "key1"), Some("changed".to_string()));
+        assert_eq!(value(&store, "key2"), Some("value2".to_string()));
+        assert_eq!(value(&store, "key99"), None);
+        assert_eq!(value(&store, "key98"), Some("value98".to_string()));
+        let dead: u64 = store.dead_bytes();
+        assert!(dead > 0);
+
+        // После открытия можно писать дальше.
+        store.put("key0", "back").unwrap();
+        drop(store);
+        let store: Bitcask = Bitcask::open(&path).unwrap();
+        assert_eq!(value(&store, "key0"), Some("back".to_string()));
+        assert_eq!(store.dead_bytes(), dead);
+    }
+
+    #[test]
+    fn test_bitcask_rejects_bad_records() {
+        let path: PathBuf = temp_file("corrupt.bitcask");
+        let length_before_last: u64;
+        {
+            let mut store: Bitcask = Bitcask::open(&path).unwrap();
+            store.put("a", "1").unwrap();
+            store.put("b", "2").unwrap();
+            length_before_last = store.file_size();
+            store.put("c", "3").unwrap();
+        }
+        // Портится значение последней записи: CRC не сходится, но запись
+        // целая, поэтому файл не трогается.
+        let length: u64 = fs::metadata(&path).unwrap().len();
+        let mut file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.seek(SeekFrom::Start(length - 1)).unwrap();
+        file.write_all(b"X").unwrap();
+        drop(file);
+        assert!(matches!(Bitcask::open(&path), Err(BitcaskError::Corrupt(offset)) if offset == length_before_last));
+        assert_eq!(fs::metadata(&path).unwrap().len(), length);
+
+        // После исправления байта все записи снова на месте.
+        let mut file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.seek(SeekFrom::Start(length - 1)).unwrap();
+        file.write_all(b"3").unwrap();
+        drop(file);
+        let mut store: Bitcask = Bitcask::open(&path).unwrap();
+        assert_eq!(store.discarded_bytes(), 0);
+        assert_eq!(value(&store, "c"), Some("3".to_string()));
+
+        // Обрезанная на середине последняя запись отбрасывается.
+        store.put("d", "4").unwrap();
+        drop(store);
+        let file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.set_len(length + 5).unwrap();
+        drop(file);
+        let mut store: Bitcask = Bitcask::open(&path).unwrap();
+        assert_eq!(store.discarded_bytes(), 5);
+        assert_eq!(fs::metadata(&path).unwrap().len(), length);
+        assert_eq!(store.len(), 3);
+        assert_eq!(value(&store, "d"), None);
+        store.put("e", "5").unwrap();
+        drop(store);
+        let store: Bitcask = Bitcask::open(&path).unwrap();
+        assert_eq!(store.discarded_bytes(), 0);
+        assert_eq!(value(&store, "e"), Some("5".to_string()));
+    }
+
+    #[test]
+    fn test_bitcask_keeps_records_after_a_corrupt_one() {
+        let path: PathBuf = temp_file("corrupt_middle.bitcask");
+        let middle: u64;
+        {
+            let mut store: Bitcask = Bitcask::open(&path).unwrap();
+            store.put("a", "1").unwrap();
+            middle = store.file_size();
+            store.put("b", "2").unwrap();
+            for i in 0..10 {
+                store.put(format!("key{}", i), format!("value{}", i)).unwrap();
+            }
+        }
+        // Портится ключ средней записи.
+        let bytes: Vec<u8> = fs::read(&path).unwrap();
+        let mut file = OpenOptions::new().write(true).open(&path).unwrap();
+        file.seek(SeekFrom::Start(middle + 12)).unwrap();
+        file.write_all(b"B").unwrap();
+        drop(file);
+
+        let error: BitcaskError = Bitcask::open(&path).err().unwrap();
+        assert!(matches!(error, BitcaskError::Corrupt(offset) if offset == middle));
+        assert_eq!(error.to_string(), format!("record at offset {} is corrupt", middle));
+        // Записи за испорченной остались в файле.
+        let after: Vec<u8> = fs::read(&path).unwrap();
+        assert_eq!(after.len(), bytes.len());
+        assert_eq!(after[middle as usize + 13..], bytes[middle as usize + 13..]);
+    }
+
+    #[test]
+    fn test_bitcask_compaction() {
+        let path: PathBuf = temp_file("compact.bitcask");
+        let mut store: Bitcask = Bitcask::open(&path).unwrap();
+        for round in 0..10 {
+            for i in 0..50 {
+                store.put(format!("key{}", i), format!("value{}-{}", i, round)).unwrap();
+            }
+        }
+        for i in 40..50 {
+            store.delete(format!("key{}", i)).unwrap();
+        }
+        let before: u64 = store.file_size();
+        let dead: u64 = store.dead_bytes();
+        store.compact().unwrap();
+        assert_eq!(store.file_size(), before - dead);
+        assert_eq!(store.dead_bytes(), 0);
+        assert_eq!(fs::metadata(&path).unwrap().len(), store.file_size());
+        assert_eq!(store.len(), 40);
+        for i in 0..40 {
+            assert_eq!(value(&store, &format!("key{}", i)), Some(format!("value{}-9", i)));
+        }
+
+        store.put("key0", "after").unwrap();
+        store.delete("key1").unwrap();
+        drop(store);
+        let store: Bitcask = Bitcask::open(&path).unwrap();
+        assert_eq!(store.len(), 39);
+        assert_eq!(value(&store, "key0"), Some("after".to_string()));
+        assert_eq!(value(&store, "key1"), None);
+        assert_eq!(value(&store, "key39"), Some("value39-9".to_string()));
+    }
+
+    #[test]
+    fn test_bitcask_interrupted_compaction() {
+        let path: PathBuf = temp_file("interrupted.bitcask");
+        let mut leftover = path.clone().into_os_string();
+        leftover.push(".compact");
+        {
+            let mut store: Bitcask = Bitcask::open(&path).unwrap();
+            store.put("key", "value").unwrap();
+        }
+        fs::write(&leftover, b"half written").unwrap();
+        let store: Bitcask = Bitcask::open(&path).unwrap();
+        assert!(!PathBuf::from(&leftover).exists());
+        assert_eq!(value(&store, "key"), Some("value".to_string()));
+    }
+
+    #[test]
+    fn test_bitcask_error_display() {
+        let error: BitcaskError = BitcaskError::TooLarge(5_000_000_000);
+        assert_eq!(error.to_string(), "5000000000 bytes do not fit in a record");
+    }
+}
@@ -0,0 +1,109 @@
+use super::{BloomFilter, CountingBloomFilter, FilterError};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bloom_filter() {
+        let mut filter: BloomFilter = BloomFilter::new(10_000, 0.01);
+        assert_eq!(filter.hash_count(), 7);
+        assert!(filter.bit_count() > 90_000 && filter.bit_count() < 100_000);
+        assert!(!filter.contains(&0));
+        for i in 0..10_000 {
+            filter.insert(&i);
+        }
+        assert!((0..10_000).all(|i| filter.contains(&i)));
+        let false_positives: usize = (10_000..110_000).filter(|i| filter.contains(i)).count();
+        assert!(false_positives < 1_500, "{} false positives", false_positives);
+        assert!((filter.false_positive_rate() - 0.01).abs() < 0.005);
+        let estimate: usize = filter.estimated_len();
+        assert!(estimate > 9_500 && estimate < 10_500, "{} estimated", estimate);
+
+        filter.clear();
+        assert!(!filter.contains(&1));
+        assert_eq!(filter.estimated_len(), 0);
+    }
+
+    #[test]
+    fn test_bloom_filter_merge_and_bytes() {
+        let mut first: BloomFilter = BloomFilter::new(1_000, 0.01);
+        let mut second: BloomFilter = BloomFilter::new(1_000, 0.01);
+        for i in 0..500 {
+            first.insert(&i);
+            second.insert(&(i + 500));
+        }
+        first.merge(&second).unwrap();
+        assert!((0..1_000).all(|i| first.contains(&i)));
+        assert_eq!(first.merge(&BloomFilter::new(1_000, 0.1)), Err(FilterError::ParametersMismatch));
+
+        let bytes: Vec<u8> = first.to_bytes();
+        let restored: BloomFilter = BloomFilter::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.bit_count(), first.bit_count());
+        assert_eq!(restored.hash_count(), first.hash_count());
+        assert!((0..1_000).all(|i| restored.contains(&i)));
+        assert_eq!(restored.to_bytes(), bytes);
+
+        let truncated: Result<BloomFilter, FilterError> = BloomFilter::from_bytes(&bytes[..bytes.len() - 1]);
+        assert_eq!(truncated.err(), Some(FilterError::InvalidData));
+        let mut wrong_magic: Vec<u8> = bytes.clone();
+        wrong_magic[0] = b'X';
+        let result: Result<BloomFilter, FilterError> = BloomFilter::from_bytes(&wrong_magic);
+        assert_eq!(result.err(), Some(FilterError::InvalidData));
+    }
+
+    #[test]
+    fn test_counting_bloom_filter() {
+        let mut filter: CountingBloomFilter = CountingBloomFilter::new(1_000, 0.01);
+        assert_eq!(filter.hash_count(), 7);
+        for i in 0..1_000 {
+            filter.insert(&i);
+        }
+        filter.insert(&7);
+        assert!((0..1_000).all(|i| filter.contains(&i)));
+        for i in 0..500 {
+            assert!(filter.remove(&i));
+        }
+        assert!((500..1_000).all(|i| filter.contains(&i)));
+        // Второе добавление 7 еще не удалено.
+        assert!(filter.contains(&7));
+        assert!(filter.remove(&7));
+        let remaining: usize = (0..500).filter(|i| filter.contains(i)).count();
+        assert!(remaining < 25, "{} removed items still found", remaining);
+        let estimate: usize = filter.estimated_len();
+        assert!(estimate > 450 && estimate < 550, "{} estimated", estimate);
+        assert!(!filter.remove(&"никогда не добавлялся"));
+
+        let mut other: CountingBloomFilter = CountingBloomFilter::new(1_000, 0.01);
+        other.insert(&5_000);
+        filter.merge(&other).unwrap();
+        assert!(filter.contains(&5_000));
+        let restored: CountingBloomFilter = CountingBloomFilter::from_bytes(&filter.to_bytes()).unwrap();
+        assert_eq!(restored.counter_count(), filter.counter_count());
+        assert!((500..1_000).all(|i| restored.contains(&i)));
+        assert!(restored.contains(&5_000));
+
+        filter.clear();
+        assert!(!filter.contains(&5_000));
+        assert_eq!(filter.estimated_len(), 0);
+    }
+
+    #[test]
+    fn test_counting_bloom_filter_saturation() {
+        let mut filter: CountingBloomFilter = CountingBloomFilter::new(10, 0.01);
+        for _ in 0..300 {
+            filter.insert(&1);
+        }
+        for _ in 0..300 {
+            filter.remove(&1);
+        }
+        // Насыщенные счетчики не уменьшаются.
+        assert!(filter.contains(&1));
+    }
+
+    #[test]
+    fn test_filter_error_display() {
+        assert_eq!(FilterError::ParametersMismatch.to_string(), "filters have different parameters");
+        assert_eq!(FilterError::Full.to_string(), "filter is full");
+    }
+}
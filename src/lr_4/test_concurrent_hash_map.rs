@@ -0,0 +1,194 @@
+use super::{ConcurrentHashMap, FxBuildHasher};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+    use std::thread;
+
+    const THREADS: u64 = 8;
+
+    #[test]
+    fn test_concurrent_hash_map_api() {
+        let map: ConcurrentHashMap<String, u32> = ConcurrentHashMap::with_shards(4);
+        assert_eq!(map.shard_count(), 4);
+        assert!(map.is_empty());
+        assert_eq!(map.insert("один".to_string(), 1), None);
+        assert_eq!(map.insert("один".to_string(), 10), Some(1));
+        map.insert("два".to_string(), 2);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("один"), Some(10));
+        assert_eq!(map.get_with("два", |value| value * 100), Some(200));
+        assert!(map.contains_key("два"));
+        assert_eq!(map.remove("два"), Some(2));
+        assert_eq!(map.remove("два"), None);
+
+        assert_eq!(map.compute("три".to_string(), |_, value| Some(value.unwrap_or(3))), Some(3));
+        assert_eq!(map.compute("три".to_string(), |_, value| value.map(|v| v * 2)), Some(6));
+        assert_eq!(map.compute("три".to_string(), |_, _| None), None);
+        assert!(!map.contains_key("три"));
+        map.compute_with("четыре".to_string(), |key, _| Some(key.len() as u32));
+        assert_eq!(map.get("четыре"), Some(12));
+
+        let mut items: Vec<(String, u32)> = map.iter().collect();
+        items.sort();
+        assert_eq!(items, vec![("один".to_string(), 10), ("четыре".to_string(), 12)]);
+        map.clear();
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn test_concurrent_hash_map_spreads_keys() {
+        let map: ConcurrentHashMap<u64, u64, FxBuildHasher> = ConcurrentHashMap::with_shards_and_hasher(8, FxBuildHasher::default());
+        for key in 0..8_000 {
+            map.insert(key, key);
+        }
+        assert_eq!(map.len(), 8_000);
+        let snapshot: Vec<(u64, u64)> = map.snapshot();
+        assert_eq!(snapshot.len(), 8_000);
+        assert!(snapshot.iter().all(|(key, value)| key == value));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_concurrent_hash_map_zero_shards() {
+        let _: ConcurrentHashMap<u32, u32> = ConcurrentHashMap::with_shards(0);
+    }
+
+    #[test]
+    fn test_concurrent_hash_map_parallel_writers() {
+        let map: ConcurrentHashMap<u64, u64> = ConcurrentHashMap::with_shards(16);
+        thread::scope(|scope| {
+            for thread in 0..THREADS {
+                let map: &ConcurrentHashMap<u64, u64> = &map;
+                scope.spawn(move || {
+                    // Каждый поток вставляет свой диапазон и удаляет из него нечетные ключи.
+                    for i in 0..2_000 {
+                        let key: u64 = thread * 2_000 + i;
+                        assert_eq!(map.insert(key, key * 3), None);
+                    }
+                    for i in (1..2_000).step_by(2) {
+                        let key: u64 = thread * 2_000 + i;
+                        assert_eq!(map.remove(&key), Some(key * 3));
+                    }
+                });
+            }
+        });
+        assert_eq!(map.len(), (THREADS * 1_000) as usize);
+        for key in 0..THREADS * 2_000 {
+            let expected: Option<u64> = if key % 2 == 0 { Some(key * 3) } else { None };
+            assert_eq!(map.get(&key), expected);
+        }
+    }
+
+    #[test]
+    fn test_concurrent_hash_map_compute_counters() {
+        let map: Arc<ConcurrentHashMap<u64, u64>> = Arc::new(ConcurrentHashMap::with_shards(4));
+        let handles: Vec<thread::JoinHandle<()>> = (0..THREADS)
+            .map(|_| {
+                let map: Arc<ConcurrentHashMap<u64, u64>> = Arc::clone(&map);
+                thread::spawn(move || {
+                    for i in 0..10_000 {
+                        map.compute(i % 50, |_, count| Some(count.unwrap_or(0) + 1));
+                    }
+                })
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        assert_eq!(map.len(), 50);
+        for key in 0..50 {
+            assert_eq!(map.get(&key), Some(THREADS * 200));
+        }
+    }
+
+    #[test]
+    fn test_concurrent_hash_map_readers_and_snapshots() {
+        // Каждый писатель переносит единицы между двумя своими ключами:
+        // сначала вычитает, потом прибавляет. В любом снимке сумма меньше
+        // исходной не больше чем на число писателей.
+        let map: ConcurrentHashMap<u64, i64> = ConcurrentHashMap::with_shards(8);
+        for key in 0..THREADS * 2 {
+            map.insert(key, 100);
+        }
+        let total: i64 = (THREADS * 2 * 100) as i64;
+        let done: AtomicBool = AtomicBool::new(false);
+        thread::scope(|scope| {
+            let writers: Vec<thread::ScopedJoinHandle<()>> = (0..THREADS)
+                .map(|thread| {
+                    let map: &ConcurrentHashMap<u64, i64> = &map;
+                    scope.spawn(move || {
+                        for i in 0..2_000 {
+                            let (from, to): (u64, u64) = match i % 2 {
+                                0 => (thread * 2, thread * 2 + 1),
+                                _ => (thread * 2 + 1, thread * 2),
+                            };
+                            map.compute(from, |_, value| value.map(|v| v - 1));
+                            map.compute(to, |_, value| value.map(|v| v + 1));
+                        }
+                    })
+                })
+                .collect();
+            let reader = scope.spawn(|| {
+                let mut snapshots: usize = 0;
+                loop {
+                    let finished: bool = done.load(Ordering::Acquire);
+                    for key in 0..THREADS * 2 {
+                        assert!(map.get(&key).is_some());
+                    }
+                    let snapshot: Vec<(u64, i64)> = map.snapshot();
+                    assert_eq!(snapshot.len(), (THREADS * 2) as usize);
+                    let sum: i64 = snapshot.iter().map(|(_, value)| value).sum();
+                    assert!(sum <= total && sum >= total - THREADS as i64);
+                    snapshots += 1;
+                    if finished {
+                        return snapshots;
+                    }
+                }
+            });
+            for writer in writers {
+                writer.join().unwrap();
+            }
+            done.store(true, Ordering::Release);
+            assert!(reader.join().unwrap() > 0);
+        });
+        assert_eq!(map.iter().map(|(_, value)| value).sum::<i64>(), total);
+        for key in 0..THREADS * 2 {
+            assert_eq!(map.get(&key), Some(100));
+        }
+    }
+
+    #[test]
+    fn test_concurrent_hash_map_survives_panic() {
+        let map: ConcurrentHashMap<u32, u32> = ConcurrentHashMap::with_shards(1);
+        map.insert(1, 1);
+        thread::scope(|scope| {
+            let result = scope.spawn(|| {
+                map.compute_with(2, |_, _| panic!("сбой в потоке"));
+            });
+            assert!(result.join().is_err());
+        });
+        assert_eq!(map.get(&1), Some(1));
+        map.insert(2, 2);
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_concurrent_hash_map_compute_keeps_value_on_panic() {
+        let map: ConcurrentHashMap<u32, String> = ConcurrentHashMap::with_shards(1);
+        map.insert(1, "один".to_string());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            map.compute_with(1, |_, _| panic!("сбой при пересчете"));
+        }));
+        assert!(result.is_err());
+        assert_eq!(map.get(&1), Some("один".to_string()));
+
+        map.compute_with(1, |_, value| value.map(|value| value.to_uppercase()));
+        assert_eq!(map.get(&1), Some("ОДИН".to_string()));
+        map.compute_with(1, |_, _| None);
+        assert!(map.is_empty());
+    }
+}
@@ -0,0 +1,101 @@
+use super::{CountMinSketch, HeavyHitters, SketchError};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::next_random;
+    use std::collections::HashMap;
+
+    /// Поток с перекосом: элемент `i` из `0..items` выпадает с
+    /// вероятностью, убывающей примерно как `1 / (i + 1)`.
+    fn skewed_stream(length: usize, items: u64, seed: u64) -> Vec<u64> {
+        let mut state: u64 = seed;
+        (0..length)
+            .map(|_| {
+                let uniform: f64 = (next_random(&mut state) >> 11) as f64 / (1u64 << 53) as f64;
+                ((items as f64).powf(uniform) - 1.0) as u64
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_count_min_sketch_error_bound() {
+        let epsilon: f64 = 0.001;
+        let mut sketch: CountMinSketch = CountMinSketch::new(epsilon, 0.01);
+        assert_eq!(sketch.width(), 2_719);
+        assert_eq!(sketch.depth(), 5);
+        let stream: Vec<u64> = skewed_stream(200_000, 50_000, 0xFACE_B00C_1234_5678);
+        let mut exact: HashMap<u64, u64> = HashMap::new();
+        for item in stream.iter() {
+            sketch.add(item, 1);
+            *exact.entry(*item).or_insert(0) += 1;
+        }
+        assert_eq!(sketch.total(), 200_000);
+        let bound: u64 = (epsilon * sketch.total() as f64) as u64;
+        let mut violations: usize = 0;
+        for (item, &count) in exact.iter() {
+            let estimate: u64 = sketch.estimate(item);
+            assert!(estimate >= count);
+            if estimate > count + bound {
+                violations += 1;
+            }
+        }
+        assert!(violations * 100 <= exact.len(), "{} of {} items exceed the bound", violations, exact.len());
+    }
+
+    #[test]
+    fn test_count_min_sketch_merge() {
+        let mut first: CountMinSketch = CountMinSketch::new(0.01, 0.01);
+        let mut second: CountMinSketch = CountMinSketch::new(0.01, 0.01);
+        first.add("а", 3);
+        second.add("а", 4);
+        second.add("б", 1);
+        first.merge(&second).unwrap();
+        assert_eq!(first.estimate("а"), 7);
+        assert_eq!(first.estimate("б"), 1);
+        assert_eq!(first.total(), 8);
+        assert_eq!(first.merge(&CountMinSketch::new(0.1, 0.01)), Err(SketchError::ParametersMismatch));
+        first.clear();
+        assert_eq!(first.estimate("а"), 0);
+    }
+
+    #[test]
+    fn test_heavy_hitters() {
+        let stream: Vec<u64> = skewed_stream(200_000, 50_000, 0x0BAD_CAFE_DEAD_BEEF);
+        let mut exact: HashMap<u64, u64> = HashMap::new();
+        let mut hitters: HeavyHitters<u64> = HeavyHitters::new(10, 0.0005, 0.01);
+        for &item in stream.iter() {
+            hitters.add(item);
+            *exact.entry(item).or_insert(0) += 1;
+        }
+        let mut expected: Vec<(u64, u64)> = exact.into_iter().collect();
+        expected.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        let top: Vec<(u64, u64)> = hitters.top();
+        assert_eq!(top.len(), 10);
+        assert!(top.windows(2).all(|pair| pair[0].1 >= pair[1].1));
+        // Пять самых частых элементов заметно отстоят от остальных.
+        for (item, count) in expected.iter().take(5) {
+            let found: &(u64, u64) = top.iter().find(|(key, _)| key == item).unwrap();
+            assert!(found.1 >= *count);
+            assert!(found.1 <= count + 100);
+        }
+        assert_eq!(hitters.estimate(&expected[0].0), top[0].1);
+    }
+
+    #[test]
+    fn test_heavy_hitters_replaces_candidates() {
+        let mut hitters: HeavyHitters<&str> = HeavyHitters::new(2, 0.01, 0.01);
+        hitters.add_count("а", 5);
+        hitters.add_count("б", 3);
+        hitters.add("в");
+        assert_eq!(hitters.top(), vec![("а", 5), ("б", 3)]);
+        hitters.add_count("в", 9);
+        assert_eq!(hitters.top(), vec![("в", 10), ("а", 5)]);
+        for _ in 0..100 {
+            hitters.add("а");
+        }
+        assert_eq!(hitters.top(), vec![("а", 105), ("в", 10)]);
+        assert_eq!(hitters.sketch().total(), 118);
+    }
+}
@@ -0,0 +1,82 @@
+use super::{CuckooFilter, FilterError};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cuckoo_filter() {
+        let mut filter: CuckooFilter = CuckooFilter::new(10_000);
+        assert!(filter.is_empty());
+        for i in 0..10_000 {
+            filter.insert(&i).unwrap();
+        }
+        assert_eq!(filter.len(), 10_000);
+        assert!((0..10_000).all(|i| filter.contains(&i)));
+        let false_positives: usize = (10_000..110_000).filter(|i| filter.contains(i)).count();
+        assert!(false_positives < 300, "{} false positives", false_positives);
+
+        for i in 0..5_000 {
+            assert!(filter.remove(&i));
+        }
+        assert_eq!(filter.len(), 5_000);
+        assert!((5_000..10_000).all(|i| filter.contains(&i)));
+        let remaining: usize = (0..5_000).filter(|i| filter.contains(i)).count();
+        assert!(remaining < 20, "{} removed items still found", remaining);
+
+        filter.clear();
+        assert!(filter.is_empty());
+        assert!(!filter.contains(&5_000));
+    }
+
+    #[test]
+    fn test_cuckoo_filter_full() {
+        let mut filter: CuckooFilter = CuckooFilter::new(100);
+        let capacity: usize = filter.capacity();
+        let mut inserted: usize = 0;
+        while filter.insert(&inserted).is_ok() {
+            inserted += 1;
+        }
+        assert_eq!(filter.len(), inserted);
+        assert!(filter.load_factor() > 0.8);
+        assert!(inserted <= capacity);
+        // Неудачная вставка ничего не теряет.
+        assert!((0..inserted).all(|i| filter.contains(&i)));
+
+        // Один элемент помещается не больше восьми раз.
+        let mut duplicates: CuckooFilter = CuckooFilter::new(100);
+        let copies: usize = (0..20).take_while(|_| duplicates.insert("ключ").is_ok()).count();
+        assert_eq!(copies, 8);
+        assert_eq!(duplicates.insert("ключ"), Err(FilterError::Full));
+    }
+
+    #[test]
+    fn test_cuckoo_filter_merge_and_bytes() {
+        let mut first: CuckooFilter = CuckooFilter::new(1_000);
+        let mut second: CuckooFilter = CuckooFilter::new(1_000);
+        for i in 0..400 {
+            first.insert(&i).unwrap();
+            second.insert(&(i + 400)).unwrap();
+        }
+        first.merge(&second).unwrap();
+        assert_eq!(first.len(), 800);
+        assert!((0..800).all(|i| first.contains(&i)));
+        assert_eq!(first.merge(&CuckooFilter::new(10)), Err(FilterError::ParametersMismatch));
+
+        // Слияние, которому не хватает места, не меняет фильтр.
+        let mut full: CuckooFilter = CuckooFilter::new(1_000);
+        for i in 0..1_300 {
+            full.insert(&(i + 10_000)).unwrap();
+        }
+        let before: Vec<u8> = first.to_bytes();
+        assert_eq!(first.merge(&full), Err(FilterError::Full));
+        assert_eq!(first.to_bytes(), before);
+        assert_eq!(first.len(), 800);
+
+        let restored: CuckooFilter = CuckooFilter::from_bytes(&before).unwrap();
+        assert_eq!(restored.len(), 800);
+        assert!((0..800).all(|i| restored.contains(&i)));
+        let truncated: Result<CuckooFilter, FilterError> = CuckooFilter::from_bytes(&before[..10]);
+        assert_eq!(truncated.err(), Some(FilterError::InvalidData));
+    }
+}
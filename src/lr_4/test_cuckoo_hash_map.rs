@@ -0,0 +1,116 @@
+use super::CuckooHashMap;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::next_random;
+    use std::hash::{BuildHasherDefault, Hasher};
+
+    /// Хэшер, который запоминает только последние записанные 8 байт, так
+    /// что затравки хэш-функций на него не влияют, а хэш целого ключа
+    /// равен самому ключу.
+    #[derive(Default)]
+    struct LastWriteHasher(u64);
+
+    impl Hasher for LastWriteHasher {
+        fn finish(&self) -> u64 {
+            self.0
+        }
+
+        fn write(&mut self, bytes: &[u8]) {
+            let mut last: [u8; 8] = self.0.to_ne_bytes();
+            for &byte in bytes {
+                last.rotate_left(1);
+                last[7] = byte;
+            }
+            self.0 = u64::from_ne_bytes(last);
+        }
+    }
+
+    #[test]
+    fn test_cuckoo_hash_map() {
+        let mut map: CuckooHashMap<String, i32> = CuckooHashMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.insert("один".to_string(), 1), None);
+        assert_eq!(map.insert("два".to_string(), 2), None);
+        assert_eq!(map.insert("один".to_string(), 10), Some(1));
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("один"), Some(&10));
+        assert!(map.contains_key("два"));
+        *map.get_mut("два").unwrap() += 1;
+        assert_eq!(map.get("два"), Some(&3));
+        assert_eq!(map.remove("два"), Some(3));
+        assert_eq!(map.remove("два"), None);
+        assert_eq!(map.len(), 1);
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.get("один"), None);
+    }
+
+    #[test]
+    fn test_cuckoo_hash_map_matches_std() {
+        let mut state: u64 = 0x0123_4567_89AB_CDEF;
+        let mut map: CuckooHashMap<u64, u64> = CuckooHashMap::with_capacity(16);
+        let mut model: std::collections::HashMap<u64, u64> = std::collections::HashMap::new();
+        for step in 0..20_000 {
+            let key: u64 = next_random(&mut state) % 3_000;
+            match next_random(&mut state) % 3 {
+                0 => assert_eq!(map.remove(&key), model.remove(&key)),
+                _ => assert_eq!(map.insert(key, step), model.insert(key, step)),
+            }
+            assert_eq!(map.len(), model.len());
+        }
+        for key in 0..3_000 {
+            assert_eq!(map.get(&key), model.get(&key));
+        }
+        assert!(map.len() <= map.capacity());
+        let mut items: Vec<(u64, u64)> = map.iter().map(|(&key, &value)| (key, value)).collect();
+        let mut expected: Vec<(u64, u64)> = model.into_iter().collect();
+        items.sort();
+        expected.sort();
+        assert_eq!(items, expected);
+    }
+
+    #[test]
+    fn test_cuckoo_hash_map_collect() {
+        let map: CuckooHashMap<i32, i32> = (0..1_000).map(|i| (i, i * i)).collect();
+        assert_eq!(map.len(), 1_000);
+        assert_eq!(map.iter().len(), 1_000);
+        assert!((0..1_000).all(|i| map.get(&i) == Some(&(i * i))));
+        assert_eq!(map.get(&1_000), None);
+    }
+
+    #[test]
+    fn test_cuckoo_hash_map_rehashes_in_place() {
+        // Зацикливание вытеснений случается примерно в одной таблице из
+        // тридцати, заполненной до предела; его лечат новые хэш-функции,
+        // а не рост.
+        let mut state: u64 = 0x0F1E_2D3C_4B5A_6978;
+        for _ in 0..200 {
+            let mut map: CuckooHashMap<u64, u64> = CuckooHashMap::with_capacity(500);
+            let initial: usize = map.capacity();
+            let keys: Vec<u64> = (0..initial).map(|_| next_random(&mut state)).collect();
+            for &key in keys.iter() {
+                map.insert(key, key);
+            }
+            assert_eq!(map.capacity(), initial);
+            assert_eq!(map.len(), keys.len());
+            assert!(keys.iter().all(|key| map.get(key) == Some(key)));
+        }
+    }
+
+    #[test]
+    fn test_cuckoo_hash_map_grows_when_rehashing_fails() {
+        // При одинаковых хэш-функциях ключи с равным остатком от деления
+        // на размер таблиц (сначала 8) делят две ячейки, и третьему
+        // помогает только рост.
+        let mut map: CuckooHashMap<u64, u64, BuildHasherDefault<LastWriteHasher>> = CuckooHashMap::default();
+        let initial: usize = map.capacity();
+        for key in [0, 8, 16] {
+            map.insert(key, key);
+        }
+        assert!(map.capacity() > initial);
+        assert_eq!(map.len(), 3);
+        assert!([0, 8, 16].iter().all(|key| map.get(key) == Some(key)));
+    }
+}
@@ -1,8 +1,9 @@
-use super::HashMap;
+use super::{Entry, HashMap, HashMapStats};
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::common::test_utils::next_random;
 
     #[test]
     fn test_hash_map () {
@@ -18,4 +19,378 @@ mod tests {
         map.remove(&"key2");
         println!("{:?}", map.get(&"key2")); // None
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_hash_map_api() {
+        let mut map: HashMap<&str, i32> = HashMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.insert("a", 1), None);
+        assert_eq!(map.insert("b", 2), None);
+        assert_eq!(map.insert("a", 10), Some(1));
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&"a"), Some(&10));
+        assert!(map.contains_key(&"b"));
+        assert!(!map.contains_key(&"c"));
+
+        *map.get_mut(&"b").unwrap() += 5;
+        assert_eq!(map.get(&"b"), Some(&7));
+        assert_eq!(map.get_mut(&"c"), None);
+
+        assert_eq!(map.remove(&"a"), Some(10));
+        assert_eq!(map.remove(&"a"), None);
+        assert_eq!(map.len(), 1);
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.get(&"b"), None);
+        map.insert("c", 3);
+        assert_eq!(map.get(&"c"), Some(&3));
+    }
+
+    #[test]
+    fn test_hash_map_entry() {
+        let mut counts: HashMap<char, usize> = HashMap::new();
+        for letter in "абракадабра".chars() {
+            *counts.entry(letter).or_insert(0) += 1;
+        }
+        assert_eq!(counts.get(&'а'), Some(&5));
+        assert_eq!(counts.get(&'б'), Some(&2));
+        assert_eq!(counts.len(), 5);
+
+        counts.entry('к').and_modify(|count| *count += 100).or_insert(0);
+        counts.entry('я').and_modify(|count| *count += 100).or_insert(7);
+        assert_eq!(counts.get(&'к'), Some(&101));
+        assert_eq!(counts.get(&'я'), Some(&7));
+
+        let mut calls: u32 = 0;
+        counts.entry('а').or_insert_with(|| { calls += 1; 0 });
+        counts.entry('ю').or_insert_with(|| { calls += 1; 0 });
+        assert_eq!(calls, 1);
+        assert_eq!(*counts.entry('э').or_default(), 0);
+
+        match counts.entry('а') {
+            Entry::Occupied(mut entry) => {
+                assert_eq!(*entry.key(), 'а');
+                assert_eq!(entry.insert(50), 5);
+                assert_eq!(*entry.get(), 50);
+            }
+            Entry::Vacant(_) => panic!("'а' is in the map"),
+        }
+        match counts.entry('щ') {
+            Entry::Occupied(_) => panic!("'щ' is not in the map"),
+            Entry::Vacant(entry) => {
+                assert_eq!(*entry.key(), 'щ');
+                *entry.insert(1) += 1;
+            }
+        }
+        assert_eq!(counts.get(&'щ'), Some(&2));
+        assert_eq!(counts.len(), 9);
+    }
+
+    #[test]
+    fn test_hash_map_matches_std() {
+        let mut state: u64 = 0x0123_4567_89AB_CDEF;
+        let mut map: HashMap<u64, u64> = HashMap::new();
+        let mut model: std::collections::HashMap<u64, u64> = std::collections::HashMap::new();
+        for step in 0..20_000 {
+            let key: u64 = next_random(&mut state) % 1_000;
+            match next_random(&mut state) % 4 {
+                0 => assert_eq!(map.remove(&key), model.remove(&key)),
+                1 => {
+                    *map.entry(key).or_insert(0) += step;
+                    *model.entry(key).or_insert(0) += step;
+                }
+                _ => assert_eq!(map.insert(key, step), model.insert(key, step)),
+            }
+            assert_eq!(map.len(), model.len());
+        }
+        for key in 0..1_000 {
+            assert_eq!(map.get(&key), model.get(&key));
+        }
+    }
+
+    #[test]
+    fn test_hash_map_iterators() {
+        let mut map: HashMap<u32, u32> = (0..100).map(|i| (i, i * i)).collect();
+        assert_eq!(map.len(), 100);
+        assert_eq!(map.iter().len(), 100);
+
+        let mut pairs: Vec<(u32, u32)> = map.iter().map(|(key, value)| (*key, *value)).collect();
+        pairs.sort();
+        assert_eq!(pairs, (0..100).map(|i| (i, i * i)).collect::<Vec<(u32, u32)>>());
+
+        let mut keys: Vec<u32> = map.keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, (0..100).collect::<Vec<u32>>());
+        assert_eq!(map.values().map(|value| *value as u64).sum::<u64>(), (0..100u64).map(|i| i * i).sum());
+
+        for (key, value) in map.iter_mut() {
+            *value += key;
+        }
+        for value in map.values_mut() {
+            *value += 1;
+        }
+        for (key, value) in &mut map {
+            *value -= key;
+        }
+        assert_eq!(map[&7], 50);
+
+        map.retain(|key, _| key % 3 == 0);
+        assert_eq!(map.len(), 34);
+        assert!(map.keys().all(|key| key % 3 == 0));
+
+        let mut total: usize = 0;
+        for (_, _) in &map {
+            total += 1;
+        }
+        assert_eq!(total, 34);
+
+        let mut owned: Vec<(u32, u32)> = map.clone().into_iter().collect();
+        owned.sort();
+        assert_eq!(owned.len(), 34);
+        assert_eq!(owned[1], (3, 10));
+
+        let drained: Vec<(u32, u32)> = map.drain().collect();
+        assert_eq!(drained.len(), 34);
+        assert!(map.is_empty());
+        assert_eq!(map.iter().next(), None);
+
+        // A drain dropped half way still empties the map.
+        map.extend((0..10).map(|i| (i, i)));
+        let mut drain = map.drain();
+        assert_eq!(drain.len(), 10);
+        drain.next();
+        drop(drain);
+        assert!(map.is_empty());
+        assert_eq!(map.get(&5), None);
+        map.insert(5, 5);
+        assert_eq!(map.len(), 1);
+    }
+
+    #[test]
+    fn test_hash_map_traits() {
+        let first: HashMap<&str, i32> = vec![("a", 1), ("b", 2), ("a", 3)].into_iter().collect();
+        assert_eq!(first.len(), 2);
+        assert_eq!(first[&"a"], 3);
+
+        let mut second: HashMap<&str, i32> = HashMap::default();
+        second.extend(vec![("b", 2), ("a", 3)]);
+        assert!(first == second);
+        second.insert("c", 0);
+        assert!(first != second);
+        second.remove(&"c");
+        second.insert("a", 4);
+        assert!(first != second);
+
+        let copy: HashMap<&str, i32> = first.clone();
+        assert!(copy == first);
+
+        let single: HashMap<&str, i32> = vec![("a", 1)].into_iter().collect();
+        assert_eq!(format!("{:?}", single), "{\"a\": 1}");
+        assert_eq!(format!("{:?}", HashMap::<i32, i32>::new()), "{}");
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_hash_map_index_missing_key() {
+        let map: HashMap<i32, i32> = HashMap::new();
+        let _ = map[&1];
+    }
+
+    #[test]
+    fn test_hash_map_borrowed_keys() {
+        let mut map: HashMap<String, u32> = HashMap::new();
+        map.insert("один".to_string(), 1);
+        map.insert("два".to_string(), 2);
+        assert_eq!(map.get("один"), Some(&1));
+        assert!(map.contains_key("два"));
+        assert!(!map.contains_key("три"));
+        *map.get_mut("два").unwrap() *= 10;
+        assert_eq!(map["два"], 20);
+        assert_eq!(map.remove("один"), Some(1));
+        assert_eq!(map.remove("один"), None);
+        assert_eq!(map.len(), 1);
+
+        let mut bytes: HashMap<Vec<u8>, &str> = HashMap::new();
+        bytes.insert(b"key".to_vec(), "value");
+        let key: &[u8] = b"key";
+        assert_eq!(bytes.get(key), Some(&"value"));
+        assert_eq!(bytes.remove(key), Some("value"));
+    }
+
+    #[test]
+    fn test_hash_map_capacity() {
+        let mut map: HashMap<u32, u32> = HashMap::with_capacity(10_000);
+        let buckets: usize = map.stats().buckets;
+        assert!(map.capacity() >= 10_000);
+        for i in 0..10_000 {
+            map.insert(i, i);
+        }
+        assert_eq!(map.stats().rehashes, 0);
+        assert_eq!(map.stats().buckets, buckets);
+
+        // Удаления не сжимают таблицу меньше запрошенной емкости.
+        for i in 0..10_000 {
+            map.remove(&i);
+        }
+        assert_eq!(map.stats().buckets, buckets);
+        map.shrink_to_fit();
+        assert_eq!(map.stats().buckets, 1);
+
+        let mut map: HashMap<u32, u32> = HashMap::new();
+        map.reserve(500);
+        let capacity: usize = map.capacity();
+        assert!(capacity >= 500);
+        for i in 0..500 {
+            map.insert(i, i);
+        }
+        assert_eq!(map.capacity(), capacity);
+        assert_eq!(map.stats().rehashes, 1);
+        map.reserve(0);
+        assert_eq!(map.stats().rehashes, 1);
+    }
+
+    #[test]
+    fn test_hash_map_auto_shrink() {
+        let mut map: HashMap<u32, u32> = HashMap::new();
+        for i in 0..4096 {
+            map.insert(i, i);
+        }
+        let grown: usize = map.stats().buckets;
+        for i in 0..4000 {
+            assert_eq!(map.remove(&i), Some(i));
+        }
+        let stats: HashMapStats = map.stats();
+        assert!(stats.buckets < grown / 8);
+        assert!(stats.items as f64 <= stats.buckets as f64 * stats.load_factor);
+        for i in 4000..4096 {
+            assert_eq!(map.get(&i), Some(&i));
+        }
+
+        map.retain(|key, _| *key % 2 == 0);
+        assert_eq!(map.len(), 48);
+        assert!(map.stats().buckets <= stats.buckets);
+        assert!(map.iter().all(|(key, value)| key % 2 == 0 && key == value));
+    }
+
+    #[test]
+    fn test_hash_map_load_factor() {
+        let mut map: HashMap<u32, u32> = HashMap::with_load_factor(0.5);
+        for i in 0..1000 {
+            map.insert(i, i);
+        }
+        assert!(map.stats().longest_chain <= map.len());
+        assert!(map.len() as f64 <= map.stats().buckets as f64 * 0.5);
+
+        map.set_load_factor(8.0);
+        assert_eq!(map.load_factor(), 8.0);
+        map.shrink_to_fit();
+        assert_eq!(map.stats().buckets, 125);
+        map.set_load_factor(1.0);
+        assert!(map.stats().buckets >= 1000);
+        for i in 0..1000 {
+            assert_eq!(map.get(&i), Some(&i));
+        }
+    }
+
+    #[test]
+    fn test_hash_map_stats() {
+        let map: HashMap<u32, u32> = HashMap::new();
+        let stats: HashMapStats = map.stats();
+        assert_eq!(stats.items, 0);
+        assert_eq!(stats.longest_chain, 0);
+        assert_eq!(stats.chain_histogram, vec![stats.buckets]);
+
+        let map: HashMap<u32, u32> = (0..300).map(|i| (i, i)).collect();
+        let stats: HashMapStats = map.stats();
+        assert_eq!(stats.items, 300);
+        assert_eq!(stats.chain_histogram.len(), stats.longest_chain + 1);
+        assert_eq!(stats.chain_histogram.iter().sum::<usize>(), stats.buckets);
+        let total: usize = stats.chain_histogram.iter().enumerate().map(|(length, count)| length * count).sum();
+        assert_eq!(total, 300);
+        assert!(stats.rehashes > 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_hash_map_zero_load_factor() {
+        let _: HashMap<u32, u32> = HashMap::with_load_factor(0.0);
+    }
+
+    #[test]
+    fn test_hash_map_incremental_rehash() {
+        let mut map: HashMap<u32, u32> = HashMap::new();
+        map.set_incremental_rehash(true);
+        let mut inserted: u32 = 0;
+        while !map.is_rehashing() || map.stats().pending_buckets < 16 {
+            map.insert(inserted, inserted);
+            inserted += 1;
+        }
+
+        // Пока идет перенос, элементы видны из обеих таблиц.
+        let stats: HashMapStats = map.stats();
+        assert!(map.is_rehashing());
+        assert_eq!(stats.chain_histogram.iter().sum::<usize>(), stats.buckets);
+        let total: usize = stats.chain_histogram.iter().enumerate().map(|(length, count)| length * count).sum();
+        assert_eq!(total, inserted as usize);
+        assert_eq!(map.iter().len(), inserted as usize);
+        assert_eq!(map.iter().count(), inserted as usize);
+        for i in 0..inserted {
+            assert_eq!(map.get(&i), Some(&i));
+        }
+
+        // Каждая операция переносит лишь несколько корзин.
+        let pending: usize = stats.pending_buckets;
+        map.insert(inserted, inserted);
+        assert!(map.stats().pending_buckets < pending);
+        assert!(map.stats().pending_buckets + 4 >= pending);
+
+        assert_eq!(map.remove(&0), Some(0));
+        *map.entry(1).or_insert(0) += 100;
+        assert_eq!(map.get(&1), Some(&101));
+        map.set_incremental_rehash(false);
+        assert!(!map.is_rehashing());
+        assert_eq!(map.stats().pending_buckets, 0);
+        assert_eq!(map.len(), inserted as usize);
+        for i in 1..=inserted {
+            assert!(map.contains_key(&i));
+        }
+    }
+
+    #[test]
+    fn test_hash_map_incremental_matches_std() {
+        let mut state: u64 = 0xFEDC_BA98_7654_3210;
+        let mut map: HashMap<u64, u64> = HashMap::with_load_factor(0.75);
+        map.set_incremental_rehash(true);
+        let mut model: std::collections::HashMap<u64, u64> = std::collections::HashMap::new();
+        let mut migrations: usize = 0;
+        for step in 0..30_000 {
+            // Сначала таблица в основном растет, потом в основном сжимается.
+            let key: u64 = next_random(&mut state) % 5_000;
+            let remove: bool = next_random(&mut state) % 10 < if step < 15_000 { 2 } else { 8 };
+            match (remove, step % 3) {
+                (true, _) => assert_eq!(map.remove(&key), model.remove(&key)),
+                (false, 0) => {
+                    *map.entry(key).or_insert(0) += step;
+                    *model.entry(key).or_insert(0) += step;
+                }
+                (false, _) => assert_eq!(map.insert(key, step), model.insert(key, step)),
+            }
+            if map.is_rehashing() {
+                migrations += 1;
+                assert_eq!(map.get(&key), model.get(&key));
+            }
+            assert_eq!(map.len(), model.len());
+        }
+        assert!(migrations > 0);
+        for key in 0..5_000 {
+            assert_eq!(map.get(&key), model.get(&key));
+        }
+        let mut pairs: Vec<(u64, u64)> = map.drain().collect();
+        pairs.sort();
+        let mut expected: Vec<(u64, u64)> = model.into_iter().collect();
+        expected.sort();
+        assert_eq!(pairs, expected);
+        assert_eq!(map.iter().count(), 0);
+    }
+}
@@ -0,0 +1,105 @@
+use super::{FxBuildHasher, HashSet};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted<'a, I: Iterator<Item = &'a i32>>(iter: I) -> Vec<i32> {
+        let mut values: Vec<i32> = iter.copied().collect();
+        values.sort();
+        values
+    }
+
+    #[test]
+    fn test_hash_set_api() {
+        let mut set: HashSet<String> = HashSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert("один".to_string()));
+        assert!(!set.insert("один".to_string()));
+        assert!(set.insert("два".to_string()));
+        assert_eq!(set.len(), 2);
+        assert!(set.contains("один"));
+        assert!(!set.contains("три"));
+        assert!(set.remove("один"));
+        assert!(!set.remove("один"));
+        assert_eq!(set.iter().collect::<Vec<&String>>(), vec!["два"]);
+
+        let mut numbers: HashSet<i32, FxBuildHasher> = (0..100).collect();
+        numbers.retain(|value| value % 10 == 0);
+        assert_eq!(sorted(numbers.iter()), vec![0, 10, 20, 30, 40, 50, 60, 70, 80, 90]);
+        numbers.extend(vec![5, 10, 15]);
+        assert_eq!(numbers.len(), 12);
+        let mut owned: Vec<i32> = numbers.clone().into_iter().collect();
+        owned.sort();
+        assert_eq!(owned.len(), 12);
+        numbers.clear();
+        assert!(numbers.is_empty());
+    }
+
+    #[test]
+    fn test_hash_set_operations() {
+        let a: HashSet<i32> = (1..=6).collect();
+        let b: HashSet<i32> = (4..=9).collect();
+        assert_eq!(sorted(a.union(&b)), (1..=9).collect::<Vec<i32>>());
+        assert_eq!(sorted(a.intersection(&b)), vec![4, 5, 6]);
+        assert_eq!(sorted(b.intersection(&a)), vec![4, 5, 6]);
+        assert_eq!(sorted(a.difference(&b)), vec![1, 2, 3]);
+        assert_eq!(sorted(b.difference(&a)), vec![7, 8, 9]);
+        assert_eq!(sorted(a.symmetric_difference(&b)), vec![1, 2, 3, 7, 8, 9]);
+
+        let small: HashSet<i32> = vec![2, 3].into_iter().collect();
+        let empty: HashSet<i32> = HashSet::new();
+        assert!(small.is_subset(&a));
+        assert!(!small.is_subset(&b));
+        assert!(a.is_superset(&small));
+        assert!(empty.is_subset(&small));
+        assert!(a.is_subset(&a));
+        assert!(small.is_disjoint(&b));
+        assert!(!a.is_disjoint(&b));
+        assert_eq!(a.union(&empty).count(), 6);
+        assert_eq!(a.intersection(&empty).count(), 0);
+    }
+
+    #[test]
+    fn test_hash_set_matches_std() {
+        let mut state: u64 = 0x0F1E_2D3C_4B5A_6978;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 300) as i32
+        };
+        let mut a: HashSet<i32> = HashSet::new();
+        let mut b: HashSet<i32> = HashSet::new();
+        let mut model_a: std::collections::HashSet<i32> = std::collections::HashSet::new();
+        let mut model_b: std::collections::HashSet<i32> = std::collections::HashSet::new();
+        for _ in 0..500 {
+            let (x, y): (i32, i32) = (next(), next());
+            assert_eq!(a.insert(x), model_a.insert(x));
+            assert_eq!(b.insert(y), model_b.insert(y));
+            let z: i32 = next();
+            assert_eq!(a.remove(&z), model_a.remove(&z));
+        }
+        assert_eq!(sorted(a.union(&b)), sorted(model_a.union(&model_b)));
+        assert_eq!(sorted(a.intersection(&b)), sorted(model_a.intersection(&model_b)));
+        assert_eq!(sorted(a.difference(&b)), sorted(model_a.difference(&model_b)));
+        assert_eq!(sorted(a.symmetric_difference(&b)), sorted(model_a.symmetric_difference(&model_b)));
+        assert_eq!(a.is_subset(&b), model_a.is_subset(&model_b));
+    }
+
+    #[test]
+    fn test_hash_set_traits() {
+        let a: HashSet<i32> = vec![1, 2, 3].into_iter().collect();
+        let b: HashSet<i32> = vec![3, 2, 1, 1].into_iter().collect();
+        assert_eq!(a, b);
+        assert_ne!(a, (1..=4).collect());
+        assert_eq!(format!("{:?}", HashSet::<i32>::from_iter(vec![7])), "{7}");
+        assert_eq!(HashSet::<i32>::default().len(), 0);
+        let mut total: i32 = 0;
+        for value in &a {
+            total += value;
+        }
+        assert_eq!(total, 6);
+        assert_eq!(a.iter().len(), 3);
+    }
+}
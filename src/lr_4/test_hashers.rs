@@ -0,0 +1,82 @@
+use super::{FnvBuildHasher, FnvHasher, FxBuildHasher, FxHasher, HashMap, SipBuildHasher, SipHasher};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasher, Hasher};
+
+    #[test]
+    fn test_siphash_reference_vectors() {
+        // Векторы из статьи SipHash: ключ 00..0f, сообщение 00..(n - 1).
+        let k0: u64 = u64::from_le_bytes([0, 1, 2, 3, 4, 5, 6, 7]);
+        let k1: u64 = u64::from_le_bytes([8, 9, 10, 11, 12, 13, 14, 15]);
+        let message: Vec<u8> = (0..15).collect();
+
+        let empty: SipHasher = SipHasher::new_with_keys(k0, k1);
+        assert_eq!(empty.finish(), 0x726f_db47_dd0e_0e31);
+
+        let mut hasher: SipHasher = SipHasher::new_with_keys(k0, k1);
+        hasher.write(&message);
+        assert_eq!(hasher.finish(), 0xa129_ca61_49be_45e5);
+
+        // Запись по частям дает тот же результат.
+        let mut hasher: SipHasher = SipHasher::new_with_keys(k0, k1);
+        hasher.write(&message[..3]);
+        hasher.write(&message[3..10]);
+        hasher.write(&message[10..]);
+        assert_eq!(hasher.finish(), 0xa129_ca61_49be_45e5);
+    }
+
+    #[test]
+    fn test_fnv_reference_vectors() {
+        let mut hasher: FnvHasher = FnvHasher::default();
+        assert_eq!(hasher.finish(), 0xcbf2_9ce4_8422_2325);
+        hasher.write(b"a");
+        assert_eq!(hasher.finish(), 0xaf63_dc4c_8601_ec8c);
+        let mut hasher: FnvHasher = FnvHasher::default();
+        hasher.write(b"foobar");
+        assert_eq!(hasher.finish(), 0x8594_4171_f739_67e8);
+    }
+
+    #[test]
+    fn test_fx_hasher() {
+        let hash_of = |value: u64| FxBuildHasher::default().hash_one(value);
+        assert_eq!(hash_of(0), 0);
+        assert_ne!(hash_of(1), hash_of(2));
+        // Одинаковые байты, записанные разными порциями, дают один хэш.
+        let mut whole: FxHasher = FxHasher::default();
+        whole.write(b"abcdefghijk");
+        let mut again: FxHasher = FxHasher::default();
+        again.write(b"abcdefghijk");
+        assert_eq!(whole.finish(), again.finish());
+    }
+
+    #[test]
+    fn test_sip_build_hasher_seeds() {
+        let hash_with = |builder: &SipBuildHasher| builder.hash_one("ключ");
+        let fixed: SipBuildHasher = SipBuildHasher::with_keys(1, 2);
+        assert_eq!(hash_with(&fixed), hash_with(&SipBuildHasher::with_keys(1, 2)));
+        assert_ne!(hash_with(&fixed), hash_with(&SipBuildHasher::with_keys(2, 1)));
+        assert_ne!(hash_with(&SipBuildHasher::random()), hash_with(&SipBuildHasher::random()));
+    }
+
+    #[test]
+    fn test_hash_map_with_hashers() {
+        let mut fx: HashMap<u64, u64, FxBuildHasher> = HashMap::with_hasher(FxBuildHasher::default());
+        let mut fnv: HashMap<u64, u64, FnvBuildHasher> = HashMap::with_hasher(FnvBuildHasher::default());
+        let mut sip: HashMap<u64, u64, SipBuildHasher> = HashMap::with_hasher(SipBuildHasher::random());
+        for key in 0..1_000 {
+            fx.insert(key, key * 2);
+            fnv.insert(key, key * 2);
+            sip.insert(key, key * 2);
+        }
+        for key in 0..1_000 {
+            assert_eq!(fx.get(&key), Some(&(key * 2)));
+            assert_eq!(fnv.get(&key), Some(&(key * 2)));
+            assert_eq!(sip.get(&key), Some(&(key * 2)));
+        }
+        fx.remove(&7);
+        assert_eq!(fx.get(&7), None);
+        assert_eq!(fx.get(&1_000), None);
+    }
+}
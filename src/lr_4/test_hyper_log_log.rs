@@ -0,0 +1,63 @@
+use super::{HyperLogLog, SketchError};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::next_random;
+
+    #[test]
+    fn test_hyper_log_log_error_bound() {
+        for precision in [8, 12, 14] {
+            for distinct in [10u64, 1_000, 100_000, 1_000_000] {
+                let mut sketch: HyperLogLog = HyperLogLog::new(precision);
+                assert_eq!(sketch.precision(), precision);
+                let mut state: u64 = 0x1234_5678_9ABC_DEF0 ^ distinct;
+                // Каждый элемент встречается в среднем дважды.
+                for _ in 0..distinct * 2 {
+                    sketch.insert(&(next_random(&mut state) % distinct));
+                }
+                for i in 0..distinct {
+                    sketch.insert(&i);
+                }
+                let error: f64 = (sketch.estimate() as f64 - distinct as f64).abs() / distinct as f64;
+                assert!(
+                    error < 3.0 * sketch.relative_error(),
+                    "precision {}: {} estimated for {}",
+                    precision,
+                    sketch.estimate(),
+                    distinct
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_hyper_log_log_merge() {
+        let mut first: HyperLogLog = HyperLogLog::new(12);
+        let mut second: HyperLogLog = HyperLogLog::new(12);
+        let mut union: HyperLogLog = HyperLogLog::new(12);
+        for i in 0..60_000 {
+            first.insert(&i);
+            union.insert(&i);
+        }
+        for i in 40_000..100_000 {
+            second.insert(&i);
+            union.insert(&i);
+        }
+        first.merge(&second).unwrap();
+        assert_eq!(first.registers(), union.registers());
+        assert_eq!(first.estimate(), union.estimate());
+        assert!((first.estimate() as f64 - 100_000.0).abs() < 100_000.0 * 3.0 * first.relative_error());
+        assert_eq!(first.merge(&HyperLogLog::new(10)), Err(SketchError::ParametersMismatch));
+        assert_eq!(SketchError::ParametersMismatch.to_string(), "sketches have different parameters");
+
+        first.clear();
+        assert_eq!(first.estimate(), 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "The precision must be between 4 and 16.")]
+    fn test_hyper_log_log_precision() {
+        HyperLogLog::new(20);
+    }
+}
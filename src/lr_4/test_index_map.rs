@@ -0,0 +1,109 @@
+use super::{FxBuildHasher, IndexMap};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::next_random;
+
+    #[test]
+    fn test_index_map_order() {
+        let mut map: IndexMap<String, i32> = IndexMap::new();
+        assert_eq!(map.first(), None);
+        assert_eq!(map.insert("б".to_string(), 2), None);
+        assert_eq!(map.insert("в".to_string(), 3), None);
+        assert_eq!(map.insert_full("а".to_string(), 1), (2, None));
+        assert_eq!(map.insert_full("б".to_string(), 20), (0, Some(2)));
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.keys().map(String::as_str).collect::<Vec<&str>>(), vec!["б", "в", "а"]);
+        assert_eq!(map.values().copied().collect::<Vec<i32>>(), vec![20, 3, 1]);
+        assert_eq!(map.first(), Some((&"б".to_string(), &20)));
+        assert_eq!(map.last(), Some((&"а".to_string(), &1)));
+        assert_eq!(map.get_index_of("в"), Some(1));
+        assert_eq!(map.get_index(3), None);
+        *map.get_index_mut(1).unwrap().1 += 30;
+        assert_eq!(map["в"], 33);
+        for (_, value) in map.iter_mut() {
+            *value *= 2;
+        }
+        assert_eq!(format!("{:?}", map), r#"{"б": 40, "в": 66, "а": 2}"#);
+
+        map.sort_keys();
+        assert_eq!(map.keys().map(String::as_str).collect::<Vec<&str>>(), vec!["а", "б", "в"]);
+        assert_eq!(map.get_index_of("в"), Some(2));
+        map.sort_by(|_, value1, _, value2| value2.cmp(value1));
+        assert_eq!(map.values().copied().collect::<Vec<i32>>(), vec![66, 40, 2]);
+        assert_eq!(map.get("а"), Some(&2));
+
+        assert_eq!(map.pop(), Some(("а".to_string(), 2)));
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.get("б"), None);
+    }
+
+    #[test]
+    fn test_index_map_removal() {
+        let mut map: IndexMap<i32, i32> = (0..10).map(|key| (key, key * 10)).collect();
+        assert_eq!(map.swap_remove(&2), Some(20));
+        assert_eq!(map.swap_remove(&2), None);
+        assert_eq!(map.keys().copied().collect::<Vec<i32>>(), vec![0, 1, 9, 3, 4, 5, 6, 7, 8]);
+        assert_eq!(map.get_index_of(&9), Some(2));
+        assert_eq!(map.shift_remove(&1), Some(10));
+        assert_eq!(map.keys().copied().collect::<Vec<i32>>(), vec![0, 9, 3, 4, 5, 6, 7, 8]);
+        assert!(map.keys().enumerate().all(|(index, key)| map.get_index_of(key) == Some(index)));
+        assert_eq!(map.shift_remove_index(7), Some((8, 80)));
+        assert_eq!(map.swap_remove_index(7), None);
+
+        map.retain(|key, _| key % 3 == 0);
+        assert_eq!(map.keys().copied().collect::<Vec<i32>>(), vec![0, 9, 3, 6]);
+        assert_eq!(map.get(&6), Some(&60));
+        assert_eq!(map.into_iter().collect::<Vec<(i32, i32)>>(), vec![(0, 0), (9, 90), (3, 30), (6, 60)]);
+    }
+
+    #[test]
+    fn test_index_map_matches_model() {
+        let mut state: u64 = 0x1357_9BDF_2468_ACE0;
+        let mut map: IndexMap<u64, u64, FxBuildHasher> = IndexMap::default();
+        let mut model: Vec<(u64, u64)> = Vec::new();
+        for step in 0..10_000 {
+            let key: u64 = next_random(&mut state) % 500;
+            let position: Option<usize> = model.iter().position(|(other, _)| *other == key);
+            match next_random(&mut state) % 4 {
+                0 => {
+                    let removed: Option<u64> = position.map(|index| model.swap_remove(index).1);
+                    assert_eq!(map.swap_remove(&key), removed);
+                }
+                1 => {
+                    let removed: Option<u64> = position.map(|index| model.remove(index).1);
+                    assert_eq!(map.shift_remove(&key), removed);
+                }
+                _ => {
+                    let old: Option<u64> = match position {
+                        Some(index) => Some(std::mem::replace(&mut model[index].1, step)),
+                        None => {
+                            model.push((key, step));
+                            None
+                        }
+                    };
+                    assert_eq!(map.insert(key, step), old);
+                }
+            }
+            assert_eq!(map.len(), model.len());
+            assert_eq!(map.get_index_of(&key), model.iter().position(|(other, _)| *other == key));
+        }
+        let items: Vec<(u64, u64)> = map.iter().map(|(&key, &value)| (key, value)).collect();
+        assert_eq!(items, model);
+        for (index, (key, value)) in model.iter().enumerate() {
+            assert_eq!(map.get(key), Some(value));
+            assert_eq!(map.get_index(index), Some((key, value)));
+        }
+    }
+
+    #[test]
+    fn test_index_map_equality() {
+        let a: IndexMap<i32, i32> = vec![(1, 1), (2, 2)].into_iter().collect();
+        let b: IndexMap<i32, i32> = vec![(2, 2), (1, 1)].into_iter().collect();
+        assert_eq!(a, b);
+        assert_eq!(a.iter().next_back(), Some((&2, &2)));
+        assert_eq!(a.clone(), a);
+    }
+}
@@ -0,0 +1,115 @@
+use super::LfuCache;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::next_random;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_lfu_cache_api() {
+        let mut cache: LfuCache<&str, u32> = LfuCache::new(3);
+        assert_eq!(cache.capacity(), 3);
+        assert!(cache.is_empty());
+        cache.put("а", 1);
+        cache.put("б", 2);
+        cache.put("в", 3);
+        assert_eq!(cache.frequency(&"а"), Some(1));
+        assert_eq!(cache.get(&"а"), Some(&1));
+        assert_eq!(cache.get(&"а"), Some(&1));
+        assert_eq!(cache.put("б", 20), Some(2));
+        assert_eq!(cache.frequency(&"а"), Some(3));
+        assert_eq!(cache.frequency(&"б"), Some(2));
+
+        // `peek` обращение не засчитывает.
+        assert_eq!(cache.peek(&"в"), Some(&3));
+        assert_eq!(cache.frequency(&"в"), Some(1));
+        assert_eq!(cache.peek_lfu(), Some((&"в", &3)));
+
+        cache.put("г", 4);
+        assert!(!cache.contains(&"в"));
+        assert_eq!(cache.len(), 3);
+        // Одно обращение теперь только у «г».
+        assert_eq!(cache.peek_lfu(), Some((&"г", &4)));
+
+        *cache.get_mut(&"г").unwrap() += 40;
+        assert_eq!(cache.frequency(&"г"), Some(2));
+        // Теперь у «б» и «г» по два обращения, давнее у «б».
+        assert_eq!(cache.pop_lfu(), Some(("б", 20)));
+        assert_eq!(cache.pop(&"а"), Some(1));
+        assert_eq!(cache.pop(&"а"), None);
+        assert_eq!(cache.pop_lfu(), Some(("г", 44)));
+        assert_eq!(cache.pop_lfu(), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_lfu_cache_eviction_callback() {
+        let evicted: Rc<RefCell<Vec<u32>>> = Rc::new(RefCell::new(Vec::new()));
+        let log: Rc<RefCell<Vec<u32>>> = Rc::clone(&evicted);
+        let mut cache: LfuCache<u32, u32> = LfuCache::new(3);
+        cache.set_on_evict(move |key, _| log.borrow_mut().push(key));
+        for i in 0..3 {
+            cache.put(i, i);
+        }
+        for _ in 0..3 {
+            cache.get(&0);
+        }
+        cache.get(&1);
+        cache.put(3, 3);
+        cache.put(4, 4);
+        assert_eq!(*evicted.borrow(), vec![2, 3]);
+        cache.set_capacity(1);
+        assert_eq!(*evicted.borrow(), vec![2, 3, 4, 1]);
+        assert_eq!(cache.peek(&0), Some(&0));
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(evicted.borrow().len(), 4);
+    }
+
+    #[test]
+    fn test_lfu_cache_matches_model() {
+        // Модель — ключ, значение, частота и время последнего обращения.
+        let mut state: u64 = 0x2468_ACE0_1357_9BDF;
+        let mut cache: LfuCache<u64, u64> = LfuCache::new(8);
+        let mut model: Vec<(u64, u64, usize, u64)> = Vec::new();
+        for step in 0..20_000u64 {
+            let key: u64 = next_random(&mut state) % 20;
+            let position: Option<usize> = model.iter().position(|entry| entry.0 == key);
+            match next_random(&mut state) % 4 {
+                0 => {
+                    let expected: Option<u64> = position.map(|position| model.remove(position).1);
+                    assert_eq!(cache.pop(&key), expected);
+                }
+                1 => {
+                    let expected: Option<u64> = position.map(|position| {
+                        model[position].2 += 1;
+                        model[position].3 = step;
+                        model[position].1
+                    });
+                    assert_eq!(cache.get(&key).copied(), expected);
+                }
+                _ => match position {
+                    Some(position) => {
+                        let old: u64 = model[position].1;
+                        model[position] = (key, step, model[position].2 + 1, step);
+                        assert_eq!(cache.put(key, step), Some(old));
+                    }
+                    None => {
+                        if model.len() == 8 {
+                            let victim: usize = (0..model.len()).min_by_key(|i| (model[*i].2, model[*i].3)).unwrap();
+                            model.remove(victim);
+                        }
+                        model.push((key, step, 1, step));
+                        assert_eq!(cache.put(key, step), None);
+                    }
+                },
+            }
+            assert_eq!(cache.len(), model.len());
+            for entry in model.iter() {
+                assert_eq!(cache.frequency(&entry.0), Some(entry.2));
+            }
+        }
+    }
+}
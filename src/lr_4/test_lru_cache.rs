@@ -0,0 +1,115 @@
+use super::LruCache;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::next_random;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn test_lru_cache_api() {
+        let mut cache: LruCache<String, u32> = LruCache::new(3);
+        assert!(cache.is_empty());
+        assert_eq!(cache.capacity(), 3);
+        assert_eq!(cache.put("а".to_string(), 1), None);
+        assert_eq!(cache.put("б".to_string(), 2), None);
+        assert_eq!(cache.put("в".to_string(), 3), None);
+        assert_eq!(cache.put("а".to_string(), 10), Some(1));
+        assert_eq!(cache.len(), 3);
+
+        // Порядок от свежего к старому: а, в, б.
+        let keys: Vec<&str> = cache.iter().map(|(key, _)| key.as_str()).collect();
+        assert_eq!(keys, vec!["а", "в", "б"]);
+        assert_eq!(cache.peek_lru(), Some((&"б".to_string(), &2)));
+
+        // `peek` не меняет порядок, а `get` меняет.
+        assert_eq!(cache.peek("б"), Some(&2));
+        assert_eq!(cache.peek_lru().map(|(key, _)| key.as_str()), Some("б"));
+        assert_eq!(cache.get("б"), Some(&2));
+        assert_eq!(cache.peek_lru().map(|(key, _)| key.as_str()), Some("в"));
+        *cache.get_mut("в").unwrap() += 30;
+        assert_eq!(cache.peek_lru().map(|(key, _)| key.as_str()), Some("а"));
+
+        cache.put("г".to_string(), 4);
+        assert!(!cache.contains("а"));
+        assert_eq!(cache.get("а"), None);
+        assert_eq!(cache.pop("в"), Some(33));
+        assert_eq!(cache.pop("в"), None);
+        assert_eq!(cache.pop_lru(), Some(("б".to_string(), 2)));
+        assert_eq!(cache.pop_lru(), Some(("г".to_string(), 4)));
+        assert_eq!(cache.pop_lru(), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_lru_cache_eviction_callback() {
+        let evicted: Rc<RefCell<Vec<(u32, u32)>>> = Rc::new(RefCell::new(Vec::new()));
+        let log: Rc<RefCell<Vec<(u32, u32)>>> = Rc::clone(&evicted);
+        let mut cache: LruCache<u32, u32> = LruCache::new(2);
+        cache.set_on_evict(move |key, value| log.borrow_mut().push((key, value)));
+        for i in 0..5 {
+            cache.put(i, i * 10);
+        }
+        assert_eq!(*evicted.borrow(), vec![(0, 0), (1, 10), (2, 20)]);
+
+        // Явное удаление и очистка функцию не вызывают.
+        cache.pop(&3);
+        cache.pop_lru();
+        cache.put(7, 70);
+        cache.clear();
+        assert_eq!(evicted.borrow().len(), 3);
+
+        for i in 0..4 {
+            cache.put(i, i);
+        }
+        cache.set_capacity(1);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.peek(&3), Some(&3));
+        assert_eq!(&evicted.borrow()[3..], &[(0, 0), (1, 1), (2, 2)]);
+    }
+
+    #[test]
+    fn test_lru_cache_matches_model() {
+        // Модель — вектор ключей от старого к свежему.
+        let mut state: u64 = 0x1357_9BDF_2468_ACE0;
+        let mut cache: LruCache<u64, u64> = LruCache::new(16);
+        let mut model: Vec<(u64, u64)> = Vec::new();
+        for step in 0..20_000 {
+            let key: u64 = next_random(&mut state) % 40;
+            let position: Option<usize> = model.iter().position(|(k, _)| *k == key);
+            match next_random(&mut state) % 3 {
+                0 => {
+                    let expected: Option<u64> = position.map(|position| model.remove(position).1);
+                    assert_eq!(cache.pop(&key), expected);
+                }
+                1 => {
+                    let expected: Option<u64> = position.map(|position| {
+                        let entry: (u64, u64) = model.remove(position);
+                        model.push(entry);
+                        entry.1
+                    });
+                    assert_eq!(cache.get(&key).copied(), expected);
+                }
+                _ => {
+                    let old: Option<u64> = position.map(|position| model.remove(position).1);
+                    if old.is_none() && model.len() == 16 {
+                        model.remove(0);
+                    }
+                    model.push((key, step));
+                    assert_eq!(cache.put(key, step), old);
+                }
+            }
+            assert_eq!(cache.len(), model.len());
+        }
+        let order: Vec<(u64, u64)> = cache.iter().map(|(key, value)| (*key, *value)).collect();
+        model.reverse();
+        assert_eq!(order, model);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_lru_cache_zero_capacity() {
+        let _: LruCache<u32, u32> = LruCache::new(0);
+    }
+}
@@ -0,0 +1,86 @@
+use super::MultiMap;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_multi_map_api() {
+        let mut map: MultiMap<String, u32> = MultiMap::new();
+        assert!(map.is_empty());
+        map.insert("а".to_string(), 1);
+        map.insert("а".to_string(), 2);
+        map.insert("а".to_string(), 1);
+        map.insert("б".to_string(), 3);
+        assert_eq!(map.len(), 4);
+        assert_eq!(map.keys_len(), 2);
+        assert_eq!(map.get("а"), Some(&1));
+        assert_eq!(map.get_all("а"), &[1, 2, 1]);
+        assert_eq!(map.get_all("в"), &[] as &[u32]);
+        assert_eq!(map.count("а"), 3);
+        assert_eq!(map.count("в"), 0);
+        assert!(map.contains_key("б"));
+
+        for value in map.get_all_mut("а") {
+            *value *= 10;
+        }
+        assert_eq!(map.get_all("а"), &[10, 20, 10]);
+        assert!(map.get_all_mut("в").is_empty());
+
+        assert!(map.remove_value("а", &10));
+        assert_eq!(map.get_all("а"), &[20, 10]);
+        assert!(!map.remove_value("а", &30));
+        assert!(!map.remove_value("в", &10));
+        assert_eq!(map.len(), 3);
+
+        assert_eq!(map.remove("а"), Some(vec![20, 10]));
+        assert_eq!(map.remove("а"), None);
+        assert_eq!(map.len(), 1);
+        assert!(map.remove_value("б", &3));
+        assert!(map.is_empty());
+        assert_eq!(map.keys_len(), 0);
+    }
+
+    #[test]
+    fn test_multi_map_iterators() {
+        let map: MultiMap<u32, u32> = (0..30).map(|i| (i % 3, i)).collect();
+        assert_eq!(map.len(), 30);
+        assert_eq!(map.keys_len(), 3);
+        assert_eq!(map.iter().len(), 30);
+
+        let mut pairs: Vec<(u32, u32)> = map.iter().map(|(key, value)| (*key, *value)).collect();
+        pairs.sort();
+        let mut expected: Vec<(u32, u32)> = (0..30).map(|i| (i % 3, i)).collect();
+        expected.sort();
+        assert_eq!(pairs, expected);
+
+        let mut keys: Vec<u32> = map.keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, vec![0, 1, 2]);
+        for (key, values) in map.iter_all() {
+            assert_eq!(values.len(), 10);
+            assert!(values.windows(2).all(|pair| pair[0] < pair[1]));
+            assert!(values.iter().all(|value| value % 3 == *key));
+        }
+        assert_eq!((&map).into_iter().count(), 30);
+    }
+
+    #[test]
+    fn test_multi_map_retain() {
+        let mut map: MultiMap<u32, u32> = (0..20).map(|i| (i % 4, i)).collect();
+        map.retain(|key, value| *key != 1 && value % 2 == 0);
+        assert_eq!(map.len(), 10);
+        assert_eq!(map.keys_len(), 2);
+        assert!(!map.contains_key(&1));
+        assert!(!map.contains_key(&3));
+        assert_eq!(map.get_all(&2), &[2, 6, 10, 14, 18]);
+
+        let mut clone: MultiMap<u32, u32> = map.clone();
+        clone.extend(vec![(7, 7), (7, 8)]);
+        assert_eq!(clone.len(), 12);
+        assert_eq!(map.len(), 10);
+        clone.clear();
+        assert!(clone.is_empty());
+        assert_eq!(format!("{:?}", MultiMap::<u32, u32>::from_iter(vec![(1, 2), (1, 3)])), "{1: [2, 3]}");
+    }
+}
@@ -0,0 +1,155 @@
+use super::{FxBuildHasher, RobinHoodMap};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::test_utils::next_random;
+
+    #[test]
+    fn test_robin_hood_map() {
+        let mut map: RobinHoodMap<&str, &str> = RobinHoodMap::new();
+        assert_eq!(map.get(&"key1"), None);
+        assert_eq!(map.remove(&"key1"), None);
+        assert_eq!(map.insert("key1", "value1"), None);
+        assert_eq!(map.insert("key2", "value2"), None);
+        assert_eq!(map.insert("key3", "value3"), None);
+        assert_eq!(map.insert("key1", "other"), Some("value1"));
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.get(&"key1"), Some(&"other"));
+        assert_eq!(map.get(&"key3"), Some(&"value3"));
+
+        assert_eq!(map.remove(&"key2"), Some("value2"));
+        assert_eq!(map.get(&"key2"), None);
+        assert!(!map.contains_key(&"key2"));
+        assert!(map.contains_key(&"key1"));
+        assert_eq!(map.get(&"key3"), Some(&"value3"));
+        assert_eq!(map.len(), 2);
+
+        *map.get_mut(&"key3").unwrap() = "changed";
+        assert_eq!(map.get(&"key3"), Some(&"changed"));
+        assert_eq!(map.get_mut(&"key2"), None);
+    }
+
+    #[test]
+    fn test_robin_hood_map_borrowed_keys() {
+        let mut map: RobinHoodMap<String, u32> = RobinHoodMap::new();
+        map.insert("один".to_string(), 1);
+        map.insert("два".to_string(), 2);
+        assert_eq!(map.get("один"), Some(&1));
+        assert!(map.contains_key("два"));
+        *map.get_mut("два").unwrap() += 10;
+        assert_eq!(map.remove("два"), Some(12));
+        assert!(!map.contains_key("два"));
+    }
+
+    #[test]
+    fn test_robin_hood_map_matches_std() {
+        let mut state: u64 = 0xDEAD_BEEF_1234_5678;
+        let mut map: RobinHoodMap<u64, u64> = RobinHoodMap::new();
+        let mut model: std::collections::HashMap<u64, u64> = std::collections::HashMap::new();
+        for step in 0..20_000 {
+            let key: u64 = next_random(&mut state) % 2_000;
+            match next_random(&mut state) % 3 {
+                0 => {
+                    assert_eq!(map.remove(&key), model.remove(&key));
+                }
+                _ => {
+                    assert_eq!(map.insert(key, step), model.insert(key, step));
+                }
+            }
+            assert_eq!(map.get(&key), model.get(&key));
+            assert_eq!(map.len(), model.len());
+        }
+        for key in 0..2_000 {
+            assert_eq!(map.get(&key), model.get(&key));
+        }
+        // Цепочки пробирования остаются короткими даже при высоком коэффициенте загрузки.
+        assert!(map.max_probe_distance() < 32);
+
+        for key in 0..2_000 {
+            map.remove(&key);
+        }
+        assert!(map.is_empty());
+        assert_eq!(map.max_probe_distance(), 0);
+    }
+
+    #[test]
+    fn test_robin_hood_map_entry() {
+        let mut counts: RobinHoodMap<u64, u32> = RobinHoodMap::new();
+        for step in 0..1_000u64 {
+            *counts.entry(step % 37).or_insert(0) += 1;
+        }
+        assert_eq!(counts.len(), 37);
+        assert_eq!(counts[&0], 28);
+        assert_eq!(counts[&36], 27);
+
+        counts.entry(0).and_modify(|count| *count = 0).or_insert(100);
+        assert_eq!(counts.get(&0), Some(&0));
+        counts.entry(1_000).and_modify(|count| *count = 0).or_insert(100);
+        assert_eq!(counts.get(&1_000), Some(&100));
+        assert_eq!(*counts.entry(2_000).or_default(), 0);
+        assert_eq!(counts.len(), 39);
+        for key in 0..37 {
+            assert!(counts.contains_key(&key));
+        }
+    }
+
+    #[test]
+    fn test_robin_hood_map_iterators() {
+        let mut map: RobinHoodMap<u64, u64> = (0..100).map(|i| (i, i * i)).collect();
+        assert_eq!(map.iter().len(), 100);
+        let mut keys: Vec<u64> = map.keys().copied().collect();
+        keys.sort();
+        assert_eq!(keys, (0..100).collect::<Vec<u64>>());
+        assert_eq!(map.values().sum::<u64>(), (0..100).map(|i| i * i).sum::<u64>());
+
+        for value in map.values_mut() {
+            *value += 1;
+        }
+        for (key, value) in &mut map {
+            *value -= key * key;
+        }
+        assert!(map.values().all(|&value| value == 1));
+
+        map.retain(|key, _| key % 2 == 0);
+        assert_eq!(map.len(), 50);
+        assert!((0..100).all(|key| map.contains_key(&key) == (key % 2 == 0)));
+
+        map.extend((100..110).map(|i| (i, 0)));
+        assert_eq!(map.len(), 60);
+        let copy: RobinHoodMap<u64, u64> = map.clone();
+        assert_eq!(copy, map);
+
+        let mut drained: Vec<(u64, u64)> = map.drain().collect();
+        drained.sort();
+        assert_eq!(drained.len(), 60);
+        assert!(map.is_empty());
+        assert_eq!(map.get(&0), None);
+
+        let mut owned: Vec<(u64, u64)> = copy.into_iter().collect();
+        owned.sort();
+        assert_eq!(owned, drained);
+    }
+
+    #[test]
+    fn test_robin_hood_map_capacity_and_hasher() {
+        let mut map: RobinHoodMap<u64, u64, FxBuildHasher> = RobinHoodMap::with_capacity_and_hasher(1_000, FxBuildHasher::default());
+        let capacity: usize = map.capacity();
+        assert!(capacity >= 1_000);
+        for key in 0..1_000 {
+            map.insert(key, key);
+        }
+        assert_eq!(map.capacity(), capacity);
+        assert!((0..1_000).all(|key| map.get(&key) == Some(&key)));
+
+        map.clear();
+        assert!(map.is_empty());
+        assert_eq!(map.capacity(), capacity);
+        assert_eq!(map.get(&1), None);
+        map.insert(1, 2);
+        assert_eq!(map[&1], 2);
+
+        let empty: RobinHoodMap<u64, u64> = RobinHoodMap::with_capacity(0);
+        assert_eq!(empty.capacity(), 0);
+    }
+}
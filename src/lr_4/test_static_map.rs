@@ -0,0 +1,85 @@
+use super::{StaticMap, StaticMapError};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::hash::{BuildHasherDefault, Hasher};
+
+    /// Хэшер, который всем ключам дает один и тот же хэш.
+    #[derive(Default)]
+    struct ConstantHasher;
+
+    impl Hasher for ConstantHasher {
+        fn finish(&self) -> u64 {
+            0
+        }
+
+        fn write(&mut self, _: &[u8]) {}
+    }
+
+    #[test]
+    fn test_static_map() {
+        let map: StaticMap<&str, i32> = StaticMap::build(vec![("один", 1), ("два", 2), ("один", 10)]).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&"один"), Some(&10));
+        assert_eq!(map.get(&"два"), Some(&2));
+        assert_eq!(map.get(&"три"), None);
+        assert!(map.contains_key(&"два"));
+        assert_eq!(map.iter().len(), 2);
+
+        let empty: StaticMap<&str, i32> = StaticMap::build(Vec::new()).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(empty.get(&"один"), None);
+        assert_eq!(empty.index_of(&"один"), None);
+    }
+
+    #[test]
+    fn test_static_map_is_minimal_perfect() {
+        for size in [1, 2, 3, 7, 100, 5_000] {
+            let map: StaticMap<String, usize> = (0..size).map(|i| (format!("key{}", i), i)).collect();
+            assert_eq!(map.len(), size);
+            let mut seen: Vec<bool> = vec![false; size];
+            for i in 0..size {
+                let key: String = format!("key{}", i);
+                let index: usize = map.index_of(key.as_str()).unwrap();
+                assert!(!seen[index]);
+                seen[index] = true;
+                assert_eq!(map.get(key.as_str()), Some(&i));
+            }
+            for i in size..size + 100 {
+                assert_eq!(map.get(format!("key{}", i).as_str()), None);
+            }
+            // Итератор идет по индексам совершенной хэш-функции.
+            for (index, (key, _)) in map.iter().enumerate() {
+                assert_eq!(map.index_of(key.as_str()), Some(index));
+            }
+        }
+    }
+
+    #[test]
+    fn test_static_map_large() {
+        let size: u64 = 200_000;
+        let map: StaticMap<u64, u64> = StaticMap::build((0..size).map(|i| (i * 7, i))).unwrap();
+        assert_eq!(map.len(), size as usize);
+        let mut seen: Vec<bool> = vec![false; size as usize];
+        for i in 0..size {
+            let index: usize = map.index_of(&(i * 7)).unwrap();
+            assert!(!seen[index]);
+            seen[index] = true;
+            assert_eq!(map.get(&(i * 7)), Some(&i));
+            assert_eq!(map.get(&(i * 7 + 1)), None);
+        }
+    }
+
+    #[test]
+    fn test_static_map_gives_up() {
+        let entries: Vec<(u32, u32)> = vec![(1, 1), (2, 2)];
+        let result = StaticMap::build_with_hasher(entries, BuildHasherDefault::<ConstantHasher>::default());
+        assert_eq!(result.err(), Some(StaticMapError::NoPerfectHash));
+        assert_eq!(StaticMapError::NoPerfectHash.to_string(), "no perfect hash function found for the keys");
+
+        let single: StaticMap<u32, u32, BuildHasherDefault<ConstantHasher>> =
+            StaticMap::build_with_hasher(vec![(1, 1)], BuildHasherDefault::default()).unwrap();
+        assert_eq!(single.get(&1), Some(&1));
+    }
+}
@@ -0,0 +1,114 @@
+use super::{ManualClock, TtlCache};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use std::time::Duration;
+
+    fn seconds(count: u64) -> Duration {
+        Duration::from_secs(count)
+    }
+
+    #[test]
+    fn test_ttl_cache_expiry() {
+        let clock: ManualClock = ManualClock::new();
+        let mut cache: TtlCache<&str, u32, ManualClock> = TtlCache::with_clock(4, seconds(10), clock.clone());
+        assert_eq!(cache.ttl(), seconds(10));
+        cache.put("а", 1);
+        cache.put_with_ttl("б", 2, seconds(30));
+        clock.advance(seconds(4));
+        assert_eq!(cache.time_to_live(&"а"), Some(seconds(6)));
+        assert_eq!(cache.get(&"а"), Some(&1));
+        assert!(cache.contains(&"а"));
+
+        clock.advance(seconds(6));
+        assert_eq!(cache.peek(&"а"), None);
+        assert!(!cache.contains(&"а"));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.get(&"а"), None);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.peek(&"б"), Some(&2));
+
+        // Перезапись продлевает срок, а устаревшее значение не возвращается.
+        cache.put("в", 3);
+        clock.advance(seconds(10));
+        assert_eq!(cache.put("в", 30), None);
+        assert_eq!(cache.put("в", 300), Some(30));
+        assert_eq!(cache.time_to_live(&"в"), Some(seconds(10)));
+        *cache.get_mut(&"в").unwrap() += 1;
+        assert_eq!(cache.pop(&"в"), Some(301));
+
+        clock.advance(seconds(10));
+        assert_eq!(cache.pop(&"б"), None);
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_ttl_cache_eviction() {
+        let clock: ManualClock = ManualClock::new();
+        let evicted: Rc<RefCell<Vec<u32>>> = Rc::new(RefCell::new(Vec::new()));
+        let log: Rc<RefCell<Vec<u32>>> = Rc::clone(&evicted);
+        let mut cache: TtlCache<u32, u32, ManualClock> = TtlCache::with_clock(3, seconds(10), clock.clone());
+        cache.set_on_evict(move |key, _| log.borrow_mut().push(key));
+        assert_eq!(cache.capacity(), 3);
+        cache.put_with_ttl(0, 0, seconds(1));
+        cache.put(1, 1);
+        cache.put(2, 2);
+        clock.advance(seconds(2));
+
+        // Первым место освобождает устаревший элемент, без вызова функции.
+        cache.put(3, 3);
+        assert!(evicted.borrow().is_empty());
+        assert_eq!(cache.len(), 3);
+        cache.put(4, 4);
+        assert_eq!(*evicted.borrow(), vec![1]);
+
+        clock.advance(seconds(9));
+        cache.put(5, 5);
+        assert_eq!(cache.len(), 3);
+        clock.advance(seconds(1));
+        assert_eq!(cache.remove_expired(), 2);
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.pop_lru(), Some((5, 5)));
+        cache.put(6, 6);
+        clock.advance(seconds(10));
+        assert_eq!(cache.pop_lru(), None);
+        assert!(cache.is_empty());
+
+        cache.put(7, 7);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_ttl_cache_system_clock() {
+        let mut cache: TtlCache<u32, u32> = TtlCache::new(2, seconds(3600));
+        cache.put(1, 1);
+        assert_eq!(cache.get(&1), Some(&1));
+        assert!(cache.time_to_live(&1).unwrap() <= seconds(3600));
+        let mut short: TtlCache<u32, u32> = TtlCache::new(2, Duration::ZERO);
+        short.put(1, 1);
+        assert_eq!(short.get(&1), None);
+    }
+
+    #[test]
+    fn test_ttl_cache_unbounded_ttl() {
+        let clock: ManualClock = ManualClock::new();
+        let mut cache: TtlCache<u32, u32, ManualClock> = TtlCache::with_clock(2, Duration::MAX, clock.clone());
+        cache.put(1, 1);
+        cache.put_with_ttl(2, 2, seconds(10));
+        assert_eq!(cache.time_to_live(&1), Some(Duration::MAX));
+        clock.advance(seconds(1_000_000));
+        assert_eq!(cache.get(&1), Some(&1));
+        assert_eq!(cache.get(&2), None);
+
+        // Вечное значение вытесняется как обычное, когда нужно место.
+        cache.put(3, 3);
+        cache.put(4, 4);
+        assert!(!cache.contains(&1));
+        assert_eq!(cache.remove_expired(), 0);
+        assert_eq!(cache.put(4, 40), Some(4));
+    }
+}
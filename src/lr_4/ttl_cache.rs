@@ -0,0 +1,295 @@
+use std::borrow::Borrow;
+use std::cell::Cell;
+use std::hash::Hash;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use super::lru_cache::LruCache;
+
+/// Источник текущего времени для `TtlCache`.
+pub trait Clock {
+    fn now(&self) -> Instant;
+}
+
+/// Настоящее время, `Instant::now()`.
+#[derive(Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Часы, которые идут только по вызову `advance`. Копии часов показывают
+/// одно и то же время, так что одну копию можно отдать кэшу, а другой
+/// управлять временем в тестах.
+#[derive(Clone)]
+pub struct ManualClock {
+    start: Instant,
+    elapsed: Rc<Cell<Duration>>, // Общее для всех копий смещение от `start`
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        ManualClock {
+            start: Instant::now(),
+            elapsed: Rc::new(Cell::new(Duration::ZERO)),
+        }
+    }
+
+    /// Переводит часы вперед на `duration`.
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed.set(self.elapsed.get() + duration);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed.get()
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        ManualClock::new()
+    }
+}
+
+/// Значение со сроком годности.
+struct Expiring<V> {
+    value: V,
+    expires_at: Option<Instant>, // `None`, если срок так велик, что не выражается в `Instant`
+}
+
+impl<V> Expiring<V> {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// Структура `TtlCache` — `LruCache`, элементы которого устаревают через
+/// заданное время после записи.
+///
+/// Устаревший элемент не виден ни одному методу и удаляется, когда к нему
+/// обращаются или когда для новой записи нужно место. `remove_expired`
+/// удаляет все устаревшие элементы сразу за O(n). Время берется из часов
+/// `C`, которые в тестах можно подменить на `ManualClock`.
+///
+/// # Примеры
+///
+/// ```
+/// use std::time::Duration;
+/// use your_crate::{ManualClock, TtlCache};
+/// let clock = ManualClock::new();
+/// let mut cache = TtlCache::with_clock(10, Duration::from_secs(60), clock.clone());
+/// cache.put("сессия", 42);
+/// clock.advance(Duration::from_secs(59));
+/// assert_eq!(cache.get(&"сессия"), Some(&42));
+/// clock.advance(Duration::from_secs(1));
+/// assert_eq!(cache.get(&"сессия"), None);
+/// ```
+pub struct TtlCache<K, V, C = SystemClock> {
+    cache: LruCache<K, Expiring<V>>,
+    ttl: Duration, // Срок жизни записи по умолчанию
+    clock: C,
+}
+
+impl<K: Eq + Hash + Clone, V> TtlCache<K, V> {
+    /// Создает кэш на `capacity` элементов, живущих `ttl` после записи.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `capacity` равно нулю.
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self::with_clock(capacity, ttl, SystemClock)
+    }
+}
+
+impl<K: Eq + Hash + Clone, V, C: Clock> TtlCache<K, V, C> {
+    /// Создает кэш, берущий время из `clock`.
+    ///
+    /// # Паника
+    ///
+    /// Паникует, если `capacity` равно нулю.
+    pub fn with_clock(capacity: usize, ttl: Duration, clock: C) -> Self {
+        TtlCache {
+            cache: LruCache::new(capacity),
+            ttl,
+            clock,
+        }
+    }
+
+    /// Задает функцию, которая получает элементы, вытесненные из-за
+    /// нехватки места. Устаревшие и явно удаленные элементы ей не
+    /// передаются.
+    pub fn set_on_evict<F: FnMut(K, V) + 'static>(&mut self, mut on_evict: F) {
+        self.cache.set_on_evict(move |key, entry: Expiring<V>| on_evict(key, entry.value));
+    }
+
+    /// Возвращает количество элементов, включая устаревшие, которые еще не
+    /// удалены.
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    /// Проверяет, пуст ли кэш.
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+
+    /// Возвращает наибольшее количество элементов.
+    pub fn capacity(&self) -> usize {
+        self.cache.capacity()
+    }
+
+    /// Возвращает срок жизни записи по умолчанию.
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// Кладет значение со сроком жизни по умолчанию, см. `put_with_ttl`.
+    pub fn put(&mut self, key: K, value: V) -> Option<V> {
+        self.put_with_ttl(key, value, self.ttl)
+    }
+
+    /// Кладет значение, которое устареет через `ttl`, и делает его самым
+    /// свежим. Если кэш полон, сначала удаляются устаревшие элементы из
+    /// конца очереди вытеснения, а если таких нет — самый старый.
+    ///
+    /// Значение, срок которого не помещается в `Instant` (например,
+    /// `Duration::MAX`), не устаревает никогда.
+    ///
+    /// Возвращает прежнее значение ключа или `None`, если ключа не было
+    /// или его значение устарело.
+    pub fn put_with_ttl(&mut self, key: K, value: V, ttl: Duration) -> Option<V> {
+        let now: Instant = self.clock.now();
+        if self.cache.len() == self.cache.capacity() && !self.cache.contains(&key) {
+            while self.cache.peek_lru().is_some_and(|(_, entry)| entry.is_expired(now)) {
+                self.cache.pop_lru();
+            }
+        }
+        let old: Option<Expiring<V>> = self.cache.put(key, Expiring { value, expires_at: now.checked_add(ttl) });
+        old.filter(|entry| !entry.is_expired(now)).map(|entry| entry.value)
+    }
+
+    /// Возвращает значение ключа и делает его самым свежим. Устаревшее
+    /// значение удаляется.
+    pub fn get<Q>(&mut self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.get_mut(key).map(|value| &*value)
+    }
+
+    /// Возвращает изменяемую ссылку на значение ключа и делает его самым
+    /// свежим. Устаревшее значение удаляется.
+    pub fn get_mut<Q>(&mut self, key: &Q) -> Option<&mut V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        if self.remove_if_expired(key) {
+            return None;
+        }
+        self.cache.get_mut(key).map(|entry| &mut entry.value)
+    }
+
+    /// Возвращает значение ключа, если оно не устарело, не меняя порядок
+    /// вытеснения.
+    pub fn peek<Q>(&self, key: &Q) -> Option<&V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let now: Instant = self.clock.now();
+        self.cache.peek(key).filter(|entry| !entry.is_expired(now)).map(|entry| &entry.value)
+    }
+
+    /// Проверяет, есть ли у ключа неустаревшее значение.
+    pub fn contains<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        self.peek(key).is_some()
+    }
+
+    /// Возвращает, сколько еще проживет значение ключа; для значения, которое
+    /// не устаревает, — `Duration::MAX`.
+    pub fn time_to_live<Q>(&self, key: &Q) -> Option<Duration>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let now: Instant = self.clock.now();
+        let entry: &Expiring<V> = self.cache.peek(key).filter(|entry| !entry.is_expired(now))?;
+        Some(entry.expires_at.map_or(Duration::MAX, |expires_at| expires_at - now))
+    }
+
+    /// Удаляет ключ.
+    ///
+    /// Возвращает удаленное значение или `None`, если ключа не было или его
+    /// значение устарело.
+    pub fn pop<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let now: Instant = self.clock.now();
+        self.cache.pop(key).filter(|entry| !entry.is_expired(now)).map(|entry| entry.value)
+    }
+
+    /// Удаляет самый старый неустаревший элемент; устаревшие элементы,
+    /// встреченные по пути, тоже удаляются.
+    pub fn pop_lru(&mut self) -> Option<(K, V)> {
+        let now: Instant = self.clock.now();
+        while let Some((key, entry)) = self.cache.pop_lru() {
+            if !entry.is_expired(now) {
+                return Some((key, entry.value));
+            }
+        }
+        None
+    }
+
+    /// Удаляет все устаревшие элементы.
+    ///
+    /// Возвращает количество удаленных элементов.
+    pub fn remove_expired(&mut self) -> usize {
+        let now: Instant = self.clock.now();
+        let expired: Vec<K> = self
+            .cache
+            .iter()
+            .filter(|(_, entry)| entry.is_expired(now))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in expired.iter() {
+            self.cache.pop(key);
+        }
+        expired.len()
+    }
+
+    /// Удаляет все элементы.
+    pub fn clear(&mut self) {
+        self.cache.clear();
+    }
+
+    /// Удаляет значение ключа, если оно устарело.
+    ///
+    /// Возвращает `true`, если значение было удалено.
+    fn remove_if_expired<Q>(&mut self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: ?Sized + Hash + Eq,
+    {
+        let now: Instant = self.clock.now();
+        match self.cache.peek(key) {
+            Some(entry) if entry.is_expired(now) => {
+                self.cache.pop(key);
+                true
+            }
+            _ => false,
+        }
+    }
+}
@@ -1,3 +1,4 @@
+mod common;
 mod lr_1;
 mod lr_2;
 mod lr_3;